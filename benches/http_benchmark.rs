@@ -97,16 +97,104 @@ fn bench_memory_allocation(c: &mut Criterion) {
     group.finish();
 }
 
+// 큰 파일 응답 벤치마크 (response_file 피쳐) — loopback 으로 파일 하나를 끝까지 받는다.
+// 4MB 는 mmap 경로, 32MB 는 스트리밍 경로. sendfile 피쳐가 있으면 커널 전송과 버퍼 복사를 비교
+#[cfg(feature = "response_file")]
+fn bench_file_serving(c: &mut Criterion) {
+    use atomic_http::{Connection, Options, ResponseUtil};
+    use criterion::Throughput;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    let dir = std::env::temp_dir().join("atomic_http_bench_files");
+    std::fs::create_dir_all(&dir).unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    #[cfg(feature = "sendfile")]
+    let modes = [("buffered", false), ("sendfile", true)];
+    #[cfg(not(feature = "sendfile"))]
+    let modes = [("buffered", false)];
+
+    let mut group = c.benchmark_group("file_serving");
+    group.sample_size(20);
+
+    for size_mb in [4usize, 32] {
+        let name = format!("file_{}mb.bin", size_mb);
+        std::fs::write(dir.join(&name), vec![7u8; size_mb * 1024 * 1024]).unwrap();
+        group.throughput(Throughput::Bytes((size_mb * 1024 * 1024) as u64));
+
+        for (mode, _use_sendfile) in modes {
+            let mut options = Options::new();
+            options.root_path = dir.clone();
+            #[cfg(feature = "sendfile")]
+            {
+                options.sendfile = _use_sendfile;
+            }
+            let options = Arc::new(options);
+            let request = format!(
+                "GET /{} HTTP/1.1\r\nHost: bench\r\nConnection: close\r\n\r\n",
+                name
+            );
+
+            group.bench_with_input(BenchmarkId::new(mode, size_mb), &size_mb, |b, _| {
+                b.to_async(&runtime).iter(|| async {
+                    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+                    let addr = listener.local_addr().unwrap();
+                    let (client, server) =
+                        tokio::join!(TcpStream::connect(addr), listener.accept());
+                    let (stream, peer) = server.unwrap();
+
+                    let options = options.clone();
+                    let name = name.clone();
+                    let server = tokio::spawn(async move {
+                        let mut conn = Connection::new(stream, options, peer);
+                        let (_request, mut response) = conn.next_request().await.unwrap().unwrap();
+                        response.body_mut().response_file(&name).unwrap();
+                        response.responser().await.unwrap();
+                    });
+
+                    let mut client = client.unwrap();
+                    client.write_all(request.as_bytes()).await.unwrap();
+                    let mut buffer = vec![0u8; 256 * 1024];
+                    let mut received = 0;
+                    loop {
+                        let n = client.read(&mut buffer).await.unwrap();
+                        if n == 0 {
+                            break;
+                        }
+                        received += n;
+                    }
+                    server.await.unwrap();
+                    black_box(received)
+                })
+            });
+        }
+    }
+
+    group.finish();
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[cfg(not(feature = "response_file"))]
+fn bench_file_serving(_c: &mut Criterion) {}
+
 // 벤치마크 그룹 정의
 #[cfg(feature = "arena")]
 criterion_group!(
     benches,
     bench_standard_parsing,
     bench_arena_parsing,
-    bench_memory_allocation
+    bench_memory_allocation,
+    bench_file_serving
 );
 
 #[cfg(not(feature = "arena"))]
-criterion_group!(benches, bench_standard_parsing, bench_memory_allocation);
+criterion_group!(
+    benches,
+    bench_standard_parsing,
+    bench_memory_allocation,
+    bench_file_serving
+);
 
 criterion_main!(benches);
@@ -110,7 +110,7 @@ async fn run_debug_server(port: u16, server_ready: Arc<Notify>) -> Result<(), Se
                                     .headers_mut()
                                     .insert("Connection", "close".parse().unwrap());
 
-                                response.body_mut().body = json_str.clone();
+                                response.body_mut().body = json_str.clone().into();
                                 *response.status_mut() = StatusCode::OK;
 
                                 if let Err(e) = response.responser().await {
@@ -368,7 +368,7 @@ impl IntegratedMultipartTest {
                             "features": ["standard"],
                             "endpoints": ["/upload", "/test/json"]
                         });
-                        response.body_mut().body = info.to_string();
+                        response.body_mut().body = info.to_string().into();
                         *response.status_mut() = StatusCode::OK;
                     }
 
@@ -411,7 +411,7 @@ impl IntegratedMultipartTest {
                                     }
                                 });
 
-                                response.body_mut().body = response_data.to_string();
+                                response.body_mut().body = response_data.to_string().into();
                                 *response.status_mut() = StatusCode::OK;
 
                                 // 파일 저장
@@ -439,7 +439,7 @@ impl IntegratedMultipartTest {
                                         "processing_time_ms": process_time.as_millis(),
                                         "memory_info": "heap_allocated_json"
                                     });
-                                    response.body_mut().body = response_data.to_string();
+                                    response.body_mut().body = response_data.to_string().into();
                                     *response.status_mut() = StatusCode::OK;
                                 }
                                 Err(e) => {
@@ -456,7 +456,7 @@ impl IntegratedMultipartTest {
 
                     _ => {
                         *response.status_mut() = StatusCode::NOT_FOUND;
-                        response.body_mut().body = "Not found".to_string();
+                        response.body_mut().body = "Not found".to_string().into();
                     }
                 }
 
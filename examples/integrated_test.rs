@@ -341,7 +341,7 @@ impl IntegratedTestManager {
                             "features": ["standard"],
                             "endpoints": ["/test/json", "/test/performance"]
                         });
-                        response.body_mut().body = info.to_string();
+                        response.body_mut().body = info.to_string().into();
                         *response.status_mut() = StatusCode::OK;
                     }
 
@@ -354,7 +354,7 @@ impl IntegratedTestManager {
                                 "data_size": data.payload.len(),
                                 "memory_model": "heap_allocated"
                             });
-                            response.body_mut().body = result.to_string();
+                            response.body_mut().body = result.to_string().into();
                             *response.status_mut() = StatusCode::OK;
                         }
                         Err(e) => {
@@ -362,7 +362,7 @@ impl IntegratedTestManager {
                                 "status": "error",
                                 "message": e.to_string()
                             });
-                            response.body_mut().body = error.to_string();
+                            response.body_mut().body = error.to_string().into();
                             *response.status_mut() = StatusCode::BAD_REQUEST;
                         }
                     },
@@ -373,13 +373,13 @@ impl IntegratedTestManager {
                             "memory_efficiency": "normal",
                             "allocation_strategy": "heap_based",
                         });
-                        response.body_mut().body = perf_data.to_string();
+                        response.body_mut().body = perf_data.to_string().into();
                         *response.status_mut() = StatusCode::OK;
                     }
 
                     _ => {
                         *response.status_mut() = StatusCode::NOT_FOUND;
-                        response.body_mut().body = "Not found".to_string();
+                        response.body_mut().body = "Not found".to_string().into();
                     }
                 }
 
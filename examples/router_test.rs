@@ -44,7 +44,8 @@ async fn main() -> Result<(), SendableError> {
                             }
                             Route::GetUser => {
                                 let id = m.params.get("id").unwrap_or("unknown");
-                                response.body_mut().body = format!(r#"{{"user_id":"{}"}}"#, id);
+                                response.body_mut().body =
+                                    format!(r#"{{"user_id":"{}"}}"#, id).into();
                                 *response.status_mut() = StatusCode::OK;
                             }
                             Route::CreateUser => {
@@ -53,7 +54,8 @@ async fn main() -> Result<(), SendableError> {
                             }
                             Route::ServeFile => {
                                 let path = m.params.get("path").unwrap_or("");
-                                response.body_mut().body = format!(r#"{{"file":"{}"}}"#, path);
+                                response.body_mut().body =
+                                    format!(r#"{{"file":"{}"}}"#, path).into();
                                 *response.status_mut() = StatusCode::OK;
                             }
                         },
@@ -45,7 +45,7 @@ async fn main() -> Result<(), SendableError> {
                     println!("WebSocket disconnected: {}", peer);
                 }
                 Ok(StreamResult::Http(_request, mut response)) => {
-                    response.body_mut().body = "Hello from HTTP".to_string();
+                    response.body_mut().body = "Hello from HTTP".to_string().into();
                     *response.status_mut() = StatusCode::OK;
                     let _ = response.responser().await;
                 }
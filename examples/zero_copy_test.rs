@@ -313,7 +313,7 @@ async fn run_standard_server(port: u16) -> Result<(), SendableError> {
                                 "processing_time_ms": start_time.elapsed().as_millis()
                             });
 
-                            response.body_mut().body = welcome_data.to_string();
+                            response.body_mut().body = welcome_data.to_string().into();
                             *response.status_mut() = StatusCode::OK;
                         }
 
@@ -329,7 +329,7 @@ async fn run_standard_server(port: u16) -> Result<(), SendableError> {
                                     "memory_info": "heap_allocated_with_copies",
                                     "request_number": req_num
                                 });
-                                response.body_mut().body = response_data.to_string();
+                                response.body_mut().body = response_data.to_string().into();
                                 *response.status_mut() = StatusCode::OK;
                             }
                             Err(e) => {
@@ -340,7 +340,8 @@ async fn run_standard_server(port: u16) -> Result<(), SendableError> {
 
                         _ => {
                             *response.status_mut() = StatusCode::NOT_FOUND;
-                            response.body_mut().body = "페이지를 찾을 수 없습니다".to_string();
+                            response.body_mut().body =
+                                "페이지를 찾을 수 없습니다".to_string().into();
                         }
                     }
 
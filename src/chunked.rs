@@ -0,0 +1,306 @@
+use std::time::Duration;
+
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use tokio::io::AsyncReadExt;
+
+use crate::helpers::traits::http_stream::{fail, ParseError};
+use crate::socket::Stream;
+use crate::{Options, SendableError};
+
+/// chunk-size 라인 (확장 포함) 한도. 정상 클라이언트는 수십 바이트를 넘지 않는다.
+const MAX_SIZE_LINE: usize = 4 * 1024;
+const BODY_READ_CHUNK: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// `1a;ext=v\r\n`
+    Size,
+    /// chunk 데이터 중 남은 바이트 수
+    Data(usize),
+    /// chunk 데이터 뒤 `\r\n`
+    DataEnd,
+    /// 마지막 chunk 뒤 trailer 필드 (빈 줄에서 끝)
+    Trailer,
+    Done,
+}
+
+/// `Transfer-Encoding: chunked` 본문 디코더 (RFC 9112 §7.1). I/O 없이 입력 조각을 받아
+/// 디코드된 데이터만 `out` 에 붙인다 — 소켓 read 경계와 chunk 경계가 어긋나도 상태를 이어간다.
+///
+/// chunk 확장 (`;name=value`) 은 무시하고, trailer 는 `trailers()` 로 보관한다.
+/// `limit` (`Options::max_body_size`) 은 디코드된 크기에 적용되며, 광고된 chunk 크기만으로
+/// 초과가 확정되면 데이터를 받기 전에 거부한다. trailer 섹션은 `max_trailer_size`
+/// (`Options::max_trailer_size`) 까지.
+#[derive(Debug)]
+pub(crate) struct ChunkedDecoder {
+    state: State,
+    /// 진행 중인 size / trailer 라인 (read 경계에 걸쳐 잘린 경우).
+    line: Vec<u8>,
+    decoded: usize,
+    limit: Option<usize>,
+    trailer_size: usize,
+    max_trailer_size: usize,
+    trailers: HeaderMap,
+}
+
+impl ChunkedDecoder {
+    pub(crate) fn new(limit: Option<usize>, max_trailer_size: usize) -> Self {
+        Self {
+            state: State::Size,
+            line: Vec::new(),
+            decoded: 0,
+            limit,
+            trailer_size: 0,
+            max_trailer_size,
+            trailers: HeaderMap::new(),
+        }
+    }
+
+    /// 종료 chunk 와 trailer 까지 모두 읽었는지.
+    pub(crate) fn is_done(&self) -> bool {
+        self.state == State::Done
+    }
+
+    pub(crate) fn trailers(&self) -> &HeaderMap {
+        &self.trailers
+    }
+
+    pub(crate) fn into_trailers(self) -> HeaderMap {
+        self.trailers
+    }
+
+    /// `input` 을 디코드해 `out` 에 붙이고 소비한 바이트 수를 돌려준다. 끝에 도달하면
+    /// 그 뒤 바이트 (pipelining 된 다음 요청) 는 소비하지 않는다.
+    pub(crate) fn decode(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<usize, ParseError> {
+        let mut pos = 0;
+        while pos < input.len() {
+            match self.state {
+                State::Done => break,
+                State::Data(remaining) => {
+                    let n = remaining.min(input.len() - pos);
+                    out.extend_from_slice(&input[pos..pos + n]);
+                    pos += n;
+                    self.state = match remaining - n {
+                        0 => State::DataEnd,
+                        rest => State::Data(rest),
+                    };
+                }
+                State::Size | State::DataEnd | State::Trailer => {
+                    let rest = &input[pos..];
+                    let (taken, complete) = match rest.iter().position(|&b| b == b'\n') {
+                        Some(i) => (i + 1, true),
+                        None => (rest.len(), false),
+                    };
+                    let len = self.line.len() + taken;
+                    if self.state == State::Trailer {
+                        if self.trailer_size + len > self.max_trailer_size {
+                            return Err(ParseError::TrailersTooLarge(self.max_trailer_size));
+                        }
+                    } else if len > MAX_SIZE_LINE {
+                        return Err(ParseError::Chunked("line too long"));
+                    }
+                    self.line.extend_from_slice(&rest[..taken]);
+                    pos += taken;
+                    if complete {
+                        let line = std::mem::take(&mut self.line);
+                        self.end_line(&line)?;
+                    }
+                }
+            }
+        }
+        Ok(pos)
+    }
+
+    fn end_line(&mut self, line: &[u8]) -> Result<(), ParseError> {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line
+            .strip_suffix(b"\r")
+            .ok_or(ParseError::Chunked("bare LF line ending"))?;
+        match self.state {
+            State::Size => {
+                let size = parse_chunk_size(line)?;
+                if size == 0 {
+                    self.state = State::Trailer;
+                    return Ok(());
+                }
+                self.decoded = self
+                    .decoded
+                    .checked_add(size)
+                    .ok_or(ParseError::Chunked("size overflow"))?;
+                if let Some(cap) = self.limit {
+                    if self.decoded > cap {
+                        return Err(ParseError::BodyTooLarge { limit: cap });
+                    }
+                }
+                self.state = State::Data(size);
+            }
+            State::DataEnd => {
+                if !line.is_empty() {
+                    return Err(ParseError::Chunked("missing CRLF after chunk data"));
+                }
+                self.state = State::Size;
+            }
+            State::Trailer => {
+                if line.is_empty() {
+                    self.state = State::Done;
+                    return Ok(());
+                }
+                self.trailer_size += line.len() + 2;
+                let colon = line
+                    .iter()
+                    .position(|&b| b == b':')
+                    .ok_or(ParseError::Chunked("malformed trailer field"))?;
+                let name = HeaderName::from_bytes(&line[..colon])
+                    .map_err(|_| ParseError::Chunked("invalid trailer name"))?;
+                let value = HeaderValue::from_bytes(trim_ows(&line[colon + 1..]))
+                    .map_err(|_| ParseError::Chunked("invalid trailer value"))?;
+                self.trailers.append(name, value);
+            }
+            State::Data(_) | State::Done => unreachable!("line state only"),
+        }
+        Ok(())
+    }
+}
+
+/// `1A2f;name=value` → 0x1a2f. 확장은 무시.
+fn parse_chunk_size(line: &[u8]) -> Result<usize, ParseError> {
+    let digits = match line.iter().position(|&b| b == b';') {
+        Some(i) => &line[..i],
+        None => line,
+    };
+    // `;` 앞 BWS 허용
+    let digits = trim_ows(digits);
+    if digits.is_empty() {
+        return Err(ParseError::Chunked("missing chunk size"));
+    }
+    let mut size: usize = 0;
+    for &b in digits {
+        let digit = (b as char)
+            .to_digit(16)
+            .ok_or(ParseError::Chunked("invalid chunk size"))?;
+        size = size
+            .checked_mul(16)
+            .and_then(|s| s.checked_add(digit as usize))
+            .ok_or(ParseError::Chunked("chunk size overflow"))?;
+    }
+    Ok(size)
+}
+
+/// 앞뒤 공백/탭 (OWS) 제거.
+pub(crate) fn trim_ows(mut value: &[u8]) -> &[u8] {
+    while let [b' ' | b'\t', rest @ ..] = value {
+        value = rest;
+    }
+    while let [rest @ .., b' ' | b'\t'] = value {
+        value = rest;
+    }
+    value
+}
+
+/// chunked body 를 종료 chunk 까지 읽어 디코드한다. buffered / arena 경로용.
+/// 반환값: (디코드된 body, trailer, 종료 chunk 뒤에 딸려 읽힌 바이트, stream).
+pub(crate) async fn read_chunked_body(
+    initial: Vec<u8>,
+    mut stream: Stream,
+    options: &Options,
+) -> Result<(Vec<u8>, HeaderMap, Vec<u8>, Stream), SendableError> {
+    let mut decoder = ChunkedDecoder::new(options.max_body_size, options.max_trailer_size);
+    let mut body = Vec::new();
+    let used = match decoder.decode(&initial, &mut body) {
+        Ok(used) => used,
+        Err(e) => return Err(fail(stream, options, e).await),
+    };
+    if decoder.is_done() {
+        let surplus = initial[used..].to_vec();
+        return Ok((body, decoder.into_trailers(), surplus, stream));
+    }
+
+    let read_timeout = Duration::from_millis(options.read_timeout_milliseconds);
+    let mut retry_count: u8 = 0;
+    let mut buf = vec![0u8; BODY_READ_CHUNK];
+    while retry_count < options.read_max_retry {
+        match tokio::time::timeout(read_timeout, stream.read(&mut buf)).await {
+            Ok(Ok(0)) => return Err(fail(stream, options, ParseError::BodyIncomplete).await),
+            Ok(Ok(n)) => {
+                let used = match decoder.decode(&buf[..n], &mut body) {
+                    Ok(used) => used,
+                    Err(e) => return Err(fail(stream, options, e).await),
+                };
+                if decoder.is_done() {
+                    let surplus = buf[used..n].to_vec();
+                    return Ok((body, decoder.into_trailers(), surplus, stream));
+                }
+                retry_count = 0;
+            }
+            Ok(Err(e)) => return Err(ParseError::Io(e).into()),
+            Err(_) => retry_count += 1,
+        }
+    }
+    Err(fail(stream, options, ParseError::BodyTimeout).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_all(input: &[u8], limit: Option<usize>) -> Result<(Vec<u8>, usize), ParseError> {
+        let mut decoder = ChunkedDecoder::new(limit, 64 * 1024);
+        let mut out = Vec::new();
+        let used = decoder.decode(input, &mut out)?;
+        assert!(decoder.is_done());
+        Ok((out, used))
+    }
+
+    #[test]
+    fn decodes_extensions_and_trailers_and_stops_at_end() {
+        let input =
+            b"5;name=\"a;b\"\r\nhello\r\n6 ; x\r\n world\r\n0\r\nX-Checksum: abc\r\n\r\nGET /next";
+        let mut decoder = ChunkedDecoder::new(None, 64 * 1024);
+        let mut out = Vec::new();
+        let used = decoder.decode(input, &mut out).unwrap();
+        assert!(decoder.is_done());
+        assert_eq!(out, b"hello world");
+        assert_eq!(&input[used..], b"GET /next");
+        assert_eq!(decoder.trailers()["x-checksum"], "abc");
+    }
+
+    #[test]
+    fn resumes_across_arbitrary_read_boundaries() {
+        let input = b"A\r\n0123456789\r\n1f\r\nabcdefghijklmnopqrstuvwxyzABCDE\r\n0\r\n\r\n";
+        for split in 1..input.len() {
+            let mut decoder = ChunkedDecoder::new(None, 64 * 1024);
+            let mut out = Vec::new();
+            for piece in input.chunks(split) {
+                assert_eq!(decoder.decode(piece, &mut out).unwrap(), piece.len());
+            }
+            assert!(decoder.is_done(), "split={}", split);
+            assert_eq!(out, b"0123456789abcdefghijklmnopqrstuvwxyzABCDE");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_and_oversized_bodies() {
+        assert!(decode_all(b"zz\r\n", None).is_err());
+        assert!(decode_all(b"\r\n", None).is_err());
+        assert!(decode_all(b"3\r\nabcX\r\n0\r\n\r\n", None).is_err());
+        assert!(decode_all(b"ffffffffffffffffff\r\n", None).is_err());
+        // 디코드된 크기 기준 — 두 chunk 합이 cap 을 넘는 순간 거부
+        let err = decode_all(b"4\r\nabcd\r\n4\r\nefgh\r\n0\r\n\r\n", Some(6)).unwrap_err();
+        assert!(err.to_string().contains("max_body_size"));
+        assert_eq!(
+            decode_all(b"4\r\nabcd\r\n2\r\nef\r\n0\r\n\r\n", Some(6))
+                .unwrap()
+                .0,
+            b"abcdef"
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_trailers() {
+        let mut decoder = ChunkedDecoder::new(None, 16);
+        let err = decoder
+            .decode(b"0\r\nDigest: sha-256=0123456789\r\n\r\n", &mut Vec::new())
+            .unwrap_err();
+        assert!(matches!(err, ParseError::TrailersTooLarge(16)), "{}", err);
+    }
+}
@@ -0,0 +1,578 @@
+//! 응답 압축 (`compression` feature). 요청의 `Accept-Encoding` 과 `Options::compression_option`
+//! 으로 gzip / brotli 를 고르고, `responser` / `responser_arena` 가 body 를 압축해 보낸다.
+
+use std::io::{self, Write};
+
+use http::header::{
+    HeaderMap, HeaderValue, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_RANGE, VARY,
+};
+use http::StatusCode;
+
+/// 응답 압축 설정. 기본은 꺼져 있다 (`enabled: false`).
+#[derive(Debug, Clone)]
+pub struct CompressionConfig {
+    pub enabled: bool,
+    /// 이보다 작은 body 는 압축하지 않는다 (바이트). 기본 1KB.
+    pub min_size: usize,
+    /// 압축할 `Content-Type`. `text/*` 처럼 `/*` 로 끝나면 그 타입 전체. 파라미터 (`; charset=..`) 는 무시.
+    pub content_types: Vec<String>,
+    /// gzip 레벨 (0–9). 기본 6.
+    pub gzip_level: u32,
+    /// brotli quality (0–11). 기본 4 — 11 은 요청마다 압축하기엔 너무 느리다.
+    pub brotli_quality: u32,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_size: 1024,
+            content_types: [
+                "text/*",
+                "application/json",
+                "application/javascript",
+                "application/xml",
+                "application/xhtml+xml",
+                "application/manifest+json",
+                "application/wasm",
+                "image/svg+xml",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            gzip_level: 6,
+            brotli_quality: 4,
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// 기본값으로 켠 설정.
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    pub fn content_types<I, S>(mut self, content_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.content_types = content_types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn gzip_level(mut self, level: u32) -> Self {
+        self.gzip_level = level.min(9);
+        self
+    }
+
+    pub fn brotli_quality(mut self, quality: u32) -> Self {
+        self.brotli_quality = quality.min(11);
+        self
+    }
+
+    fn allows(&self, content_type: &str) -> bool {
+        let mime = content_type.split(';').next().unwrap_or("").trim();
+        self.content_types
+            .iter()
+            .any(|allowed| match allowed.strip_suffix("/*") {
+                Some(top) => mime
+                    .split_once('/')
+                    .is_some_and(|(t, _)| t.eq_ignore_ascii_case(top)),
+                None => mime.eq_ignore_ascii_case(allowed),
+            })
+    }
+}
+
+/// 응답 `Content-Encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    Gzip,
+    Brotli,
+}
+
+impl Encoding {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Brotli => "br",
+        }
+    }
+}
+
+/// `Accept-Encoding` 에서 가장 높은 q 의 coding 을 고른다 (RFC 9110 §12.5.3). 같으면 br 우선.
+/// `*` 는 따로 적히지 않은 coding 에 적용되고, `q=0` 은 거부.
+pub(crate) fn negotiate(accept_encoding: &str) -> Option<Encoding> {
+    let (mut br, mut gzip, mut any) = (None, None, None);
+    for item in accept_encoding.split(',') {
+        let mut params = item.split(';');
+        let coding = params.next().unwrap_or("").trim();
+        let q = params
+            .find_map(|p| {
+                let (name, value) = p.split_once('=')?;
+                name.trim().eq_ignore_ascii_case("q").then(|| value.trim())
+            })
+            .map_or(Some(1000), parse_qvalue);
+        let Some(q) = q else {
+            continue;
+        };
+        if coding.eq_ignore_ascii_case("br") {
+            br = Some(q);
+        } else if coding.eq_ignore_ascii_case("gzip") || coding.eq_ignore_ascii_case("x-gzip") {
+            gzip = Some(q);
+        } else if coding == "*" {
+            any = Some(q);
+        }
+    }
+    let br = br.or(any).unwrap_or(0);
+    let gzip = gzip.or(any).unwrap_or(0);
+    match (br, gzip) {
+        (0, 0) => None,
+        (br, gzip) if br >= gzip => Some(Encoding::Brotli),
+        _ => Some(Encoding::Gzip),
+    }
+}
+
+/// `0.8` → 800. 범위 밖이거나 숫자가 아니면 `None`.
+fn parse_qvalue(value: &str) -> Option<u16> {
+    let q = value.parse::<f32>().ok()?;
+    (0.0..=1.0)
+        .contains(&q)
+        .then(|| (q * 1000.0).round() as u16)
+}
+
+/// 이 응답을 압축할지 정한다. 압축 대상 (설정 · 상태 · 타입 · 크기 조건 통과) 이면 `Vary: Accept-Encoding`
+/// 을 붙이고, 클라이언트가 받을 수 있는 coding 이 있으면 `Content-Encoding` 을 붙이고 기존
+/// `Content-Length` 를 지운 뒤 돌려준다. 이미 `Content-Encoding` 이 있거나 `no-transform` 이면 건드리지 않는다.
+pub(crate) fn choose(
+    config: &CompressionConfig,
+    accept_encoding: Option<&HeaderValue>,
+    status: StatusCode,
+    headers: &mut HeaderMap,
+    content_type: Option<&str>,
+    len: usize,
+) -> Option<Encoding> {
+    if !compressible(config, status, headers, content_type, len) {
+        return None;
+    }
+
+    let varies = headers.get_all(VARY).iter().any(|v| {
+        v.to_str().is_ok_and(|v| {
+            v.split(',')
+                .any(|f| f.trim() == "*" || f.trim().eq_ignore_ascii_case("accept-encoding"))
+        })
+    });
+    if !varies {
+        headers.append(VARY, HeaderValue::from_static("Accept-Encoding"));
+    }
+
+    let encoding = negotiate(accept_encoding?.to_str().ok()?)?;
+    headers.insert(
+        CONTENT_ENCODING,
+        HeaderValue::from_static(encoding.as_str()),
+    );
+    headers.remove(CONTENT_LENGTH);
+    Some(encoding)
+}
+
+/// `choose` 가 고를 coding 을 헤더를 바꾸지 않고 미리 본다 (파일 응답의 coding 별 `ETag` 용).
+#[cfg(feature = "response_file")]
+pub(crate) fn select(
+    config: &CompressionConfig,
+    accept_encoding: Option<&HeaderValue>,
+    status: StatusCode,
+    headers: &HeaderMap,
+    content_type: Option<&str>,
+    len: usize,
+) -> Option<Encoding> {
+    if !compressible(config, status, headers, content_type, len) {
+        return None;
+    }
+    negotiate(accept_encoding?.to_str().ok()?)
+}
+
+/// 설정 · 상태 · 타입 · 크기 조건상 압축 대상인지.
+fn compressible(
+    config: &CompressionConfig,
+    status: StatusCode,
+    headers: &HeaderMap,
+    content_type: Option<&str>,
+    len: usize,
+) -> bool {
+    if !config.enabled
+        || len < config.min_size
+        || status.is_informational()
+        || status == StatusCode::NO_CONTENT
+        || status == StatusCode::NOT_MODIFIED
+        // Content-Range 는 압축 전 바이트 범위를 가리킨다
+        || status == StatusCode::PARTIAL_CONTENT
+        || headers.contains_key(CONTENT_ENCODING)
+        || headers.contains_key(CONTENT_RANGE)
+    {
+        return false;
+    }
+    let no_transform = headers.get_all(CACHE_CONTROL).iter().any(|v| {
+        v.to_str()
+            .is_ok_and(|v| v.to_ascii_lowercase().contains("no-transform"))
+    });
+    let content_type = match content_type {
+        Some(content_type) => content_type,
+        None => headers
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or(""),
+    };
+    !no_transform && config.allows(content_type)
+}
+
+/// 점진 압축기. 파일 스트리밍처럼 전체 길이를 모르는 body 는 `write` 로 조각마다 나온 출력을
+/// chunked 로 보내고 `finish` 로 마무리한다.
+pub(crate) enum Encoder {
+    Gzip(flate2::write::GzEncoder<Vec<u8>>),
+    Brotli(Box<brotli::CompressorWriter<Vec<u8>>>),
+}
+
+impl Encoder {
+    pub(crate) fn new(encoding: Encoding, config: &CompressionConfig) -> Self {
+        match encoding {
+            Encoding::Gzip => Self::Gzip(flate2::write::GzEncoder::new(
+                Vec::new(),
+                flate2::Compression::new(config.gzip_level.min(9)),
+            )),
+            // lgwin 22 = brotli 기본 window (4MB)
+            Encoding::Brotli => Self::Brotli(Box::new(brotli::CompressorWriter::new(
+                Vec::new(),
+                64 * 1024,
+                config.brotli_quality.min(11),
+                22,
+            ))),
+        }
+    }
+
+    /// `data` 를 넣고 지금까지 나온 압축 출력을 꺼낸다 (비어 있을 수 있음).
+    pub(crate) fn write(&mut self, data: &[u8]) -> io::Result<Vec<u8>> {
+        Ok(match self {
+            Self::Gzip(encoder) => {
+                encoder.write_all(data)?;
+                std::mem::take(encoder.get_mut())
+            }
+            Self::Brotli(encoder) => {
+                encoder.write_all(data)?;
+                std::mem::take(encoder.get_mut())
+            }
+        })
+    }
+
+    /// 스트림을 닫고 남은 출력을 꺼낸다.
+    pub(crate) fn finish(self) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Gzip(encoder) => encoder.finish(),
+            Self::Brotli(encoder) => Ok(encoder.into_inner()),
+        }
+    }
+}
+
+/// in-memory body 를 통째로 압축한다.
+pub(crate) fn compress(
+    encoding: Encoding,
+    config: &CompressionConfig,
+    data: &[u8],
+) -> io::Result<Vec<u8>> {
+    let mut encoder = Encoder::new(encoding, config);
+    let mut out = encoder.write(data)?;
+    out.extend_from_slice(&encoder.finish()?);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn negotiates_by_qvalue_with_brotli_preferred_on_ties() {
+        assert_eq!(negotiate("gzip, deflate, br"), Some(Encoding::Brotli));
+        assert_eq!(negotiate("gzip;q=1.0, br;q=0.5"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("br;q=0, gzip"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("*;q=0.3, br;q=0"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("identity"), None);
+        assert_eq!(negotiate("gzip;q=0, br;q=0"), None);
+        assert_eq!(negotiate("gzip;q=abc"), None);
+        assert_eq!(negotiate(""), None);
+    }
+
+    #[test]
+    fn choose_checks_config_type_size_and_existing_headers() {
+        let config = CompressionConfig::new().min_size(10);
+        let accept = HeaderValue::from_static("gzip");
+        let choose_with = |headers: &mut HeaderMap, content_type: Option<&str>, len: usize| {
+            choose(
+                &config,
+                Some(&accept),
+                StatusCode::OK,
+                headers,
+                content_type,
+                len,
+            )
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert(CONTENT_LENGTH, HeaderValue::from_static("100"));
+        #[cfg(feature = "response_file")]
+        {
+            let peeked = select(&config, Some(&accept), StatusCode::OK, &headers, None, 100);
+            assert_eq!(peeked, Some(Encoding::Gzip));
+            assert!(!headers.contains_key(CONTENT_ENCODING));
+        }
+        assert_eq!(choose_with(&mut headers, None, 100), Some(Encoding::Gzip));
+        assert_eq!(headers[CONTENT_ENCODING], "gzip");
+        assert_eq!(headers[VARY], "Accept-Encoding");
+        assert!(!headers.contains_key(CONTENT_LENGTH));
+
+        // 너무 작음 / 타입 불일치 / 이미 인코딩됨 / no-transform
+        let mut headers = HeaderMap::new();
+        assert_eq!(choose_with(&mut headers, Some("text/html"), 9), None);
+        assert_eq!(choose_with(&mut headers, Some("image/png"), 100), None);
+        headers.insert(CONTENT_ENCODING, HeaderValue::from_static("br"));
+        assert_eq!(choose_with(&mut headers, Some("text/html"), 100), None);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CACHE_CONTROL,
+            HeaderValue::from_static("public, no-transform"),
+        );
+        assert_eq!(choose_with(&mut headers, Some("text/css"), 100), None);
+        assert!(headers.get(VARY).is_none());
+
+        // handler 가 직접 만든 부분 응답
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_RANGE, HeaderValue::from_static("bytes 0-99/1000"));
+        assert_eq!(choose_with(&mut headers, Some("text/plain"), 100), None);
+        let mut headers = HeaderMap::new();
+        let partial = choose(
+            &config,
+            Some(&accept),
+            StatusCode::PARTIAL_CONTENT,
+            &mut headers,
+            Some("text/plain"),
+            100,
+        );
+        assert_eq!(partial, None);
+        assert!(!headers.contains_key(CONTENT_ENCODING));
+
+        // 압축 대상이지만 클라이언트가 못 받으면 Vary 만
+        let mut headers = HeaderMap::new();
+        let identity = HeaderValue::from_static("identity");
+        let chosen = choose(
+            &config,
+            Some(&identity),
+            StatusCode::OK,
+            &mut headers,
+            Some("text/plain; charset=utf-8"),
+            100,
+        );
+        assert_eq!(chosen, None);
+        assert_eq!(headers[VARY], "Accept-Encoding");
+        assert!(!headers.contains_key(CONTENT_ENCODING));
+
+        // 꺼져 있으면 아무것도 안 함
+        let mut headers = HeaderMap::new();
+        let off = CompressionConfig::default();
+        assert_eq!(
+            choose(
+                &off,
+                Some(&accept),
+                StatusCode::OK,
+                &mut headers,
+                Some("text/plain"),
+                4096
+            ),
+            None
+        );
+        assert!(headers.is_empty());
+    }
+
+    #[test]
+    fn compressed_output_round_trips() {
+        let data = "atomic_http compression ".repeat(200);
+        let config = CompressionConfig::new();
+
+        let gz = compress(Encoding::Gzip, &config, data.as_bytes()).unwrap();
+        assert!(gz.len() < data.len());
+        let mut plain = String::new();
+        flate2::read::GzDecoder::new(&gz[..])
+            .read_to_string(&mut plain)
+            .unwrap();
+        assert_eq!(plain, data);
+
+        // 조각 단위 압축도 같은 결과로 풀린다
+        let mut encoder = Encoder::new(Encoding::Brotli, &config);
+        let mut br = Vec::new();
+        for piece in data.as_bytes().chunks(100) {
+            br.extend_from_slice(&encoder.write(piece).unwrap());
+        }
+        br.extend_from_slice(&encoder.finish().unwrap());
+        let mut plain = String::new();
+        brotli::Decompressor::new(&br[..], 4096)
+            .read_to_string(&mut plain)
+            .unwrap();
+        assert_eq!(plain, data);
+    }
+
+    #[cfg(feature = "arena")]
+    #[tokio::test]
+    async fn responses_are_compressed_per_accept_encoding() {
+        use crate::connection::Connection;
+        use crate::test_support::{read_raw, socket_pair};
+        use crate::{Options, ResponseUtil, ResponseUtilArena};
+        use http::header::CONTENT_TYPE;
+        use std::sync::Arc;
+        use tokio::io::AsyncWriteExt;
+
+        let page = "<p>compress me</p>".repeat(200);
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let mut options = Options::new();
+        options.read_timeout_milliseconds = 500;
+        options.compression_option = CompressionConfig::new();
+        let mut conn = Connection::new(server, Arc::new(options), peer);
+
+        let body = page.clone();
+        let handle = tokio::spawn(async move {
+            let (request, mut response) = conn.next_request().await.unwrap().unwrap();
+            response
+                .headers_mut()
+                .insert(CONTENT_TYPE, "text/html".parse().unwrap());
+            response.body_mut().body = body.clone().into();
+            response.responser().await.unwrap();
+            assert!(conn.recycle(request, response).await.unwrap());
+
+            let (_request, mut response) = conn.next_request_arena().await.unwrap().unwrap();
+            response
+                .headers_mut()
+                .insert(CONTENT_TYPE, "text/html; charset=utf-8".parse().unwrap());
+            response.body_mut().set_arena_response(&body).unwrap();
+            response.responser_arena().await.unwrap();
+            assert!(conn.recycle_arena(response).await.unwrap());
+
+            // 협상할 coding 이 없으면 원본 그대로, Vary 만 붙는다
+            let (request, mut response) = conn.next_request().await.unwrap().unwrap();
+            response
+                .headers_mut()
+                .insert(CONTENT_TYPE, "text/html".parse().unwrap());
+            response.body_mut().body = body.into();
+            response.responser().await.unwrap();
+            conn.recycle(request, response).await.unwrap();
+        });
+
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\nAccept-Encoding: gzip, br;q=0.5\r\n\r\n")
+            .await
+            .unwrap();
+        let (head, compressed) = read_raw(&mut client).await;
+        assert!(head.contains("content-encoding: gzip"), "got: {}", head);
+        assert!(head.contains("vary: accept-encoding"));
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, page);
+
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\nAccept-Encoding: gzip, br\r\n\r\n")
+            .await
+            .unwrap();
+        let (head, compressed) = read_raw(&mut client).await;
+        assert!(head.contains("content-encoding: br"), "got: {}", head);
+        let mut decoded = String::new();
+        brotli::Decompressor::new(compressed.as_slice(), 4096)
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, page);
+
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let (head, plain) = read_raw(&mut client).await;
+        assert!(!head.contains("content-encoding"), "got: {}", head);
+        assert!(head.contains("vary: accept-encoding"));
+        assert_eq!(plain, page.as_bytes());
+        handle.await.unwrap();
+    }
+
+    #[cfg(all(feature = "arena", feature = "response_file"))]
+    #[tokio::test]
+    async fn streamed_files_are_compressed_with_chunked_framing() {
+        use crate::test_support::{file_connection, TempDir};
+        use crate::ResponseUtilArena;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let dir = TempDir::new("compressed_file");
+        let page = "line of text\n".repeat(40_000);
+        dir.write("big.txt", &page);
+
+        let (mut client, mut conn) = file_connection(&dir, |options| {
+            // 제로카피 대신 128KB 스트리밍 경로를 타게 한다
+            options.zero_copy_threshold = 0;
+            options.compression_option = CompressionConfig::new();
+        })
+        .await;
+
+        let handle = tokio::spawn(async move {
+            let (_request, mut response) = conn.next_request_arena().await.unwrap().unwrap();
+            response.body_mut().response_file("big.txt").unwrap();
+            response.responser_arena().await.unwrap();
+        });
+
+        client
+            .write_all(b"GET /big.txt HTTP/1.1\r\nHost: x\r\nAccept-Encoding: gzip\r\n\r\n")
+            .await
+            .unwrap();
+        handle.await.unwrap();
+        drop(client.shutdown().await);
+        let mut raw = Vec::new();
+        client.read_to_end(&mut raw).await.unwrap();
+
+        let end = raw.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let head = String::from_utf8_lossy(&raw[..end]).to_ascii_lowercase();
+        assert!(head.contains("transfer-encoding: chunked"), "got: {}", head);
+        assert!(head.contains("content-encoding: gzip"));
+        assert!(!head.contains("content-length"));
+
+        // chunked 해제 후 gzip 해제
+        let mut rest = &raw[end + 4..];
+        let mut compressed = Vec::new();
+        loop {
+            let line_end = rest.windows(2).position(|w| w == b"\r\n").unwrap();
+            let size =
+                usize::from_str_radix(std::str::from_utf8(&rest[..line_end]).unwrap(), 16).unwrap();
+            rest = &rest[line_end + 2..];
+            if size == 0 {
+                assert_eq!(rest, b"\r\n");
+                break;
+            }
+            compressed.extend_from_slice(&rest[..size]);
+            assert_eq!(&rest[size..size + 2], b"\r\n");
+            rest = &rest[size + 2..];
+        }
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, page);
+    }
+}
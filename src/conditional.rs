@@ -0,0 +1,472 @@
+//! 파일 응답의 조건부 요청 처리 (`response_file` feature). `If-Match` / `If-Unmodified-Since` /
+//! `If-None-Match` / `If-Modified-Since` 를 파일의 `ETag` / `Last-Modified` 와 비교해 304 / 412 를 정한다.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use http::header::{
+    HeaderMap, HeaderValue, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_UNMODIFIED_SINCE,
+};
+use http::Method;
+
+use crate::helpers::traits::zero_copy::FileValidators;
+
+/// 요청의 조건 헤더. 하나라도 있을 때만 만든다.
+#[derive(Debug, Clone)]
+pub(crate) struct Preconditions {
+    /// `GET` / `HEAD` 이면 `If-None-Match` 일치 시 304, 아니면 412.
+    safe: bool,
+    if_match: Option<HeaderValue>,
+    if_unmodified_since: Option<HeaderValue>,
+    if_none_match: Option<HeaderValue>,
+    if_modified_since: Option<HeaderValue>,
+}
+
+/// 조건 평가 결과. 조건을 통과하면 `Preconditions::evaluate` 가 `None` 을 돌려준다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Precondition {
+    /// `304 Not Modified`
+    NotModified,
+    /// `412 Precondition Failed`
+    Failed,
+}
+
+impl Preconditions {
+    pub(crate) fn from_request(method: &Method, headers: &HeaderMap) -> Option<Box<Self>> {
+        let preconditions = Self {
+            safe: method == Method::GET || method == Method::HEAD,
+            if_match: headers.get(IF_MATCH).cloned(),
+            if_unmodified_since: headers.get(IF_UNMODIFIED_SINCE).cloned(),
+            if_none_match: headers.get(IF_NONE_MATCH).cloned(),
+            if_modified_since: headers.get(IF_MODIFIED_SINCE).cloned(),
+        };
+        let any = preconditions.if_match.is_some()
+            || preconditions.if_unmodified_since.is_some()
+            || preconditions.if_none_match.is_some()
+            || preconditions.if_modified_since.is_some();
+        any.then(|| Box::new(preconditions))
+    }
+
+    /// RFC 9110 13.2.2 순서로 평가한다. `If-Match` 가 있으면 `If-Unmodified-Since` 를,
+    /// `If-None-Match` 가 있으면 `If-Modified-Since` 를 보지 않는다. 해석할 수 없는 날짜는 무시한다.
+    pub(crate) fn evaluate(&self, validators: &FileValidators) -> Option<Precondition> {
+        if let Some(if_match) = &self.if_match {
+            if !list_matches(if_match, &validators.etag, false) {
+                return Some(Precondition::Failed);
+            }
+        } else if let Some(date) = self.if_unmodified_since.as_ref().and_then(parse_date) {
+            if seconds(validators.last_modified) > date {
+                return Some(Precondition::Failed);
+            }
+        }
+
+        if let Some(if_none_match) = &self.if_none_match {
+            if list_matches(if_none_match, &validators.etag, true) {
+                return Some(if self.safe {
+                    Precondition::NotModified
+                } else {
+                    Precondition::Failed
+                });
+            }
+        } else if let Some(date) = self.if_modified_since.as_ref().and_then(parse_date) {
+            if self.safe && seconds(validators.last_modified) <= date {
+                return Some(Precondition::NotModified);
+            }
+        }
+        None
+    }
+}
+
+/// `If-Range` 검사. entity-tag 는 strong 비교, 날짜는 `Last-Modified` 와 정확히 같아야 한다.
+pub(crate) fn if_range_matches(if_range: &HeaderValue, validators: &FileValidators) -> bool {
+    let Ok(value) = if_range.to_str() else {
+        return false;
+    };
+    let value = value.trim();
+    if value.starts_with('"') || value.starts_with("W/") {
+        return !value.starts_with("W/") && value == &*validators.etag;
+    }
+    parse_date(if_range) == Some(seconds(validators.last_modified))
+}
+
+/// `"a", W/"b"` / `*` 목록에 `etag` 가 있는지. `weak` 이면 `W/` 를 무시하고 비교한다
+/// (`If-None-Match`), 아니면 `W/` 태그는 맞지 않는다 (`If-Match`).
+fn list_matches(list: &HeaderValue, etag: &str, weak: bool) -> bool {
+    let Ok(mut rest) = list.to_str() else {
+        return false;
+    };
+    if rest.trim() == "*" {
+        return true;
+    }
+    loop {
+        rest = rest.trim_start_matches([',', ' ', '\t']);
+        if rest.is_empty() {
+            return false;
+        }
+        let is_weak = rest.starts_with("W/");
+        if is_weak {
+            rest = &rest[2..];
+        }
+        let Some(tail) = rest.strip_prefix('"') else {
+            return false;
+        };
+        let Some(end) = tail.find('"') else {
+            return false;
+        };
+        let tag = &rest[..end + 2];
+        if (weak || !is_weak) && tag == etag {
+            return true;
+        }
+        rest = &tail[end + 1..];
+    }
+}
+
+fn parse_date(value: &HeaderValue) -> Option<u64> {
+    let date = httpdate::parse_http_date(value.to_str().ok()?.trim()).ok()?;
+    Some(seconds(date))
+}
+
+/// HTTP-date 는 초 단위라 비교 전에 버린다.
+fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn validators() -> FileValidators {
+        FileValidators::new(10, UNIX_EPOCH + Duration::from_millis(1_700_000_000_500))
+    }
+
+    fn evaluate(method: Method, headers: &[(&str, &str)]) -> Option<Precondition> {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.append(
+                http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                value.parse().unwrap(),
+            );
+        }
+        Preconditions::from_request(&method, &map)?.evaluate(&validators())
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        let etag = validators().etag.to_string();
+        let weak = format!("W/{}", etag);
+        assert_eq!(
+            evaluate(Method::GET, &[("if-none-match", &etag)]),
+            Some(Precondition::NotModified)
+        );
+        assert_eq!(
+            evaluate(
+                Method::GET,
+                &[("if-none-match", &format!("\"x\", {}", weak))]
+            ),
+            Some(Precondition::NotModified)
+        );
+        assert_eq!(
+            evaluate(Method::HEAD, &[("if-none-match", "*")]),
+            Some(Precondition::NotModified)
+        );
+        assert_eq!(
+            evaluate(Method::PUT, &[("if-none-match", "*")]),
+            Some(Precondition::Failed)
+        );
+        assert_eq!(
+            evaluate(Method::GET, &[("if-none-match", "\"other\"")]),
+            None
+        );
+    }
+
+    #[test]
+    fn if_match_uses_strong_comparison() {
+        let etag = validators().etag.to_string();
+        assert_eq!(evaluate(Method::GET, &[("if-match", &etag)]), None);
+        assert_eq!(evaluate(Method::GET, &[("if-match", "*")]), None);
+        assert_eq!(
+            evaluate(Method::GET, &[("if-match", &format!("W/{}", etag))]),
+            Some(Precondition::Failed)
+        );
+        assert_eq!(
+            evaluate(Method::GET, &[("if-match", "\"other\"")]),
+            Some(Precondition::Failed)
+        );
+    }
+
+    #[test]
+    fn dates_compare_at_second_precision() {
+        let modified = httpdate::fmt_http_date(validators().last_modified);
+        let earlier = httpdate::fmt_http_date(UNIX_EPOCH + Duration::from_secs(1_600_000_000));
+        assert_eq!(
+            evaluate(Method::GET, &[("if-modified-since", &modified)]),
+            Some(Precondition::NotModified)
+        );
+        assert_eq!(
+            evaluate(Method::GET, &[("if-modified-since", &earlier)]),
+            None
+        );
+        assert_eq!(
+            evaluate(Method::POST, &[("if-modified-since", &modified)]),
+            None
+        );
+        assert_eq!(
+            evaluate(Method::GET, &[("if-modified-since", "garbage")]),
+            None
+        );
+
+        assert_eq!(
+            evaluate(Method::GET, &[("if-unmodified-since", &modified)]),
+            None
+        );
+        assert_eq!(
+            evaluate(Method::GET, &[("if-unmodified-since", &earlier)]),
+            Some(Precondition::Failed)
+        );
+    }
+
+    #[test]
+    fn match_headers_take_precedence_over_dates() {
+        let etag = validators().etag.to_string();
+        let earlier = httpdate::fmt_http_date(UNIX_EPOCH + Duration::from_secs(1_600_000_000));
+        // If-None-Match 가 맞지 않으면 If-Modified-Since 는 보지 않는다
+        assert_eq!(
+            evaluate(
+                Method::GET,
+                &[
+                    ("if-none-match", "\"other\""),
+                    (
+                        "if-modified-since",
+                        &httpdate::fmt_http_date(SystemTime::now())
+                    ),
+                ]
+            ),
+            None
+        );
+        assert_eq!(
+            evaluate(
+                Method::GET,
+                &[("if-match", &etag), ("if-unmodified-since", &earlier)]
+            ),
+            None
+        );
+        assert!(Preconditions::from_request(&Method::GET, &HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn if_range_needs_strong_etag_or_exact_date() {
+        let validators = validators();
+        let header = |v: &str| HeaderValue::from_str(v).unwrap();
+        assert!(if_range_matches(&header(&validators.etag), &validators));
+        assert!(!if_range_matches(
+            &header(&format!("W/{}", validators.etag)),
+            &validators
+        ));
+        assert!(!if_range_matches(&header("\"other\""), &validators));
+        assert!(if_range_matches(
+            &header(&httpdate::fmt_http_date(validators.last_modified)),
+            &validators
+        ));
+        assert!(!if_range_matches(
+            &header(&httpdate::fmt_http_date(UNIX_EPOCH)),
+            &validators
+        ));
+    }
+
+    #[tokio::test]
+    async fn file_responses_answer_conditional_requests() {
+        use crate::test_support::{file_connection, read_raw, TempDir};
+        use crate::ResponseUtil;
+        use tokio::io::AsyncWriteExt;
+
+        let dir = TempDir::new("conditional");
+        dir.write("app.js", "console.log(1);".repeat(20));
+
+        let (mut client, mut conn) = file_connection(&dir, |_| {}).await;
+
+        let handle = tokio::spawn(async move {
+            while let Some((request, mut response)) = conn.next_request().await.unwrap() {
+                response.body_mut().response_file("app.js").unwrap();
+                response.responser().await.unwrap();
+                if !conn.recycle(request, response).await.unwrap() {
+                    break;
+                }
+            }
+        });
+        let get = |extra: &str| format!("GET /app.js HTTP/1.1\r\nHost: x\r\n{}\r\n", extra);
+
+        client.write_all(get("").as_bytes()).await.unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 200"), "got: {}", head);
+        assert_eq!(body.len(), 300);
+        let header = |name: &str| {
+            head.lines()
+                .find_map(|l| l.strip_prefix(name))
+                .map(|v| v.trim().to_string())
+                .unwrap()
+        };
+        let etag = header("etag:");
+        // read_raw 가 헤더를 소문자로 바꾸므로 날짜는 파일에서 다시 만든다
+        let modified = std::fs::metadata(dir.path().join("app.js"))
+            .unwrap()
+            .modified()
+            .unwrap();
+        let last_modified = httpdate::fmt_http_date(modified);
+        assert_eq!(header("last-modified:"), last_modified.to_ascii_lowercase());
+        assert!(etag.starts_with('"'));
+
+        client
+            .write_all(get(&format!("If-None-Match: W/{}\r\n", etag)).as_bytes())
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 304"), "got: {}", head);
+        assert!(head.contains(&format!("etag: {}", etag)));
+        assert!(!head.contains("content-length"));
+        assert!(body.is_empty());
+
+        client
+            .write_all(get(&format!("If-Modified-Since: {}\r\n", last_modified)).as_bytes())
+            .await
+            .unwrap();
+        let (head, _) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 304"), "got: {}", head);
+
+        client
+            .write_all(get("If-Match: \"stale\"\r\n").as_bytes())
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 412"), "got: {}", head);
+        assert!(body.is_empty());
+
+        client
+            .write_all(get(&format!("Range: bytes=0-6\r\nIf-Range: {}\r\n", etag)).as_bytes())
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 206"), "got: {}", head);
+        assert_eq!(body, b"console");
+
+        client
+            .write_all(get("If-None-Match: \"stale\"\r\nConnection: close\r\n").as_bytes())
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 200"), "got: {}", head);
+        assert_eq!(body.len(), 300);
+
+        handle.await.unwrap();
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn compressed_file_responses_get_their_own_etag() {
+        use crate::test_support::{file_connection, read_raw, TempDir};
+        use crate::{CompressionConfig, ResponseUtil};
+        use tokio::io::AsyncWriteExt;
+
+        let dir = TempDir::new("encoded_etag");
+        dir.write("app.js", "console.log(1);".repeat(200));
+
+        let (mut client, mut conn) = file_connection(&dir, |options| {
+            options.compression_option = CompressionConfig::new();
+        })
+        .await;
+
+        let handle = tokio::spawn(async move {
+            while let Some((request, mut response)) = conn.next_request().await.unwrap() {
+                response.body_mut().response_file("app.js").unwrap();
+                response.responser().await.unwrap();
+                if !conn.recycle(request, response).await.unwrap() {
+                    break;
+                }
+            }
+        });
+        let get = |extra: &str| format!("GET /app.js HTTP/1.1\r\nHost: x\r\n{}\r\n", extra);
+        let etag_of = |head: &str| {
+            head.lines()
+                .find_map(|l| l.strip_prefix("etag:"))
+                .map(|v| v.trim().to_string())
+                .unwrap()
+        };
+
+        client.write_all(get("").as_bytes()).await.unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(!head.contains("content-encoding"), "got: {}", head);
+        assert_eq!(body.len(), 3000);
+        let identity = etag_of(&head);
+
+        client
+            .write_all(get("Accept-Encoding: gzip\r\n").as_bytes())
+            .await
+            .unwrap();
+        let (head, _) = read_raw(&mut client).await;
+        assert!(head.contains("content-encoding: gzip"), "got: {}", head);
+        let gzip = etag_of(&head);
+        assert_eq!(gzip, format!("{}-gzip\"", identity.trim_end_matches('"')));
+
+        // 원본의 태그로는 압축본을 재검증할 수 없다
+        client
+            .write_all(
+                get(&format!(
+                    "Accept-Encoding: gzip\r\nIf-None-Match: {}\r\n",
+                    identity
+                ))
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 200"), "got: {}", head);
+        assert!(head.contains("content-encoding: gzip"));
+        assert!(!body.is_empty());
+
+        client
+            .write_all(
+                get(&format!(
+                    "Accept-Encoding: gzip\r\nIf-None-Match: {}\r\n",
+                    gzip
+                ))
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 304"), "got: {}", head);
+        assert!(head.contains(&format!("etag: {}", gzip)));
+        assert!(body.is_empty());
+
+        // 206 은 압축하지 않으므로 압축본의 태그로는 구간을 받을 수 없다
+        client
+            .write_all(
+                get(&format!(
+                    "Accept-Encoding: gzip\r\nRange: bytes=0-6\r\nIf-Range: {}\r\n",
+                    gzip
+                ))
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        let (head, _) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 200"), "got: {}", head);
+        assert!(head.contains("content-encoding: gzip"));
+
+        client
+            .write_all(
+                get(&format!(
+                    "Accept-Encoding: gzip\r\nRange: bytes=0-6\r\nIf-Range: {}\r\nConnection: close\r\n",
+                    identity
+                ))
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 206"), "got: {}", head);
+        assert!(head.contains(&format!("etag: {}", identity)));
+        assert_eq!(body, b"console");
+
+        handle.await.unwrap();
+    }
+}
@@ -0,0 +1,846 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use http::header::CONNECTION;
+use http::{HeaderMap, HeaderValue, Request, Response, Version};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::helpers::traits::http_stream::{
+    fail, get_parse_result_from_request, get_request, parse_request_auto_from_head,
+    read_full_request, read_pipelined_headers, FullRequest, HeaderReadResult,
+};
+#[cfg(feature = "arena")]
+use crate::helpers::traits::http_stream::{
+    get_parse_result_arena_writer, parse_http_request_arena,
+};
+use crate::proxy_protocol::read_preamble;
+use crate::response_body::SinkState;
+use crate::socket::{ListenerId, OwnedReadHalf, OwnedWriteHalf, Peer, Stream};
+#[cfg(feature = "arena")]
+use crate::ArenaWriter;
+use crate::{
+    AutoParseResult, Body, ConnectionGuard, Options, SendableError, Writer, DEFAULT_AUTO_ARENA_CAP,
+};
+
+/// `connection_pool` feature 비활성화 시 keep-alive idle timeout 기본값 (nginx keepalive_timeout과 동일).
+#[cfg(not(feature = "connection_pool"))]
+const DEFAULT_KEEP_ALIVE_IDLE: Duration = Duration::from_secs(75);
+
+/// HTTP/1.1 keep-alive 연결 드라이버. 하나의 소켓에서 `(Request, Response)` 쌍을 연속으로 꺼낸다.
+///
+/// `Accept::parse_request*` 는 소켓 (`Stream`) 을 소비하고 `Writer` 는 write half만 가지므로
+/// 연결당 요청 1개만 처리할 수 있었다. `Connection` 은 응답이 끝난 뒤 `recycle*` 로
+/// 두 half를 다시 합쳐 다음 요청을 같은 소켓에서 읽는다.
+///
+/// ```rust,no_run
+/// # use atomic_http::*;
+/// # async fn run(accept: Accept) -> Result<(), SendableError> {
+/// let mut conn = accept.into_connection();
+/// while let Some((request, mut response)) = conn.next_request().await? {
+///     response.body_mut().body = "ok".into();
+///     *response.status_mut() = http::StatusCode::OK;
+///     response.responser().await?;
+///     if !conn.recycle(request, response).await? {
+///         break;
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// - `Connection: close` 요청, HTTP/1.0 (명시적 `Connection: keep-alive` 없을 때),
+///   keep-alive 비활성 설정, graceful shutdown 진행 중이면 응답에 `Connection: close` 를 넣고 그 요청을 마지막으로 닫는다.
+/// - 두 번째 요청부터는 keep-alive idle timeout (`connection_option.max_idle_time`) 안에
+///   다음 요청의 첫 바이트가 오지 않으면 `None` 을 반환하고 연결을 닫는다.
+/// - HTTP/1.1 pipelining: 클라이언트가 응답 전에 다음 요청을 이어 보내도, 이번 요청
+///   body(Content-Length 또는 chunked 종료 chunk) 뒤에 딸려 읽힌 바이트는 다음 `next_request*` 의 앞부분으로 보관된다.
+///   직전 응답을 `recycle*` 하기 전에는 다음 요청을 꺼낼 수 없으므로 응답은 항상 요청 순서대로 나간다.
+pub struct Connection {
+    /// 다음 요청을 읽을 소켓. 요청을 꺼낸 뒤 `recycle*` 전까지는 `None`.
+    stream: Option<Stream>,
+    /// 이전 요청을 읽다가 미리 받아둔 다음 요청의 앞부분 (pipelining).
+    pending: Vec<u8>,
+    option: Arc<Options>,
+    peer: Peer,
+    pub(crate) listener: ListenerId,
+    /// 현재 처리 중인 요청 이후 연결을 유지할지.
+    keep_alive: bool,
+    /// 연결이 닫혔는지 (close 판정, EOF, idle timeout, 파싱 에러).
+    closed: bool,
+    requests_served: usize,
+    /// `Server::accept` 에서 온 연결이면 shutdown 추적 토큰. 각 응답 `Writer` 에도 clone 을 넘긴다.
+    pub(crate) guard: Option<ConnectionGuard>,
+}
+
+impl Connection {
+    pub fn new(stream: impl Into<Stream>, option: Arc<Options>, peer: impl Into<Peer>) -> Self {
+        Self {
+            stream: Some(stream.into()),
+            pending: Vec::new(),
+            option,
+            peer: peer.into(),
+            listener: ListenerId::PRIMARY,
+            keep_alive: false,
+            closed: false,
+            requests_served: 0,
+            guard: None,
+        }
+    }
+
+    /// TCP 상대 주소. UDS 연결이면 `None`.
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        self.peer.addr()
+    }
+
+    pub fn peer(&self) -> Peer {
+        self.peer
+    }
+
+    /// 연결이 들어온 listener (`Accept::listener`).
+    pub fn listener(&self) -> ListenerId {
+        self.listener
+    }
+
+    pub fn get_request_ip(&self) -> String {
+        match self.peer.addr() {
+            Some(addr) => addr.ip().to_string(),
+            None => self.peer.to_string(),
+        }
+    }
+
+    /// 이 연결에서 지금까지 꺼낸 요청 수.
+    pub fn requests_served(&self) -> usize {
+        self.requests_served
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// buffered 파싱 (`Accept::parse_request` 와 동일). 연결이 끝났으면 `None`.
+    pub async fn next_request(
+        &mut self,
+    ) -> Result<Option<(Request<Body>, Response<Writer>)>, SendableError> {
+        let head = match self.read_head().await? {
+            Some(h) => h,
+            None => return Ok(None),
+        };
+        let result = self.buffered(head).await;
+        let (request, mut response) = self.mark_on_error(result)?;
+        response.body_mut().guard = self.guard.clone();
+        self.begin(request.version(), request.headers(), response.headers_mut());
+        Ok(Some((request, response)))
+    }
+
+    /// arena 파싱 (`Accept::parse_request_arena_writer` 와 동일). 연결이 끝났으면 `None`.
+    #[cfg(feature = "arena")]
+    pub async fn next_request_arena(
+        &mut self,
+    ) -> Result<Option<(Request<crate::ArenaBody>, Response<ArenaWriter>)>, SendableError> {
+        let head = match self.read_head().await? {
+            Some(h) => h,
+            None => return Ok(None),
+        };
+        let result = self.arena(head).await;
+        let (request, mut response) = self.mark_on_error(result)?;
+        response.body_mut().guard = self.guard.clone();
+        self.begin(request.version(), request.headers(), response.headers_mut());
+        Ok(Some((request, response)))
+    }
+
+    /// `Accept::parse_request_auto` 와 동일 (`DEFAULT_AUTO_ARENA_CAP`). 연결이 끝났으면 `None`.
+    pub async fn next_request_auto(&mut self) -> Result<Option<AutoParseResult>, SendableError> {
+        self.next_request_auto_with_cap(DEFAULT_AUTO_ARENA_CAP)
+            .await
+    }
+
+    /// `next_request_auto` 의 명시적 cap 버전.
+    ///
+    /// `Accept::parse_request_auto` 와 달리 Content-Length 없는 요청 (GET 등) 은 body 길이 0 으로
+    /// 확정되므로 arena 경로로 간다 (keep-alive 연결에선 "연결 끝까지" body 가 성립하지 않음).
+    pub async fn next_request_auto_with_cap(
+        &mut self,
+        arena_cap: usize,
+    ) -> Result<Option<AutoParseResult>, SendableError> {
+        let head = match self.read_head().await? {
+            Some(h) => h,
+            None => return Ok(None),
+        };
+        let mut result = self.mark_on_error(
+            parse_request_auto_from_head(head, Arc::clone(&self.option), self.peer, arena_cap)
+                .await,
+        )?;
+        result.set_guard(self.guard.clone());
+        match &mut result {
+            #[cfg(feature = "arena")]
+            AutoParseResult::Arena { request, response } => {
+                self.begin(request.version(), request.headers(), response.headers_mut());
+            }
+            AutoParseResult::Streaming { request, response } => {
+                self.begin(request.version(), request.headers(), response.headers_mut());
+            }
+        }
+        Ok(Some(result))
+    }
+
+    /// 응답 전송 후 소켓을 되돌려 받는다. 다음 요청을 받을 수 있으면 `true`.
+    ///
+    /// `request` 가 streaming body라면 body를 끝까지 읽은 상태여야 재사용된다
+    /// (남은 body가 다음 요청으로 해석되는 것을 막기 위해 덜 읽었으면 연결을 닫음).
+    /// `body_writer` 로 보내던 응답 body 를 끝내지 않았을 때도 닫는다.
+    pub async fn recycle(
+        &mut self,
+        request: Request<Body>,
+        response: Response<Writer>,
+    ) -> Result<bool, SendableError> {
+        let mut body = request.into_body();
+        let body_complete = body.is_complete();
+        if body_complete {
+            self.keep_pending(body.take_surplus());
+        }
+        let read_half = body.take_read_half();
+        let keep_alive = body_complete && !requests_close(response.headers());
+        let mut writer = response.into_body();
+        let keep_alive = keep_alive && writer.sink_state != SinkState::Open;
+        let read_half = read_half.or_else(|| writer.read_half.take());
+        self.reunite(read_half, writer.stream, keep_alive).await
+    }
+
+    /// 핸들러가 `request` 를 소비한 경우의 `recycle`. buffered 요청은 read half 가 `Writer` 에
+    /// 있으므로 재사용되고, streaming body 를 request 와 함께 drop 했다면 연결을 닫는다.
+    pub async fn recycle_response(
+        &mut self,
+        response: Response<Writer>,
+    ) -> Result<bool, SendableError> {
+        let keep_alive = !requests_close(response.headers());
+        let mut writer = response.into_body();
+        let keep_alive = keep_alive && writer.sink_state != SinkState::Open;
+        let read_half = writer.read_half.take();
+        self.reunite(read_half, writer.stream, keep_alive).await
+    }
+
+    /// `recycle` 의 arena 버전. arena 경로는 body를 이미 다 읽었으므로 응답만 받는다.
+    #[cfg(feature = "arena")]
+    pub async fn recycle_arena(
+        &mut self,
+        response: Response<ArenaWriter>,
+    ) -> Result<bool, SendableError> {
+        let keep_alive = !requests_close(response.headers());
+        let mut writer = response.into_body();
+        let keep_alive = keep_alive && writer.sink_state != SinkState::Open;
+        let read_half = writer.read_half.take();
+        self.reunite(read_half, writer.stream, keep_alive).await
+    }
+
+    /// 두 half 를 다시 합쳐 다음 요청을 준비. read half 가 없으면 (streaming body drop 등)
+    /// 재사용할 수 없으므로 write half 를 닫고 연결 종료.
+    async fn reunite(
+        &mut self,
+        read_half: Option<OwnedReadHalf>,
+        mut write_half: OwnedWriteHalf,
+        keep_alive: bool,
+    ) -> Result<bool, SendableError> {
+        let read_half = match read_half {
+            Some(r) => r,
+            None => {
+                self.closed = true;
+                let _ = write_half.shutdown().await;
+                return Ok(false);
+            }
+        };
+        let stream = read_half.reunite(write_half)?;
+        self.finish(stream, self.keep_alive && keep_alive).await
+    }
+
+    /// 다음 요청의 헤더를 읽는다. 이번 요청 body 뒤에 딸려온 바이트는 `pending` 으로 보관.
+    /// 첫 요청이면 PROXY preamble (`Options::proxy_protocol`) 을 먼저 소비해 `peer` 를 갱신한다.
+    async fn read_head(&mut self) -> Result<Option<HeaderReadResult>, SendableError> {
+        let first = self.requests_served == 0;
+        let mut stream = match self.wait_for_request().await? {
+            Some(s) => s,
+            None => return Ok(None),
+        };
+        if first {
+            let preamble = read_preamble(&mut stream, &self.option, &mut self.peer).await;
+            let rest = self.mark_on_error(preamble)?;
+            self.pending.extend_from_slice(&rest);
+        }
+        let prefix = std::mem::take(&mut self.pending);
+        let (head, surplus) =
+            self.mark_on_error(read_pipelined_headers(stream, &self.option, prefix).await)?;
+        self.pending = surplus;
+        Ok(Some(head))
+    }
+
+    /// body (chunked 종료 chunk) 뒤에 딸려 읽힌 바이트를 다음 요청 앞부분으로 보관.
+    fn keep_pending(&mut self, mut surplus: Vec<u8>) {
+        if !surplus.is_empty() {
+            surplus.append(&mut self.pending);
+            self.pending = surplus;
+        }
+    }
+
+    /// buffered 파싱 (`StreamHttp::parse_request` 와 동일 결과).
+    async fn buffered(
+        &mut self,
+        head: HeaderReadResult,
+    ) -> Result<(Request<Body>, Response<Writer>), SendableError> {
+        let FullRequest {
+            bytes,
+            trailers,
+            surplus,
+            stream,
+            ..
+        } = read_full_request(head, &self.option).await?;
+        self.keep_pending(surplus);
+        let mut request = match get_request(bytes, self.option.max_headers).await {
+            Ok(request) => request,
+            Err(e) => return Err(fail(stream, &self.option, e).await),
+        };
+        request.body_mut().trailers = trailers.map(Box::new);
+        get_parse_result_from_request(request, stream, Arc::clone(&self.option), self.peer)
+    }
+
+    #[cfg(feature = "arena")]
+    async fn arena(
+        &mut self,
+        head: HeaderReadResult,
+    ) -> Result<(Request<crate::ArenaBody>, Response<ArenaWriter>), SendableError> {
+        let FullRequest {
+            bytes,
+            header_end,
+            surplus,
+            stream,
+            ..
+        } = read_full_request(head, &self.option).await?;
+        self.keep_pending(surplus);
+        let request = match parse_http_request_arena(
+            crate::ArenaBody::new(&bytes, header_end, header_end),
+            self.option.max_headers,
+        ) {
+            Ok(request) => request,
+            Err(e) => return Err(fail(stream, &self.option, e).await),
+        };
+        get_parse_result_arena_writer(request, stream, Arc::clone(&self.option), self.peer)
+    }
+
+    /// 다음 요청을 읽을 소켓을 꺼낸다. 첫 요청 이후에는 idle timeout 동안 첫 바이트를 기다린다
+    /// (pipelining 으로 다음 요청이 이미 `pending` 에 있으면 기다리지 않음).
+    async fn wait_for_request(&mut self) -> Result<Option<Stream>, SendableError> {
+        if self.closed {
+            return Ok(None);
+        }
+        let mut stream = match self.stream.take() {
+            Some(s) => s,
+            None => {
+                return Err(
+                    "previous response must be recycled before reading the next request".into(),
+                )
+            }
+        };
+
+        if self.requests_served > 0 && self.pending.is_empty() {
+            // shutdown 중 idle 연결은 다음 요청을 기다리지 않고 바로 닫음
+            let guard = self.guard.clone();
+            let shutdown = async move {
+                match guard {
+                    Some(g) => g.triggered().await,
+                    None => std::future::pending().await,
+                }
+            };
+            // 첫 바이트는 pending 으로 넘긴다 (UDS 는 peek 이 없음). read 는 cancel-safe
+            let mut probe = [0u8; 1];
+            let arrived = tokio::select! {
+                biased;
+                _ = shutdown => false,
+                read = tokio::time::timeout(
+                    keep_alive_idle_timeout(&self.option),
+                    stream.read(&mut probe),
+                ) => matches!(read, Ok(Ok(n)) if n > 0),
+            };
+            // EOF, idle timeout, 소켓 에러, shutdown 모두 조용히 연결 종료
+            if !arrived {
+                crate::dev_print!("keep-alive connection closed: {}", self.peer);
+                self.closed = true;
+                let _ = stream.shutdown().await;
+                return Ok(None);
+            }
+            self.pending.push(probe[0]);
+        }
+        self.requests_served += 1;
+        Ok(Some(stream))
+    }
+
+    fn mark_on_error<T>(&mut self, result: Result<T, SendableError>) -> Result<T, SendableError> {
+        if result.is_err() {
+            self.closed = true;
+        }
+        result
+    }
+
+    /// 요청 헤더로 keep-alive 여부를 정하고, 닫을 거면 응답에 `Connection: close` 추가.
+    fn begin(&mut self, version: Version, headers: &HeaderMap, response_headers: &mut HeaderMap) {
+        let shutting_down = self.guard.as_ref().is_some_and(|g| g.is_shutting_down());
+        self.keep_alive = keep_alive_enabled(&self.option)
+            && !shutting_down
+            && wants_keep_alive(version, headers);
+        if !self.keep_alive && !response_headers.contains_key(CONNECTION) {
+            response_headers.insert(CONNECTION, HeaderValue::from_static("close"));
+        }
+    }
+
+    async fn finish(
+        &mut self,
+        mut stream: Stream,
+        keep_alive: bool,
+    ) -> Result<bool, SendableError> {
+        if keep_alive {
+            self.stream = Some(stream);
+            return Ok(true);
+        }
+        self.closed = true;
+        let _ = stream.shutdown().await;
+        Ok(false)
+    }
+}
+
+/// 요청이 연결 유지를 원하는지 (HTTP/1.1 기본 유지, HTTP/1.0 기본 종료).
+fn wants_keep_alive(version: Version, headers: &HeaderMap) -> bool {
+    if has_connection_token(headers, "close") {
+        return false;
+    }
+    match version {
+        Version::HTTP_11 => true,
+        Version::HTTP_10 => has_connection_token(headers, "keep-alive"),
+        _ => false,
+    }
+}
+
+/// 핸들러가 응답에 `Connection: close` 를 직접 넣었는지.
+fn requests_close(headers: &HeaderMap) -> bool {
+    has_connection_token(headers, "close")
+}
+
+fn has_connection_token(headers: &HeaderMap, token: &str) -> bool {
+    headers.get_all(CONNECTION).iter().any(|v| {
+        v.to_str()
+            .map(|s| s.split(',').any(|t| t.trim().eq_ignore_ascii_case(token)))
+            .unwrap_or(false)
+    })
+}
+
+#[cfg(feature = "connection_pool")]
+fn keep_alive_enabled(option: &Options) -> bool {
+    option.connection_option.enable_keep_alive
+}
+
+#[cfg(not(feature = "connection_pool"))]
+fn keep_alive_enabled(_option: &Options) -> bool {
+    true
+}
+
+#[cfg(feature = "connection_pool")]
+fn keep_alive_idle_timeout(option: &Options) -> Duration {
+    option.connection_option.max_idle_time
+}
+
+#[cfg(not(feature = "connection_pool"))]
+fn keep_alive_idle_timeout(_option: &Options) -> Duration {
+    DEFAULT_KEEP_ALIVE_IDLE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{read_response, socket_pair, test_options};
+    use crate::ResponseUtil;
+    use http::StatusCode;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    async fn serve(mut conn: Connection) -> usize {
+        while let Some((request, mut response)) = conn.next_request().await.unwrap() {
+            response.body_mut().body = request.uri().path().to_string().into();
+            *response.status_mut() = StatusCode::OK;
+            response.responser().await.unwrap();
+            if !conn.recycle(request, response).await.unwrap() {
+                break;
+            }
+        }
+        conn.requests_served()
+    }
+
+    #[tokio::test]
+    async fn serves_multiple_requests_on_one_socket() {
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let handle = tokio::spawn(serve(Connection::new(server, test_options(), peer)));
+
+        client
+            .write_all(b"GET /first HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        let first = read_response(&mut client).await;
+        assert!(first.starts_with("HTTP/1.1 200"), "got: {}", first);
+        assert!(first.ends_with("/first"));
+
+        client
+            .write_all(b"GET /second HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let second = read_response(&mut client).await;
+        assert!(second.ends_with("/second"), "got: {}", second);
+        assert!(second.to_ascii_lowercase().contains("connection: close"));
+
+        assert_eq!(handle.await.unwrap(), 2);
+        // 서버가 닫았으므로 EOF
+        let mut rest = Vec::new();
+        assert_eq!(client.read_to_end(&mut rest).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn proxy_preamble_is_read_once_per_connection() {
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let mut options = Options::new();
+        options.proxy_protocol = crate::ProxyProtocol::Required;
+        options.proxy_protocol_trusted = vec!["127.0.0.1".parse().unwrap()];
+        let handle = tokio::spawn(async move {
+            let mut conn = Connection::new(server, Arc::new(options), peer);
+            let mut seen = Vec::new();
+            while let Some((request, mut response)) = conn.next_request().await.unwrap() {
+                seen.push((request.body().ip(), conn.peer_addr()));
+                *response.status_mut() = StatusCode::OK;
+                response.responser().await.unwrap();
+                if !conn.recycle(request, response).await.unwrap() {
+                    break;
+                }
+            }
+            seen
+        });
+
+        client
+            .write_all(
+                b"PROXY TCP6 2001:db8::5 ::1 4321 80\r\n\
+                  GET /a HTTP/1.1\r\nHost: x\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        read_response(&mut client).await;
+        client
+            .write_all(b"GET /b HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        read_response(&mut client).await;
+
+        let original: SocketAddr = "[2001:db8::5]:4321".parse().unwrap();
+        let seen = handle.await.unwrap();
+        assert_eq!(seen, vec![(Some(original), Some(original)); 2]);
+    }
+
+    #[tokio::test]
+    async fn http10_without_keep_alive_closes_after_first_response() {
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let handle = tokio::spawn(serve(Connection::new(server, test_options(), peer)));
+
+        client
+            .write_all(b"GET /only HTTP/1.0\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        let response = read_response(&mut client).await;
+        assert!(response.to_ascii_lowercase().contains("connection: close"));
+        assert_eq!(handle.await.unwrap(), 1);
+    }
+
+    #[cfg(feature = "connection_pool")]
+    #[tokio::test]
+    async fn idle_timeout_ends_connection() {
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let mut options = Options::new();
+        options.read_timeout_milliseconds = 500;
+        options.connection_option.max_idle_time = Duration::from_millis(100);
+        let handle = tokio::spawn(serve(Connection::new(server, Arc::new(options), peer)));
+
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        read_response(&mut client).await;
+        // 두 번째 요청을 보내지 않음 → idle timeout 후 서버가 연결 종료
+        let served = tokio::time::timeout(Duration::from_secs(2), handle)
+            .await
+            .expect("idle timeout should end the driver")
+            .unwrap();
+        assert_eq!(served, 1);
+    }
+
+    #[tokio::test]
+    async fn pipelined_requests_are_answered_in_order() {
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let mut conn = Connection::new(server, test_options(), peer);
+            let mut bodies = Vec::new();
+            while let Some((request, mut response)) = conn.next_request().await.unwrap() {
+                bodies.push(request.body().buffered_bytes().to_vec());
+                response.body_mut().body = request.uri().path().to_string().into();
+                *response.status_mut() = StatusCode::OK;
+                response.responser().await.unwrap();
+                if !conn.recycle(request, response).await.unwrap() {
+                    break;
+                }
+            }
+            bodies
+        });
+
+        // 세 요청을 응답을 기다리지 않고 한 번에 전송
+        client
+            .write_all(
+                b"POST /a HTTP/1.1\r\nHost: x\r\nContent-Length: 5\r\n\r\nhello\
+                  GET /b HTTP/1.1\r\nHost: x\r\n\r\n\
+                  GET /c HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+
+        let mut all = Vec::new();
+        client.read_to_end(&mut all).await.unwrap();
+        let all = String::from_utf8_lossy(&all);
+        let a = all.find("/a").expect("response for /a");
+        let b = all.find("/b").expect("response for /b");
+        let c = all.find("/c").expect("response for /c");
+        assert!(a < b && b < c, "responses out of order: {}", all);
+        assert_eq!(all.matches("HTTP/1.1 200").count(), 3);
+
+        let bodies = handle.await.unwrap();
+        assert_eq!(bodies, vec![b"hello".to_vec(), Vec::new(), Vec::new()]);
+    }
+
+    #[tokio::test]
+    async fn short_body_is_rejected_and_closes_the_connection() {
+        for (tail, status) in [(false, "408"), (true, "400")] {
+            let (mut client, server) = socket_pair().await;
+            let peer = client.local_addr().unwrap();
+            let handle = tokio::spawn(async move {
+                let mut conn = Connection::new(server, test_options(), peer);
+                conn.next_request().await.map(|r| r.is_some())
+            });
+
+            // body 자리에 다음 요청처럼 보이는 바이트 — 짧은 body 로 받아들이면 smuggling
+            client
+                .write_all(b"POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 100\r\n\r\nGET /x")
+                .await
+                .unwrap();
+            if tail {
+                client.shutdown().await.unwrap();
+            }
+            let mut all = Vec::new();
+            client.read_to_end(&mut all).await.unwrap();
+            let all = String::from_utf8_lossy(&all);
+            assert!(
+                all.starts_with(&format!("HTTP/1.1 {}", status)),
+                "got: {}",
+                all
+            );
+            assert!(all.contains("connection: close\r\n"));
+            assert!(handle.await.unwrap().is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn pipelined_streaming_body_does_not_swallow_next_request() {
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let mut conn = Connection::new(server, test_options(), peer);
+            let mut seen = Vec::new();
+            // cap 0 → body 있는 요청은 streaming 경로 (body 없는 GET 은 CL=0 으로 arena)
+            while let Some(result) = conn.next_request_auto_with_cap(0).await.unwrap() {
+                let keep_going = match result {
+                    AutoParseResult::Streaming {
+                        mut request,
+                        mut response,
+                    } => {
+                        let body = request.body_mut().bytes(Some(1024)).await.unwrap();
+                        seen.push((request.uri().path().to_string(), body));
+                        *response.status_mut() = StatusCode::NO_CONTENT;
+                        response.responser().await.unwrap();
+                        conn.recycle(request, response).await.unwrap()
+                    }
+                    #[cfg(feature = "arena")]
+                    AutoParseResult::Arena {
+                        request,
+                        mut response,
+                    } => {
+                        use crate::ResponseUtilArena;
+                        seen.push((request.uri().path().to_string(), Vec::new()));
+                        *response.status_mut() = StatusCode::NO_CONTENT;
+                        response.responser_arena().await.unwrap();
+                        conn.recycle_arena(response).await.unwrap()
+                    }
+                };
+                if !keep_going {
+                    break;
+                }
+            }
+            seen
+        });
+
+        client
+            .write_all(
+                b"PUT /up HTTP/1.1\r\nHost: x\r\nContent-Length: 3\r\n\r\nabc\
+                  GET /next HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut all = Vec::new();
+        client.read_to_end(&mut all).await.unwrap();
+
+        let seen = handle.await.unwrap();
+        assert_eq!(
+            seen,
+            vec![
+                ("/up".to_string(), b"abc".to_vec()),
+                ("/next".to_string(), Vec::new())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn chunked_requests_keep_connection_alive() {
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let mut conn = Connection::new(server, test_options(), peer);
+            let mut bodies = Vec::new();
+            while let Some((request, mut response)) = conn.next_request().await.unwrap() {
+                bodies.push(request.body().buffered_bytes().to_vec());
+                *response.status_mut() = StatusCode::NO_CONTENT;
+                response.responser().await.unwrap();
+                if !conn.recycle(request, response).await.unwrap() {
+                    break;
+                }
+            }
+            bodies
+        });
+
+        // 종료 chunk 뒤에 이어 보낸 요청이 다음 요청으로 읽혀야 한다
+        client
+            .write_all(
+                b"POST /a HTTP/1.1\r\nHost: x\r\nTransfer-Encoding: chunked\r\n\r\n\
+                  3\r\nabc\r\n2\r\nde\r\n0\r\n\r\n\
+                  POST /b HTTP/1.1\r\nHost: x\r\nTransfer-Encoding: chunked\r\n\r\n\
+                  1;x=y\r\nz\r\n0\r\nTrailer-Field: 1\r\n\r\n\
+                  GET /c HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut all = Vec::new();
+        client.read_to_end(&mut all).await.unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&all)
+                .matches("HTTP/1.1 204")
+                .count(),
+            3
+        );
+
+        let bodies = handle.await.unwrap();
+        assert_eq!(bodies, vec![b"abcde".to_vec(), b"z".to_vec(), Vec::new()]);
+    }
+
+    #[tokio::test]
+    async fn streaming_chunked_body_hands_back_next_request() {
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let mut conn = Connection::new(server, test_options(), peer);
+            let mut paths = Vec::new();
+            // chunked 요청은 길이를 모르므로 cap 과 무관하게 streaming 경로
+            while let Some(result) = conn.next_request_auto().await.unwrap() {
+                let keep_going = match result {
+                    AutoParseResult::Streaming {
+                        mut request,
+                        mut response,
+                    } => {
+                        let body = request.body_mut().bytes(Some(1024)).await.unwrap();
+                        paths.push((request.uri().path().to_string(), body));
+                        *response.status_mut() = StatusCode::NO_CONTENT;
+                        response.responser().await.unwrap();
+                        conn.recycle(request, response).await.unwrap()
+                    }
+                    #[cfg(feature = "arena")]
+                    AutoParseResult::Arena {
+                        request,
+                        mut response,
+                    } => {
+                        use crate::ResponseUtilArena;
+                        paths.push((request.uri().path().to_string(), Vec::new()));
+                        *response.status_mut() = StatusCode::NO_CONTENT;
+                        response.responser_arena().await.unwrap();
+                        conn.recycle_arena(response).await.unwrap()
+                    }
+                };
+                if !keep_going {
+                    break;
+                }
+            }
+            paths
+        });
+
+        client
+            .write_all(
+                b"PUT /up HTTP/1.1\r\nHost: x\r\nTransfer-Encoding: chunked\r\n\r\n\
+                  4\r\nbody\r\n0\r\n\r\n\
+                  GET /next HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut all = Vec::new();
+        client.read_to_end(&mut all).await.unwrap();
+
+        let paths = handle.await.unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                ("/up".to_string(), b"body".to_vec()),
+                ("/next".to_string(), Vec::new())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn shutdown_sends_connection_close_on_in_flight_response() {
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let shutdown = crate::ShutdownHandle::new();
+        let mut conn = Connection::new(server, test_options(), peer);
+        conn.guard = Some(shutdown.track());
+        let handle = tokio::spawn(serve(conn));
+
+        client
+            .write_all(b"GET /slow HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        // 요청을 읽은 뒤 응답 전에 shutdown 이 시작돼도 close 로 응답
+        shutdown.trigger();
+        let response = read_response(&mut client).await;
+        assert!(response.to_ascii_lowercase().contains("connection: close"));
+        assert_eq!(handle.await.unwrap(), 1);
+        assert_eq!(shutdown.live_connections(), 0);
+    }
+
+    #[test]
+    fn keep_alive_semantics_follow_version_and_connection_header() {
+        let mut headers = HeaderMap::new();
+        assert!(wants_keep_alive(Version::HTTP_11, &headers));
+        assert!(!wants_keep_alive(Version::HTTP_10, &headers));
+
+        headers.insert(CONNECTION, HeaderValue::from_static("Keep-Alive"));
+        assert!(wants_keep_alive(Version::HTTP_10, &headers));
+
+        headers.insert(CONNECTION, HeaderValue::from_static("upgrade, close"));
+        assert!(!wants_keep_alive(Version::HTTP_11, &headers));
+    }
+}
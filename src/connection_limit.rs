@@ -0,0 +1,446 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, Notify, OwnedSemaphorePermit, Semaphore};
+
+use crate::socket::{AcceptSet, ListenerId, Peer, Stream};
+use crate::Options;
+
+/// 503 응답 전송 제한 시간. 느린 클라이언트가 거절 task를 붙잡지 못하게.
+const REJECT_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+const REJECT_RESPONSE: &[u8] = b"HTTP/1.1 503 Service Unavailable\r\n\
+Content-Length: 0\r\n\
+Connection: close\r\n\
+Retry-After: 1\r\n\r\n";
+
+/// `Options::max_connections` / `max_connections_per_ip` 초과 시 동작.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConnectionLimitPolicy {
+    /// 자리가 날 때까지 대기. 전체 한도는 listener accept 자체를 미루고 (커널 backlog 에서 대기),
+    /// IP별 한도는 해당 소켓만 따로 대기시켜 다른 IP의 accept 는 막지 않는다. IP별로 대기하는 연결은
+    /// 전체 슬롯을 차지하지 않으며, 대기 연결이 `Options::max_parked_connections` 를 넘으면 503 으로 거절한다.
+    #[default]
+    Wait,
+    /// 즉시 `503 Service Unavailable` + `Connection: close` 로 응답하고 닫는다.
+    Reject,
+}
+
+impl std::str::FromStr for ConnectionLimitPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "wait" => Ok(Self::Wait),
+            "reject" | "503" => Ok(Self::Reject),
+            other => Err(format!("unknown connection limit policy: {}", other)),
+        }
+    }
+}
+
+/// 연결 하나가 차지한 한도 슬롯. drop 시 반환 (`ConnectionGuard` 가 보유).
+pub(crate) struct ConnectionPermit {
+    _global: Option<OwnedSemaphorePermit>,
+    per_ip: Option<(IpAddr, Arc<PerIpCounts>)>,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        if let Some((ip, counts)) = self.per_ip.take() {
+            counts.release(ip);
+        }
+    }
+}
+
+struct PerIpCounts {
+    max: usize,
+    map: DashMap<IpAddr, usize>,
+    released: Notify,
+}
+
+impl PerIpCounts {
+    fn try_acquire(&self, ip: IpAddr) -> bool {
+        let mut count = self.map.entry(ip).or_insert(0);
+        if *count >= self.max {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+
+    async fn acquire(&self, ip: IpAddr) {
+        loop {
+            let released = self.released.notified();
+            if self.try_acquire(ip) {
+                return;
+            }
+            released.await;
+        }
+    }
+
+    fn release(&self, ip: IpAddr) {
+        // 0 이 되면 항목 제거 — 지나간 IP 가 map 에 쌓이지 않도록
+        self.map.remove_if_mut(&ip, |_, count| {
+            *count = count.saturating_sub(1);
+            *count == 0
+        });
+        self.released.notify_waiters();
+    }
+}
+
+/// IP별 대기 끝에 슬롯을 얻은 연결.
+type Parked = (Stream, Peer, ListenerId, ConnectionPermit);
+
+/// `Server::accept` 앞단의 동시 연결 제한. 한도가 없으면 listener accept 를 그대로 통과시킨다.
+pub(crate) struct AcceptGate {
+    global: Option<Arc<Semaphore>>,
+    per_ip: Option<Arc<PerIpCounts>>,
+    policy: ConnectionLimitPolicy,
+    /// IP별 대기 중인 연결 수 상한 (`Options::max_parked_connections`, gate 를 공유하는 acceptor 전체).
+    /// 대기 연결마다 task 와 fd 를 들고 있으므로 상한이 없으면 한 IP 가 fd 를 고갈시킬 수 있다.
+    parked: Arc<Semaphore>,
+    parked_tx: mpsc::UnboundedSender<Parked>,
+    parked_rx: mpsc::UnboundedReceiver<Parked>,
+}
+
+enum Admission {
+    Admitted(Stream, ConnectionPermit),
+    /// IP별 한도 대기로 넘김 — 나중에 `parked_rx` 로 돌아온다.
+    Parked,
+    Rejected,
+}
+
+impl AcceptGate {
+    pub(crate) fn new(options: &Options) -> Self {
+        let (parked_tx, parked_rx) = mpsc::unbounded_channel();
+        Self {
+            global: options
+                .max_connections
+                .map(|max| Arc::new(Semaphore::new(max))),
+            per_ip: options.max_connections_per_ip.map(|max| {
+                Arc::new(PerIpCounts {
+                    max,
+                    map: DashMap::new(),
+                    released: Notify::new(),
+                })
+            }),
+            policy: options.connection_limit_policy,
+            parked: Arc::new(Semaphore::new(options.max_parked_connections)),
+            parked_tx,
+            parked_rx,
+        }
+    }
+
+    /// 같은 한도 (전체 / IP별 카운터) 를 공유하는 gate. `SO_REUSEPORT` acceptor 마다 하나씩.
+    /// IP별 대기 후 돌아오는 연결은 자기를 accept 한 gate 로 돌아온다.
+    pub(crate) fn share(&self) -> Self {
+        let (parked_tx, parked_rx) = mpsc::unbounded_channel();
+        Self {
+            global: self.global.clone(),
+            per_ip: self.per_ip.clone(),
+            policy: self.policy,
+            parked: Arc::clone(&self.parked),
+            parked_tx,
+            parked_rx,
+        }
+    }
+
+    fn is_unlimited(&self) -> bool {
+        self.global.is_none() && self.per_ip.is_none()
+    }
+
+    /// 한도 안에 들어온 다음 연결. 한도 초과로 거절한 연결은 여기서 처리하고 건너뛴다.
+    /// cancel-safe: 대기 중 drop 돼도 이미 accept 한 소켓은 잃지 않는다.
+    pub(crate) async fn accept(
+        &mut self,
+        listeners: &AcceptSet<'_>,
+    ) -> std::io::Result<(Stream, Peer, ListenerId, Option<ConnectionPermit>)> {
+        if self.is_unlimited() {
+            let (stream, peer, id) = listeners.accept().await?;
+            return Ok((stream, peer, id, None));
+        }
+        loop {
+            tokio::select! {
+                biased;
+                Some((stream, peer, id, permit)) = self.parked_rx.recv() => {
+                    return Ok((stream, peer, id, Some(permit)));
+                }
+                accepted = Self::accept_within_global(self.global.as_ref(), self.policy, listeners) => {
+                    let (stream, peer, id, global) = accepted?;
+                    if let Admission::Admitted(stream, permit) = self.admit(stream, peer, id, global) {
+                        return Ok((stream, peer, id, Some(permit)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Wait 정책이면 전체 슬롯을 먼저 확보한 뒤 listener accept.
+    async fn accept_within_global(
+        global: Option<&Arc<Semaphore>>,
+        policy: ConnectionLimitPolicy,
+        listeners: &AcceptSet<'_>,
+    ) -> std::io::Result<(Stream, Peer, ListenerId, Option<OwnedSemaphorePermit>)> {
+        let permit = match (global, policy) {
+            (Some(sem), ConnectionLimitPolicy::Wait) => Some(
+                Arc::clone(sem)
+                    .acquire_owned()
+                    .await
+                    .expect("connection limit semaphore is never closed"),
+            ),
+            _ => None,
+        };
+        let (stream, peer, id) = listeners.accept().await?;
+        Ok((stream, peer, id, permit))
+    }
+
+    fn admit(
+        &self,
+        stream: Stream,
+        peer: Peer,
+        id: ListenerId,
+        global: Option<OwnedSemaphorePermit>,
+    ) -> Admission {
+        let global = match (global, &self.global) {
+            (Some(p), _) => Some(p),
+            (None, Some(sem)) => match Arc::clone(sem).try_acquire_owned() {
+                Ok(p) => Some(p),
+                Err(_) => {
+                    crate::dev_print!("connection limit reached, rejecting {}", peer);
+                    reject(stream);
+                    return Admission::Rejected;
+                }
+            },
+            (None, None) => None,
+        };
+
+        // IP별 한도는 TCP 연결에만 적용 (UDS 는 전체 한도만)
+        let (counts, ip) = match (&self.per_ip, peer.addr()) {
+            (Some(c), Some(addr)) => (Arc::clone(c), addr.ip()),
+            _ => {
+                let permit = ConnectionPermit {
+                    _global: global,
+                    per_ip: None,
+                };
+                return Admission::Admitted(stream, permit);
+            }
+        };
+
+        if counts.try_acquire(ip) {
+            let permit = ConnectionPermit {
+                _global: global,
+                per_ip: Some((ip, counts)),
+            };
+            return Admission::Admitted(stream, permit);
+        }
+
+        match self.policy {
+            ConnectionLimitPolicy::Reject => {
+                crate::dev_print!("per-ip connection limit reached, rejecting {}", peer);
+                drop(global);
+                reject(stream);
+                Admission::Rejected
+            }
+            ConnectionLimitPolicy::Wait => {
+                // 대기하는 동안 전체 슬롯은 돌려준다 — 한 IP 의 대기 연결이 전체 한도를 채우지 못하게
+                drop(global);
+                let Ok(slot) = Arc::clone(&self.parked).try_acquire_owned() else {
+                    crate::dev_print!("too many connections waiting, rejecting {}", peer);
+                    reject(stream);
+                    return Admission::Rejected;
+                };
+                let global = self.global.clone();
+                let parked_tx = self.parked_tx.clone();
+                tokio::spawn(async move {
+                    let _slot = slot;
+                    // IP별 슬롯을 먼저, 그다음 전체 슬롯. 도중에 취소되면 permit drop 으로 반환
+                    let admitted = async {
+                        counts.acquire(ip).await;
+                        let mut permit = ConnectionPermit {
+                            _global: None,
+                            per_ip: Some((ip, counts)),
+                        };
+                        if let Some(sem) = global {
+                            permit._global = Some(
+                                sem.acquire_owned()
+                                    .await
+                                    .expect("connection limit semaphore is never closed"),
+                            );
+                        }
+                        permit
+                    };
+                    tokio::select! {
+                        // Server 가 drop (shutdown) 되면 대기 중인 연결도 버림
+                        _ = parked_tx.closed() => {}
+                        permit = admitted => {
+                            let _ = parked_tx.send((stream, peer, id, permit));
+                        }
+                    }
+                });
+                Admission::Parked
+            }
+        }
+    }
+}
+
+/// 503 을 보내고 닫는다. accept 루프를 막지 않도록 별도 task.
+fn reject(mut stream: Stream) {
+    tokio::spawn(async move {
+        let _ = tokio::time::timeout(REJECT_WRITE_TIMEOUT, async {
+            stream.write_all(REJECT_RESPONSE).await?;
+            stream.shutdown().await
+        })
+        .await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ResponseUtil, Server};
+    use std::net::SocketAddr;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpStream;
+
+    const MAX_PARKED: usize = 4;
+
+    async fn limited_server(
+        max: Option<usize>,
+        per_ip: Option<usize>,
+        policy: ConnectionLimitPolicy,
+    ) -> (SocketAddr, tokio::task::JoinHandle<()>) {
+        let mut options = Options::new();
+        options.max_connections = max;
+        options.max_connections_per_ip = per_ip;
+        options.connection_limit_policy = policy;
+        options.max_parked_connections = MAX_PARKED;
+        let mut server = Server::with_options("127.0.0.1:0", options).await.unwrap();
+        let addr = server.listener().local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            while let Ok(accept) = server.accept().await {
+                tokio::spawn(async move {
+                    let (_request, mut response) = accept.parse_request().await.unwrap();
+                    *response.status_mut() = http::StatusCode::OK;
+                    response.responser().await.unwrap();
+                });
+            }
+        });
+        (addr, handle)
+    }
+
+    async fn read_all(stream: &mut TcpStream) -> String {
+        let mut buf = Vec::new();
+        let _ = tokio::time::timeout(Duration::from_secs(2), stream.read_to_end(&mut buf)).await;
+        String::from_utf8_lossy(&buf).to_string()
+    }
+
+    #[tokio::test]
+    async fn reject_policy_replies_503_when_full() {
+        let (addr, server) = limited_server(Some(1), None, ConnectionLimitPolicy::Reject).await;
+
+        // 첫 연결이 요청을 보내지 않고 슬롯을 점유
+        let mut first = TcpStream::connect(addr).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let mut second = TcpStream::connect(addr).await.unwrap();
+        let rejected = read_all(&mut second).await;
+        assert!(rejected.starts_with("HTTP/1.1 503"), "got: {}", rejected);
+
+        first
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        assert!(read_all(&mut first).await.starts_with("HTTP/1.1 200"));
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn per_ip_wait_policy_queues_until_slot_frees() {
+        let (addr, server) = limited_server(None, Some(1), ConnectionLimitPolicy::Wait).await;
+
+        let mut first = TcpStream::connect(addr).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut second = TcpStream::connect(addr).await.unwrap();
+        second
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+
+        // 두 번째는 첫 연결이 끝날 때까지 처리되지 않음
+        let mut buf = [0u8; 64];
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), second.read(&mut buf))
+                .await
+                .is_err()
+        );
+
+        first
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        assert!(read_all(&mut first).await.starts_with("HTTP/1.1 200"));
+        assert!(read_all(&mut second).await.starts_with("HTTP/1.1 200"));
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn parked_connections_do_not_hold_global_slots() {
+        let (addr, server) = limited_server(Some(2), Some(1), ConnectionLimitPolicy::Wait).await;
+
+        // 127.0.0.1 이 IP별 슬롯을 점유하고, 두 번째 연결은 대기
+        let _first = TcpStream::connect(addr).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let _parked = TcpStream::connect(addr).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // 다른 IP 는 남은 전체 슬롯으로 바로 처리된다
+        let socket = tokio::net::TcpSocket::new_v4().unwrap();
+        socket.bind("127.0.0.2:0".parse().unwrap()).unwrap();
+        let mut other = socket.connect(addr).await.unwrap();
+        other
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        let response = read_all(&mut other).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "got: {}", response);
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn parked_connections_are_capped() {
+        let (addr, server) = limited_server(None, Some(1), ConnectionLimitPolicy::Wait).await;
+
+        let _first = TcpStream::connect(addr).await.unwrap();
+        let mut parked = Vec::new();
+        for _ in 0..MAX_PARKED {
+            parked.push(TcpStream::connect(addr).await.unwrap());
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let mut over = TcpStream::connect(addr).await.unwrap();
+        let rejected = read_all(&mut over).await;
+        assert!(rejected.starts_with("HTTP/1.1 503"), "got: {}", rejected);
+        server.abort();
+    }
+
+    #[test]
+    fn per_ip_counts_release_removes_entry() {
+        let counts = PerIpCounts {
+            max: 2,
+            map: DashMap::new(),
+            released: Notify::new(),
+        };
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        assert!(counts.try_acquire(ip));
+        assert!(counts.try_acquire(ip));
+        assert!(!counts.try_acquire(ip));
+        counts.release(ip);
+        assert!(counts.try_acquire(ip));
+        counts.release(ip);
+        counts.release(ip);
+        assert!(counts.map.is_empty());
+    }
+}
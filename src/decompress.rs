@@ -0,0 +1,474 @@
+use brotli_decompressor::{BrotliDecompressStream, BrotliResult, BrotliState, StandardAlloc};
+use flate2::{Crc, Decompress, FlushDecompress, Status};
+
+use crate::helpers::traits::http_stream::{starts_with_ignore_case, ParseError};
+
+/// 디코더가 한 번에 풀어내는 최대 크기. 출력은 이 단위로만 늘어나고 매번 한도를 확인하므로
+/// 압축 폭탄이라도 `limit + OUTPUT_CHUNK` 이상 메모리를 쓰지 않는다.
+const OUTPUT_CHUNK: usize = 16 * 1024;
+/// gzip 헤더 (FEXTRA / FNAME / FCOMMENT 포함) 한도.
+const MAX_GZIP_HEADER: usize = 64 * 1024;
+
+const CONTENT_ENCODING: &[u8] = b"content-encoding:";
+const CONTENT_LENGTH: &[u8] = b"content-length:";
+
+/// 해제할 수 있는 요청 `Content-Encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ContentCoding {
+    /// `gzip` / `x-gzip` (RFC 1952).
+    Gzip,
+    /// `deflate` — zlib (RFC 1950). zlib 헤더가 없으면 raw deflate 로 본다 (흔한 클라이언트 버그).
+    Deflate,
+    /// `br` (RFC 7932).
+    Brotli,
+}
+
+/// 헤더 블록의 `Content-Encoding` 을 읽는다. 없거나 `identity` 뿐이면 `None`.
+/// 모르는 coding 이나 여러 coding 을 겹친 경우는 `UnsupportedEncoding` (`415`).
+pub(crate) fn content_coding(headers: &[u8]) -> Result<Option<ContentCoding>, ParseError> {
+    let mut coding = None;
+    for line in headers.split(|&b| b == b'\n') {
+        if !starts_with_ignore_case(line, CONTENT_ENCODING) {
+            continue;
+        }
+        let value = line[CONTENT_ENCODING.len()..]
+            .strip_suffix(b"\r")
+            .unwrap_or(&line[CONTENT_ENCODING.len()..]);
+        for token in value.split(|&b| b == b',') {
+            let token = crate::chunked::trim_ows(token);
+            if token.is_empty() || token.eq_ignore_ascii_case(b"identity") {
+                continue;
+            }
+            let unsupported =
+                || ParseError::UnsupportedEncoding(String::from_utf8_lossy(token).into_owned());
+            let next =
+                if token.eq_ignore_ascii_case(b"gzip") || token.eq_ignore_ascii_case(b"x-gzip") {
+                    ContentCoding::Gzip
+                } else if token.eq_ignore_ascii_case(b"deflate") {
+                    ContentCoding::Deflate
+                } else if token.eq_ignore_ascii_case(b"br") {
+                    ContentCoding::Brotli
+                } else {
+                    return Err(unsupported());
+                };
+            // 겹친 coding (`gzip, br`) 은 지원하지 않는다
+            if coding.replace(next).is_some() {
+                return Err(unsupported());
+            }
+        }
+    }
+    Ok(coding)
+}
+
+/// 해제한 요청의 헤더 블록에서 `Content-Encoding` / `Content-Length` 줄을 뺀 사본 —
+/// 핸들러가 보는 헤더가 풀린 body 와 맞도록.
+pub(crate) fn strip_coding_headers(headers: &[u8]) -> Vec<u8> {
+    let mut kept = Vec::with_capacity(headers.len());
+    for line in headers.split_inclusive(|&b| b == b'\n') {
+        if !starts_with_ignore_case(line, CONTENT_ENCODING)
+            && !starts_with_ignore_case(line, CONTENT_LENGTH)
+        {
+            kept.extend_from_slice(line);
+        }
+    }
+    kept
+}
+
+/// buffered / arena 경로용: 헤더 + 압축 body 버퍼를 헤더 (coding 줄 제거) + 해제한 body 로 바꾼다.
+/// 새 헤더 길이 (= body 시작 오프셋) 를 돌려준다.
+pub(crate) fn decode_request(
+    bytes: &mut Vec<u8>,
+    header_end: usize,
+    coding: ContentCoding,
+    limit: Option<usize>,
+) -> Result<usize, ParseError> {
+    let body = bytes.split_off(header_end);
+    let mut decoder = ContentDecoder::new(coding, limit);
+    let mut decoded = Vec::new();
+    decoder.decode(&body, &mut decoded)?;
+    decoder.finish()?;
+    *bytes = strip_coding_headers(bytes);
+    let header_end = bytes.len();
+    bytes.extend_from_slice(&decoded);
+    Ok(header_end)
+}
+
+enum Stage {
+    /// gzip 헤더를 모으는 중.
+    GzipHeader(Vec<u8>),
+    GzipBody(Box<Decompress>, Crc),
+    /// CRC32 + ISIZE (8 바이트) 를 모으는 중.
+    GzipTrailer(Vec<u8>, Crc),
+    /// zlib / raw deflate 판별에 필요한 첫 2 바이트를 모으는 중.
+    DeflateSniff(Vec<u8>),
+    Deflate(Box<Decompress>),
+    Brotli(Box<BrotliState<StandardAlloc, StandardAlloc, StandardAlloc>>),
+    Done,
+}
+
+/// 요청 body `Content-Encoding` 디코더. `ChunkedDecoder` 처럼 I/O 없이 입력 조각을 받아
+/// 해제된 데이터만 `out` 에 붙인다. `limit` (`Options::max_body_size`) 은 해제된 크기에 적용.
+pub(crate) struct ContentDecoder {
+    stage: Stage,
+    produced: usize,
+    limit: Option<usize>,
+}
+
+impl std::fmt::Debug for ContentDecoder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContentDecoder")
+            .field("produced", &self.produced)
+            .field("limit", &self.limit)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ContentDecoder {
+    pub(crate) fn new(coding: ContentCoding, limit: Option<usize>) -> Self {
+        let stage = match coding {
+            ContentCoding::Gzip => Stage::GzipHeader(Vec::new()),
+            ContentCoding::Deflate => Stage::DeflateSniff(Vec::new()),
+            ContentCoding::Brotli => Stage::Brotli(Box::new(BrotliState::new_strict(
+                StandardAlloc::default(),
+                StandardAlloc::default(),
+                StandardAlloc::default(),
+            ))),
+        };
+        Self {
+            stage,
+            produced: 0,
+            limit,
+        }
+    }
+
+    /// `input` 을 해제해 `out` 에 붙인다. 압축 스트림이 끝난 뒤의 바이트는 에러.
+    pub(crate) fn decode(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<(), ParseError> {
+        let mut buf = [0u8; OUTPUT_CHUNK];
+        match &mut self.stage {
+            Stage::GzipHeader(header) => {
+                header.extend_from_slice(input);
+                let Some(len) = gzip_header_len(header)? else {
+                    if header.len() > MAX_GZIP_HEADER {
+                        return Err(ParseError::Decompression("gzip header too large"));
+                    }
+                    return Ok(());
+                };
+                let rest = header.split_off(len);
+                self.stage = Stage::GzipBody(Box::new(Decompress::new(false)), Crc::new());
+                self.decode(&rest, out)
+            }
+            Stage::GzipBody(inflate, crc) => {
+                let start = out.len();
+                let (used, end) = inflate_into(
+                    inflate,
+                    input,
+                    out,
+                    &mut buf,
+                    &mut self.produced,
+                    self.limit,
+                )?;
+                crc.update(&out[start..]);
+                if end {
+                    let crc = std::mem::replace(crc, Crc::new());
+                    self.stage = Stage::GzipTrailer(Vec::with_capacity(8), crc);
+                    return self.decode(&input[used..], out);
+                }
+                Ok(())
+            }
+            Stage::GzipTrailer(trailer, crc) => {
+                let take = (8 - trailer.len()).min(input.len());
+                trailer.extend_from_slice(&input[..take]);
+                if trailer.len() < 8 {
+                    return Ok(());
+                }
+                let sum = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+                let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+                if sum != crc.sum() || size != crc.amount() {
+                    return Err(ParseError::Decompression("gzip checksum mismatch"));
+                }
+                self.stage = Stage::Done;
+                self.decode(&input[take..], out)
+            }
+            Stage::DeflateSniff(head) => {
+                head.extend_from_slice(input);
+                if head.len() < 2 {
+                    return Ok(());
+                }
+                // RFC 1950: CM = 8, FCHECK 로 (CMF * 256 + FLG) 가 31 의 배수
+                let zlib = head[0] & 0x0f == 8 && u16::from_be_bytes([head[0], head[1]]) % 31 == 0;
+                let head = std::mem::take(head);
+                self.stage = Stage::Deflate(Box::new(Decompress::new(zlib)));
+                self.decode(&head, out)
+            }
+            Stage::Deflate(inflate) => {
+                let (used, end) = inflate_into(
+                    inflate,
+                    input,
+                    out,
+                    &mut buf,
+                    &mut self.produced,
+                    self.limit,
+                )?;
+                if end {
+                    self.stage = Stage::Done;
+                    return self.decode(&input[used..], out);
+                }
+                Ok(())
+            }
+            Stage::Brotli(state) => {
+                let mut available_in = input.len();
+                let mut input_offset = 0;
+                loop {
+                    let mut available_out = buf.len();
+                    let mut output_offset = 0;
+                    let mut total_out = 0;
+                    let result = BrotliDecompressStream(
+                        &mut available_in,
+                        &mut input_offset,
+                        input,
+                        &mut available_out,
+                        &mut output_offset,
+                        &mut buf,
+                        &mut total_out,
+                        &mut **state,
+                    );
+                    emit(&buf[..output_offset], out, &mut self.produced, self.limit)?;
+                    match result {
+                        BrotliResult::NeedsMoreOutput => continue,
+                        BrotliResult::NeedsMoreInput => return Ok(()),
+                        BrotliResult::ResultSuccess => break,
+                        BrotliResult::ResultFailure => {
+                            return Err(ParseError::Decompression("invalid brotli data"))
+                        }
+                    }
+                }
+                self.stage = Stage::Done;
+                self.decode(&input[input_offset..], out)
+            }
+            Stage::Done if input.is_empty() => Ok(()),
+            Stage::Done => Err(ParseError::Decompression(
+                "trailing data after compressed body",
+            )),
+        }
+    }
+
+    /// body 끝 (EOF / 종료 chunk) 에서 호출. 압축 스트림이 덜 끝났으면 에러.
+    pub(crate) fn finish(&self) -> Result<(), ParseError> {
+        match self.stage {
+            Stage::Done => Ok(()),
+            _ => Err(ParseError::Decompression("truncated compressed body")),
+        }
+    }
+}
+
+/// `buf` 크기씩 풀어 `out` 에 붙인다. 반환값: (소비한 입력 바이트, 스트림 끝 도달).
+fn inflate_into(
+    inflate: &mut Decompress,
+    input: &[u8],
+    out: &mut Vec<u8>,
+    buf: &mut [u8],
+    produced: &mut usize,
+    limit: Option<usize>,
+) -> Result<(usize, bool), ParseError> {
+    let mut consumed = 0;
+    loop {
+        let (in_before, out_before) = (inflate.total_in(), inflate.total_out());
+        let status = inflate
+            .decompress(&input[consumed..], buf, FlushDecompress::None)
+            .map_err(|_| ParseError::Decompression("invalid deflate data"))?;
+        let used = (inflate.total_in() - in_before) as usize;
+        let written = (inflate.total_out() - out_before) as usize;
+        consumed += used;
+        emit(&buf[..written], out, produced, limit)?;
+        if status == Status::StreamEnd {
+            return Ok((consumed, true));
+        }
+        // 입력을 다 넣었고 출력 버퍼가 남았으면 더 나올 것이 없음. 진전이 없어도 멈춘다.
+        if (consumed == input.len() && written < buf.len()) || (used == 0 && written == 0) {
+            return Ok((consumed, false));
+        }
+    }
+}
+
+fn emit(
+    data: &[u8],
+    out: &mut Vec<u8>,
+    produced: &mut usize,
+    limit: Option<usize>,
+) -> Result<(), ParseError> {
+    *produced += data.len();
+    if let Some(cap) = limit {
+        if *produced > cap {
+            return Err(ParseError::BodyTooLarge { limit: cap });
+        }
+    }
+    out.extend_from_slice(data);
+    Ok(())
+}
+
+/// gzip 멤버 헤더 길이 (RFC 1952 §2.3). 아직 다 안 들어왔으면 `None`.
+fn gzip_header_len(header: &[u8]) -> Result<Option<usize>, ParseError> {
+    const FHCRC: u8 = 0x02;
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    const FCOMMENT: u8 = 0x10;
+
+    if header.len() < 10 {
+        return Ok(None);
+    }
+    let flags = header[3];
+    if header[..3] != [0x1f, 0x8b, 8] || flags & 0xe0 != 0 {
+        return Err(ParseError::Decompression("invalid gzip header"));
+    }
+    let mut pos = 10;
+    if flags & FEXTRA != 0 {
+        let Some(xlen) = header.get(pos..pos + 2) else {
+            return Ok(None);
+        };
+        pos += 2 + u16::from_le_bytes([xlen[0], xlen[1]]) as usize;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            match header
+                .get(pos..)
+                .and_then(|rest| rest.iter().position(|&b| b == 0))
+            {
+                Some(nul) => pos += nul + 1,
+                None => return Ok(None),
+            }
+        }
+    }
+    if flags & FHCRC != 0 {
+        pos += 2;
+    }
+    Ok((header.len() >= pos).then_some(pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn decode_pieces(
+        coding: ContentCoding,
+        input: &[u8],
+        split: usize,
+        limit: Option<usize>,
+    ) -> Result<Vec<u8>, ParseError> {
+        let mut decoder = ContentDecoder::new(coding, limit);
+        let mut out = Vec::new();
+        for piece in input.chunks(split) {
+            decoder.decode(piece, &mut out)?;
+        }
+        decoder.finish()?;
+        Ok(out)
+    }
+
+    #[test]
+    fn parses_content_encoding_header() {
+        let coding = |raw: &str| content_coding(raw.as_bytes());
+        assert_eq!(coding("POST / HTTP/1.1\r\nHost: a\r\n\r\n").unwrap(), None);
+        assert_eq!(
+            coding("POST / HTTP/1.1\r\nContent-Encoding: identity, GZIP\r\n\r\n").unwrap(),
+            Some(ContentCoding::Gzip)
+        );
+        assert_eq!(
+            coding("POST / HTTP/1.1\r\ncontent-encoding: br\r\n\r\n").unwrap(),
+            Some(ContentCoding::Brotli)
+        );
+        let err = coding("POST / HTTP/1.1\r\nContent-Encoding: zstd\r\n\r\n").unwrap_err();
+        assert!(
+            matches!(&err, ParseError::UnsupportedEncoding(c) if c == "zstd"),
+            "{}",
+            err
+        );
+        // 겹친 coding 은 지원하지 않음
+        assert!(coding(
+            "POST / HTTP/1.1\r\nContent-Encoding: gzip\r\nContent-Encoding: br\r\n\r\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn decodes_gzip_deflate_and_brotli_across_read_boundaries() {
+        let data =
+            br#"{"items":[1,2,3,4,5,6,7,8,9,10],"name":"atomic_http atomic_http atomic_http"}"#;
+        let gz = gzip(data);
+        let mut zlib = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        zlib.write_all(data).unwrap();
+        let zlib = zlib.finish().unwrap();
+        let mut raw =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        raw.write_all(data).unwrap();
+        let raw = raw.finish().unwrap();
+        let mut br = Vec::new();
+        brotli::BrotliCompress(&mut &data[..], &mut br, &Default::default()).unwrap();
+
+        for split in [1, 3, 7, 1024] {
+            assert_eq!(
+                decode_pieces(ContentCoding::Gzip, &gz, split, None).unwrap(),
+                data
+            );
+            assert_eq!(
+                decode_pieces(ContentCoding::Deflate, &zlib, split, None).unwrap(),
+                data
+            );
+            assert_eq!(
+                decode_pieces(ContentCoding::Deflate, &raw, split, None).unwrap(),
+                data
+            );
+            assert_eq!(
+                decode_pieces(ContentCoding::Brotli, &br, split, None).unwrap(),
+                data
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_bombs_truncation_and_corruption() {
+        // 10MB 의 0 → 약 10KB. 해제 크기 한도에 걸려야 한다
+        let bomb = gzip(&vec![0u8; 10 * 1024 * 1024]);
+        assert!(bomb.len() < 64 * 1024);
+        let err =
+            decode_pieces(ContentCoding::Gzip, &bomb, bomb.len(), Some(1024 * 1024)).unwrap_err();
+        assert!(
+            matches!(err, ParseError::BodyTooLarge { limit } if limit == 1024 * 1024),
+            "{}",
+            err
+        );
+
+        let gz = gzip(b"hello world");
+        let err = decode_pieces(ContentCoding::Gzip, &gz[..gz.len() - 3], 4, None).unwrap_err();
+        assert!(matches!(err, ParseError::Decompression(_)), "{}", err);
+
+        let mut corrupt = gz.clone();
+        let last = corrupt.len() - 5;
+        corrupt[last] ^= 0xff;
+        assert!(decode_pieces(ContentCoding::Gzip, &corrupt, 4, None).is_err());
+
+        let mut trailing = gz;
+        trailing.extend_from_slice(b"junk");
+        assert!(decode_pieces(ContentCoding::Gzip, &trailing, 4, None).is_err());
+    }
+
+    #[test]
+    fn decode_request_rewrites_headers() {
+        let body = gzip(br#"{"a":1}"#);
+        let mut bytes = format!(
+            "POST / HTTP/1.1\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nX-Id: 7\r\n\r\n",
+            body.len()
+        )
+        .into_bytes();
+        let header_end = bytes.len();
+        bytes.extend_from_slice(&body);
+        let header_end = decode_request(&mut bytes, header_end, ContentCoding::Gzip, None).unwrap();
+        assert_eq!(&bytes[..header_end], b"POST / HTTP/1.1\r\nX-Id: 7\r\n\r\n");
+        assert_eq!(&bytes[header_end..], br#"{"a":1}"#);
+    }
+}
@@ -0,0 +1,315 @@
+use std::net::{IpAddr, SocketAddr};
+
+use http::header::{HeaderMap, FORWARDED};
+use http::Request;
+
+use crate::proxy_protocol::IpCidr;
+#[cfg(feature = "arena")]
+use crate::ArenaBody;
+use crate::Body;
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+const X_FORWARDED_PROTO: &str = "x-forwarded-proto";
+const X_FORWARDED_HOST: &str = "x-forwarded-host";
+
+/// reverse proxy 체인을 거슬러 올라가 찾은 실제 클라이언트 정보.
+///
+/// `Options::trusted_proxies` 에 있는 hop 만 믿고 `Forwarded` (RFC 7239), 없으면
+/// `X-Forwarded-For` / `-Proto` / `-Host` 를 오른쪽부터 읽는다. 신뢰하지 않는 hop 을 만나면
+/// 그 주소가 클라이언트이고, 그보다 왼쪽 값 (클라이언트가 위조할 수 있는 부분) 은 무시한다.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ClientInfo {
+    /// 클라이언트 IP. UDS 연결이고 forwarding 정보도 없으면 `None`.
+    pub ip: Option<IpAddr>,
+    /// 클라이언트가 쓴 scheme (`https` 등). proxy 가 알려주지 않았으면 `None`.
+    pub proto: Option<String>,
+    /// 클라이언트가 보낸 `Host`. proxy 가 알려주지 않았으면 `None`.
+    pub host: Option<String>,
+}
+
+/// `Request` 에서 `ClientInfo` 를 꺼내는 accessor.
+///
+/// `trusted_proxies` 가 설정돼 있으면 파싱 시 계산해 둔 값을, 아니면 TCP peer 만 돌려준다.
+pub trait ClientInfoExt {
+    fn client_info(&self) -> ClientInfo;
+
+    fn client_ip(&self) -> Option<IpAddr> {
+        self.client_info().ip
+    }
+}
+
+impl ClientInfoExt for Request<Body> {
+    fn client_info(&self) -> ClientInfo {
+        resolved_or_peer(self, self.body().ip)
+    }
+}
+
+#[cfg(feature = "arena")]
+impl ClientInfoExt for Request<ArenaBody> {
+    fn client_info(&self) -> ClientInfo {
+        resolved_or_peer(self, self.body().ip)
+    }
+}
+
+fn resolved_or_peer<B>(request: &Request<B>, peer: Option<SocketAddr>) -> ClientInfo {
+    match request.extensions().get::<ClientInfo>() {
+        Some(info) => info.clone(),
+        None => ClientInfo {
+            ip: peer.map(|addr| addr.ip()),
+            ..ClientInfo::default()
+        },
+    }
+}
+
+/// `headers` 와 직접 연결한 peer 로 클라이언트를 찾는다. peer 가 `None` (UDS) 이면 로컬 proxy 로 보고 신뢰.
+pub fn resolve(headers: &HeaderMap, peer: Option<IpAddr>, trusted: &[IpCidr]) -> ClientInfo {
+    let is_trusted = |ip: IpAddr| trusted.iter().any(|cidr| cidr.contains(ip));
+    let mut info = ClientInfo {
+        ip: peer,
+        ..ClientInfo::default()
+    };
+    if peer.is_some_and(|ip| !is_trusted(ip)) {
+        return info;
+    }
+
+    let hops = if headers.contains_key(FORWARDED) {
+        forwarded_hops(headers)
+    } else {
+        x_forwarded_hops(headers)
+    };
+    // 오른쪽 (우리와 가까운 proxy 가 붙인 값) 부터
+    for hop in hops.into_iter().rev() {
+        let Some(ip) = hop.ip else {
+            // "unknown" / 난독화된 식별자 — 더 거슬러 올라갈 수 없음
+            break;
+        };
+        info = ClientInfo {
+            ip: Some(ip),
+            proto: hop.proto.or(info.proto),
+            host: hop.host.or(info.host),
+        };
+        if !is_trusted(ip) {
+            break;
+        }
+    }
+    info
+}
+
+#[derive(Debug, Default)]
+struct Hop {
+    ip: Option<IpAddr>,
+    proto: Option<String>,
+    host: Option<String>,
+}
+
+/// `Forwarded: for=192.0.2.60;proto=http;by=203.0.113.43, for="[2001:db8::1]:4711"`
+fn forwarded_hops(headers: &HeaderMap) -> Vec<Hop> {
+    header_list(headers, FORWARDED.as_str())
+        .iter()
+        .map(|element| {
+            let mut hop = Hop::default();
+            for pair in split_unquoted(element, ';') {
+                let Some((key, value)) = pair.split_once('=') else {
+                    continue;
+                };
+                let value = unquote(value.trim());
+                match key.trim().to_ascii_lowercase().as_str() {
+                    "for" => hop.ip = parse_node(value),
+                    "proto" => hop.proto = Some(value.to_ascii_lowercase()),
+                    "host" => hop.host = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+            hop
+        })
+        .collect()
+}
+
+/// `X-Forwarded-For` 를 hop 으로 나누고 `-Proto` / `-Host` 를 붙인다. 값 개수가 hop 수와 같으면
+/// hop 별로, 아니면 (edge proxy 하나만 설정하는 흔한 경우) 가장 오른쪽 값을 모든 hop 에 쓴다.
+fn x_forwarded_hops(headers: &HeaderMap) -> Vec<Hop> {
+    let addrs = header_list(headers, X_FORWARDED_FOR);
+    let protos = header_list(headers, X_FORWARDED_PROTO);
+    let hosts = header_list(headers, X_FORWARDED_HOST);
+    let aligned = |values: &[String], index: usize| -> Option<String> {
+        if values.len() == addrs.len() {
+            Some(values[index].clone())
+        } else {
+            values.last().cloned()
+        }
+    };
+    addrs
+        .iter()
+        .enumerate()
+        .map(|(i, addr)| Hop {
+            ip: parse_node(addr),
+            proto: aligned(&protos, i).map(|p| p.to_ascii_lowercase()),
+            host: aligned(&hosts, i),
+        })
+        .collect()
+}
+
+/// 같은 이름의 헤더 여러 줄을 순서대로 이어 콤마 단위로 나눈다.
+fn header_list(headers: &HeaderMap, name: &str) -> Vec<String> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| split_unquoted(value, ','))
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// quoted-string 안의 구분자는 무시하고 나눈다.
+fn split_unquoted(value: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c == separator && !in_quotes => {
+                parts.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// `1.2.3.4`, `1.2.3.4:80`, `[2001:db8::1]:443`, `2001:db8::1`. `unknown` / `_hidden` 은 `None`.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = unquote(node.trim());
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+    node.parse::<IpAddr>()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trusted() -> Vec<IpCidr> {
+        vec!["10.0.0.0/8".parse().unwrap(), "::1".parse().unwrap()]
+    }
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(
+                http::header::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                value.parse().unwrap(),
+            );
+        }
+        map
+    }
+
+    fn ip(s: &str) -> Option<IpAddr> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn x_forwarded_for_stops_at_first_untrusted_hop() {
+        // 클라이언트가 앞에 위조 값을 넣어도 신뢰하지 않는 hop 에서 멈춘다
+        let h = headers(&[
+            ("x-forwarded-for", "6.6.6.6, 198.51.100.4"),
+            ("x-forwarded-for", "10.1.1.1"),
+            ("x-forwarded-proto", "HTTPS"),
+            ("x-forwarded-host", "example.com"),
+        ]);
+        let info = resolve(&h, ip("10.0.0.2"), &trusted());
+        assert_eq!(info.ip, ip("198.51.100.4"));
+        assert_eq!(info.proto.as_deref(), Some("https"));
+        assert_eq!(info.host.as_deref(), Some("example.com"));
+
+        // hop 수와 같은 개수면 hop 별 값
+        let h = headers(&[
+            ("x-forwarded-for", "198.51.100.4, 10.1.1.1"),
+            ("x-forwarded-proto", "https, http"),
+        ]);
+        let info = resolve(&h, ip("10.0.0.2"), &trusted());
+        assert_eq!(info.ip, ip("198.51.100.4"));
+        assert_eq!(info.proto.as_deref(), Some("https"));
+    }
+
+    #[test]
+    fn headers_from_untrusted_peer_are_ignored() {
+        let h = headers(&[
+            ("x-forwarded-for", "1.2.3.4"),
+            ("x-forwarded-proto", "https"),
+        ]);
+        let info = resolve(&h, ip("203.0.113.1"), &trusted());
+        assert_eq!(
+            info,
+            ClientInfo {
+                ip: ip("203.0.113.1"),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn forwarded_takes_precedence_and_parses_rfc7239_nodes() {
+        let h = headers(&[
+            ("x-forwarded-for", "9.9.9.9"),
+            (
+                "forwarded",
+                r#"for="[2001:db8:cafe::17]:4711";proto=https;host="api.example.com", for=10.2.2.2:8080;proto=http"#,
+            ),
+        ]);
+        let info = resolve(&h, ip("::1"), &trusted());
+        assert_eq!(info.ip, ip("2001:db8:cafe::17"));
+        assert_eq!(info.proto.as_deref(), Some("https"));
+        assert_eq!(info.host.as_deref(), Some("api.example.com"));
+
+        // 난독화된 식별자는 더 거슬러 올라가지 않는다
+        let h = headers(&[("forwarded", "for=_hidden, for=10.3.3.3")]);
+        assert_eq!(resolve(&h, ip("10.0.0.1"), &trusted()).ip, ip("10.3.3.3"));
+    }
+
+    #[tokio::test]
+    async fn parsed_requests_carry_resolved_client() {
+        use crate::{Connection, Options};
+        use std::sync::Arc;
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (client, server) =
+            tokio::join!(tokio::net::TcpStream::connect(addr), listener.accept());
+        let (mut client, (server, peer)) = (client.unwrap(), server.unwrap());
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\nX-Forwarded-For: 192.0.2.44\r\nX-Forwarded-Proto: https\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut options = Options::new();
+        options.trusted_proxies = vec!["127.0.0.1".parse().unwrap()];
+        let mut conn = Connection::new(server, Arc::new(options), peer);
+        let (request, _response) = conn.next_request().await.unwrap().unwrap();
+        assert_eq!(request.client_ip(), ip("192.0.2.44"));
+        assert_eq!(request.client_info().proto.as_deref(), Some("https"));
+        // 원래 TCP peer 는 그대로
+        assert_eq!(request.body().ip, Some(peer));
+    }
+
+    #[test]
+    fn unix_socket_peer_is_trusted() {
+        let h = headers(&[("x-forwarded-for", "192.0.2.8")]);
+        assert_eq!(resolve(&h, None, &trusted()).ip, ip("192.0.2.8"));
+        assert_eq!(resolve(&HeaderMap::new(), None, &trusted()).ip, None);
+    }
+}
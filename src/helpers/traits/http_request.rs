@@ -272,6 +272,31 @@ impl RequestUtilsArena for Request<ArenaBody> {
     }
 }
 
+/// 요청의 `TE` 헤더 accessor. body 종류 (`Body` / `ArenaBody`) 에 상관없이 모든 `Request` 에 구현.
+pub trait TrailersExt {
+    /// 클라이언트가 `TE: trailers` 로 응답 trailer 를 받을 수 있다고 알렸는지 (gRPC 등).
+    fn accepts_trailers(&self) -> bool;
+}
+
+impl<B> TrailersExt for Request<B> {
+    fn accepts_trailers(&self) -> bool {
+        te_has_trailers(self.headers())
+    }
+}
+
+/// `TE: trailers, deflate;q=0.5` 처럼 `TE` 목록에 `trailers` 가 있는지.
+fn te_has_trailers(headers: &http::HeaderMap) -> bool {
+    headers
+        .get_all(http::header::TE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|item| {
+            let coding = item.split(';').next().unwrap_or_default();
+            coding.trim().eq_ignore_ascii_case("trailers")
+        })
+}
+
 // Content-Disposition에서 특정 값 추출 - 입력 슬라이스의 부분 슬라이스를 반환 (할당 없음)
 #[cfg(feature = "arena")]
 fn extract_content_disposition_value<'a>(header_value: &'a str, key: &str) -> Option<&'a str> {
@@ -333,6 +358,20 @@ mod tests {
             .unwrap()
     }
 
+    #[test]
+    fn accepts_trailers_reads_te_list() {
+        let request = |te: &str| {
+            Request::builder()
+                .header("te", te)
+                .body(Body::from_bytes(Vec::new(), None))
+                .unwrap()
+        };
+        assert!(request("trailers").accepts_trailers());
+        assert!(request("deflate;q=0.5, Trailers").accepts_trailers());
+        assert!(!request("gzip").accepts_trailers());
+        assert!(!build_req(Vec::new(), "x").accepts_trailers());
+    }
+
     #[tokio::test]
     async fn multipart_collects_all_text_fields() {
         // 회귀 방지: 이전 Form.text 단일 튜플은 마지막 필드만 남는 버그가 있었음.
@@ -3,17 +3,40 @@ use http::Response;
 use std::fmt::Write as _;
 use tokio::io::AsyncWriteExt;
 
+use crate::response_body::{BodyFraming, SinkState};
+use crate::shutdown::ConnectionGuard;
+use crate::socket::OwnedWriteHalf;
+#[cfg(feature = "response_file")]
+use crate::FileBody;
+#[cfg(any(feature = "connection_pool", feature = "compression"))]
+use crate::Options;
 #[cfg(feature = "arena")]
-use crate::ArenaWriter;
-use crate::{SendableError, Writer};
+use crate::{ArenaResponse, ArenaWriter};
+use crate::{BodyStream, BodyWriter, ResponseBody, SendableError, Writer};
 #[cfg(feature = "response_file")]
 use std::path::Path;
 
+#[cfg(all(feature = "compression", feature = "response_file"))]
+use crate::compression::Encoder;
+#[cfg(feature = "compression")]
+use crate::compression::{self, Encoding};
 use crate::helpers::traits::zero_copy::ZeroCopyCache;
+#[cfg(feature = "response_file")]
+use crate::{
+    conditional::{Precondition, Preconditions},
+    helpers::traits::zero_copy::FileLoadResult,
+    range::{Multipart, Partial, RangeRequest},
+};
+use http::HeaderValue;
+#[cfg(any(feature = "connection_pool", feature = "compression"))]
+use std::sync::Arc;
 
 impl Writer {
+    /// 메모리에 있는 body (텍스트 / 바이트) 를 그대로 보낸다. 헤더는 보내지 않는다.
     pub async fn write_bytes(&mut self) -> Result<(), SendableError> {
-        self.stream.send_bytes(self.bytes.as_slice()).await?;
+        if let Some(data) = self.body.as_bytes() {
+            self.stream.send_bytes(data).await?;
+        }
         Ok(())
     }
 
@@ -29,29 +52,15 @@ impl Writer {
         let file_path = safe_path_join(root_path, &path)
             .ok_or("invalid path: traversal segments are not allowed")?;
 
-        // 제로카피 기능이 활성화된 경우 memmap2 사용 시도
-        // 파일 크기 확인
-        if let Ok(metadata) = std::fs::metadata(&file_path) {
-            let file_size = metadata.len() as usize;
-
-            // 작은 파일들은 제로카피 캐시 사용
-            if file_size <= 10 * 1024 * 1024 {
-                // 10MB 이하
-                crate::dev_print!(
-                    "Using zero-copy for file: {:?} ({}KB)",
-                    file_path,
-                    file_size / 1024
-                );
-                // non-UTF-8 경로(Windows 등)에서 panic 회피 — lossy 변환
-                self.body = format!("__ZERO_COPY_FILE__:{}", file_path.to_string_lossy());
-                self.use_file = true;
-                return Ok(());
-            }
-        }
-
-        // 기존 방식 (대용량 파일 또는 zero_copy 기능 비활성화)
-        self.body = file_path.to_string_lossy().into_owned();
-        self.use_file = true;
+        // 10MB 이하는 제로카피 캐시 (memory cache / mmap), 그보다 크면 스트리밍
+        let file = FileBody::new(file_path, 10 * 1024 * 1024)?;
+        crate::dev_print!(
+            "Serving file: {:?} ({}KB, zero-copy: {})",
+            file.path(),
+            file.len() / 1024,
+            file.is_zero_copy()
+        );
+        self.body = ResponseBody::File(file);
         Ok(())
     }
 }
@@ -60,139 +69,152 @@ impl Writer {
 pub trait ResponseUtil {
     async fn responser(&mut self) -> Result<(), SendableError>;
 
+    /// 헤더를 보내고 body 를 써 나갈 `BodyWriter` 를 돌려준다. `body` 는 보내지 않으며, 이후
+    /// `responser` 는 아무것도 하지 않는다 (`serve*` 핸들러에서 써도 응답이 두 번 나가지 않음).
+    /// `Content-Length` 헤더가 있으면 그대로, 없으면 chunked 로 보낸다 (HTTP/1.0 은 연결을 닫아 끝을 알림).
+    async fn body_writer(&mut self) -> Result<BodyWriter<'_>, SendableError>;
+
     async fn send_zero_copy_file(&mut self, mut send_string: String) -> Result<(), SendableError>;
 }
 
 #[async_trait]
 impl ResponseUtil for Response<Writer> {
     async fn responser(&mut self) -> Result<(), SendableError> {
-        // 일반적인 응답 헤더 크기 (~512B) 사전 할당으로 재할당 방지
-        let mut send_string = String::with_capacity(512);
-        if cfg!(feature = "response_file") && self.body().use_file {
-            use http::StatusCode;
-            *self.status_mut() = StatusCode::from_u16(200)?;
+        // `body_writer` 로 이미 보낸 응답
+        if self.body().sink_state != SinkState::Idle {
+            return Ok(());
         }
-        write!(send_string, "{:?} {}\r\n", self.version(), self.status())?;
-
-        #[cfg(feature = "connection_pool")]
-        {
-            use http::header::CONNECTION;
-            let options = &self.body().options;
-            let connection_config = &options.connection_option;
-
-            // Keep-alive 설정이 활성화된 경우에만 헤더 추가
-            if connection_config.enable_keep_alive && !self.headers().contains_key(CONNECTION) {
-                send_string.push_str("Connection: keep-alive\r\n");
-                write!(
-                    send_string,
-                    "Keep-Alive: timeout={}, max={}\r\n",
-                    connection_config.max_idle_time.as_secs(),
-                    connection_config.max_connections_per_host
-                )?;
-            } else if !connection_config.enable_keep_alive
-                && !self.headers().contains_key(CONNECTION)
-            {
-                send_string.push_str("Connection: close\r\n");
+        // 조건부 요청 / Range 는 status line 을 쓰기 전에 평가해야 304 / 412 / 206 / 416 을 낼 수 있다
+        #[cfg(feature = "response_file")]
+        let outcome = if matches!(self.body().body, ResponseBody::File(_)) {
+            *self.status_mut() = http::StatusCode::OK;
+            file_outcome(self)?
+        } else {
+            None
+        };
+        // 스트림은 status line 전에 꺼내 경계를 정한다 (HTTP/1.0 이면 `Connection: close`)
+        let stream = match std::mem::take(&mut self.body_mut().body) {
+            ResponseBody::Stream(stream) => Some((stream_framing(self)?, stream)),
+            body => {
+                self.body_mut().body = body;
+                None
             }
+        };
+        let send_string = response_head(self)?;
+        if let Some((framing, stream)) = stream {
+            return send_stream_body(self, send_string, framing, stream).await;
         }
 
-        if cfg!(feature = "response_file") && self.body().use_file {
-            use tokio::{fs, io::AsyncReadExt};
-
+        match &self.body().body {
             #[cfg(feature = "response_file")]
-            {
+            ResponseBody::File(file) => {
                 use http::header::CONTENT_TYPE;
+                use tokio::{fs, io::AsyncReadExt};
+
+                let mut send_string = send_string;
+
+                let (zero_copy, path) = (file.is_zero_copy(), file.path().to_path_buf());
                 self.headers_mut().remove(CONTENT_TYPE);
 
-                // 제로카피 파일 처리 확인
-                if self.body().body.starts_with("__ZERO_COPY_FILE__:") {
+                if let Some(outcome) = outcome {
+                    return send_file_outcome(self, send_string, outcome).await;
+                }
+
+                // 제로카피 파일 처리
+                if zero_copy {
                     return self.send_zero_copy_file(send_string).await;
                 }
 
-                // 기존 파일 처리 방식
-                match self.body().body.split('.').last().unwrap() {
+                // 대용량 파일은 스트리밍
+                let path_str = path.to_string_lossy();
+                match path_str.split('.').last().unwrap() {
                     "zip" => {
                         send_string.push_str("Content-Type: application/zip\r\n");
                         write!(
                             send_string,
                             "content-disposition: attachment; filename={}\r\n",
-                            self.body().body
+                            path_str
                         )?;
                     }
                     _ => {
                         write!(
                             send_string,
                             "Content-Type: {}\r\n",
-                            get_content_type(&self.body().body)
+                            get_content_type(&path_str)
                         )?;
                     }
                 }
-            }
 
-            for (key, value) in self.headers().iter() {
-                write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
-            }
+                let mut file = fs::File::open(&path).await?;
+                let content_length = file.metadata().await?.len();
 
-            let mut file = fs::File::open(&self.body().body).await?;
-            let content_length = file.metadata().await?.len();
-            write!(send_string, "content-length: {}\r\n", content_length)?;
+                // 압축하면 길이를 미리 알 수 없어 chunked 로 보낸다 — HTTP/1.0 은 압축하지 않음
+                #[cfg(feature = "compression")]
+                let encoding = if self.version() == http::Version::HTTP_11 {
+                    let content_type = get_content_type(&path_str);
+                    choose_encoding(self, Some(&content_type), content_length as usize)
+                } else {
+                    None
+                };
 
-            send_string.push_str("\r\n");
+                for (key, value) in self.headers().iter() {
+                    write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
+                }
 
-            // 여기서 mutable borrow 문제 해결: body_mut()을 한 번만 호출
-            let body = self.body_mut();
-            body.stream.send_bytes(send_string.as_bytes()).await?;
+                // 고정 128KB 스트리밍 버퍼 — content_length 기반 통째 할당 대신
+                // TCP burst 한 번에 적합한 크기 + 메모리 사용량 일정.
+                // BufReader는 제거: 128KB 직접 read가 더 빠름 (중간 복사 1단계 절약).
+                const FILE_STREAM_BUF_SIZE: usize = 128 * 1024;
 
-            // 고정 128KB 스트리밍 버퍼 — content_length 기반 통째 할당 대신
-            // TCP burst 한 번에 적합한 크기 + 메모리 사용량 일정.
-            // BufReader는 제거: 128KB 직접 read가 더 빠름 (중간 복사 1단계 절약).
-            const FILE_STREAM_BUF_SIZE: usize = 128 * 1024;
-            let mut buffer = vec![0u8; FILE_STREAM_BUF_SIZE];
-            while let Ok(len) = file.read(&mut buffer).await {
-                if len == 0 {
-                    break;
+                #[cfg(feature = "compression")]
+                if let Some(encoding) = encoding {
+                    send_string.push_str("transfer-encoding: chunked\r\n\r\n");
+                    let encoder = Encoder::new(encoding, &self.body().options.compression_option);
+                    let body = self.body_mut();
+                    body.stream.send_bytes(send_string.as_bytes()).await?;
+                    send_compressed_file(
+                        &mut body.stream,
+                        &mut file,
+                        encoder,
+                        FILE_STREAM_BUF_SIZE,
+                    )
+                    .await?;
+                    body.stream.flush().await?;
+                    return Ok(());
                 }
-                body.stream.send_bytes(&buffer[0..len]).await?;
-            }
-        } else if !self.body().bytes.is_empty() {
-            for (key, value) in self.headers().iter() {
-                write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
-            }
-            send_string.push_str("\r\n");
-
-            // 헤더 + 바디를 vectored I/O로 한번에 전송 (clone/copy 0회)
-            let body = self.body_mut();
-            #[cfg(feature = "vectored_io")]
-            {
-                use std::io::IoSlice;
-                let bufs = [
-                    IoSlice::new(send_string.as_bytes()),
-                    IoSlice::new(&body.bytes),
-                ];
-                body.stream.send_vectored(&bufs).await?;
-            }
-            #[cfg(not(feature = "vectored_io"))]
-            {
-                body.stream.send_bytes(send_string.as_bytes()).await?;
-                body.stream.send_bytes(&body.bytes).await?;
-            }
-        } else {
-            let (body_str, content_string) = get_body(self.body().body.as_str()).await;
-            send_string.push_str(&content_string);
 
-            for (key, value) in self.headers().iter() {
-                write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
-            }
-            crate::dev_print!("headers: {}", &send_string);
-            send_string.push_str("\r\n");
+                write!(send_string, "content-length: {}\r\n", content_length)?;
+
+                send_string.push_str("\r\n");
 
-            send_string.push_str(&body_str);
+                // 여기서 mutable borrow 문제 해결: body_mut()을 한 번만 호출
+                let body = self.body_mut();
+                body.stream.send_bytes(send_string.as_bytes()).await?;
 
-            // mutable borrow 문제 해결
-            self.body_mut()
-                .stream
-                .send_bytes(send_string.as_bytes())
-                .await?;
+                // 평문 TCP 면 커널이 파일에서 소켓으로 바로 보낸다 (sendfile). 아니면 버퍼로 복사
+                #[cfg(all(feature = "sendfile", target_os = "linux"))]
+                let sent = body.options.sendfile
+                    && crate::sendfile::send_file(&body.stream, &file, 0, content_length).await?;
+                #[cfg(not(all(feature = "sendfile", target_os = "linux")))]
+                let sent = false;
+
+                if !sent {
+                    let mut buffer = vec![0u8; FILE_STREAM_BUF_SIZE];
+                    loop {
+                        let len = file.read(&mut buffer).await?;
+                        if len == 0 {
+                            break;
+                        }
+                        body.stream.send_bytes(&buffer[0..len]).await?;
+                    }
+                }
+            }
+            _ => {
+                // 압축하면 body 가 압축 결과 (`Bytes`) 로 바뀐다
+                #[cfg(feature = "compression")]
+                compress_writer_body(self)?;
+                send_buffered_body(self, send_string).await?;
+            }
         }
 
         // flush는 별도로 처리
@@ -200,9 +222,24 @@ impl ResponseUtil for Response<Writer> {
         Ok(())
     }
 
+    async fn body_writer(&mut self) -> Result<BodyWriter<'_>, SendableError> {
+        let framing = stream_framing(self)?;
+        let send_string = response_head(self)?;
+        start_body(self, send_string, framing).await
+    }
+
     async fn send_zero_copy_file(&mut self, mut send_string: String) -> Result<(), SendableError> {
         use http::header::CONTENT_TYPE;
-        let file_path = &self.body().body[19..]; // "__ZERO_COPY_FILE__:" 이후의 경로
+        // 압축 협상이 헤더를 고치는 동안 self 를 빌리지 않도록 경로를 복사
+        #[cfg(feature = "response_file")]
+        let file_path = self
+            .body()
+            .file()
+            .map(|f| f.path().to_string_lossy().into_owned());
+        #[cfg(not(feature = "response_file"))]
+        let file_path: Option<String> = None;
+        let file_path = file_path.ok_or("no file body")?;
+        let file_path = file_path.as_str();
 
         // 캐시를 사용한 파일 로드
         let cache = ZeroCopyCache::global();
@@ -246,6 +283,22 @@ impl ResponseUtil for Response<Writer> {
 
         self.headers_mut().remove(CONTENT_TYPE);
 
+        #[cfg(feature = "compression")]
+        let compressed = compress_body(self, Some(&get_content_type(file_path)), file_data)?;
+        #[cfg(feature = "compression")]
+        let (file_data, content_length) = match &compressed {
+            Some(compressed) => (compressed.as_slice(), compressed.len()),
+            None => (file_data, content_length),
+        };
+
+        // mmap 한 큰 파일을 압축 없이 보낼 때는 같은 fd 에서 sendfile 로 보낸다
+        #[cfg(all(feature = "sendfile", target_os = "linux"))]
+        let sendfile = file_result
+            .mapped_file()
+            .filter(|_| self.body().options.sendfile);
+        #[cfg(all(feature = "sendfile", target_os = "linux", feature = "compression"))]
+        let sendfile = sendfile.filter(|_| compressed.is_none());
+
         // 추가 헤더들
         for (key, value) in self.headers().iter() {
             write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
@@ -257,6 +310,15 @@ impl ResponseUtil for Response<Writer> {
 
         let body = self.body_mut();
 
+        #[cfg(all(feature = "sendfile", target_os = "linux"))]
+        if let Some(file) = sendfile {
+            body.stream.send_bytes(send_string.as_bytes()).await?;
+            if !crate::sendfile::send_file(&body.stream, file, 0, content_length as u64).await? {
+                body.stream.send_bytes(file_data).await?;
+            }
+            return Ok(());
+        }
+
         #[cfg(feature = "vectored_io")]
         {
             // Vectored I/O로 헤더와 파일 데이터를 한 번에 전송
@@ -294,6 +356,9 @@ impl ResponseUtil for Response<Writer> {
 pub trait ResponseUtilArena {
     async fn responser_arena(&mut self) -> Result<(), SendableError>;
 
+    /// `ResponseUtil::body_writer` 의 arena 버전.
+    async fn body_writer_arena(&mut self) -> Result<BodyWriter<'_>, SendableError>;
+
     #[cfg(feature = "arena")]
     async fn send_arena_zero_copy_file(
         &mut self,
@@ -306,97 +371,122 @@ pub trait ResponseUtilArena {
 #[async_trait]
 impl ResponseUtilArena for Response<ArenaWriter> {
     async fn responser_arena(&mut self) -> Result<(), SendableError> {
-        let mut send_string = String::with_capacity(512);
-
-        if cfg!(feature = "response_file") && self.body().use_file {
-            use http::StatusCode;
-            *self.status_mut() = StatusCode::from_u16(200)?;
+        if self.body().sink_state != SinkState::Idle {
+            return Ok(());
         }
-
-        write!(send_string, "{:?} {}\r\n", self.version(), self.status())?;
-
-        #[cfg(feature = "connection_pool")]
-        {
-            use http::header::CONNECTION;
-            let options = &self.body().options;
-            let connection_config = &options.connection_option;
-
-            // Keep-alive 설정이 활성화된 경우에만 헤더 추가
-            if connection_config.enable_keep_alive && !self.headers().contains_key(CONNECTION) {
-                send_string.push_str("Connection: keep-alive\r\n");
-                write!(
-                    send_string,
-                    "Keep-Alive: timeout={}, max={}\r\n",
-                    connection_config.max_idle_time.as_secs(),
-                    connection_config.max_connections_per_host
-                )?;
-            } else if !connection_config.enable_keep_alive
-                && !self.headers().contains_key(CONNECTION)
-            {
-                send_string.push_str("Connection: close\r\n");
+        #[cfg(feature = "response_file")]
+        let outcome = if matches!(self.body().body, ArenaResponse::File(_)) {
+            *self.status_mut() = http::StatusCode::OK;
+            file_outcome(self)?
+        } else {
+            None
+        };
+        // 스트림은 status line 전에 꺼내 경계를 정한다 (responser와 동일)
+        let stream = match std::mem::replace(&mut self.body_mut().body, ArenaResponse::Empty) {
+            ArenaResponse::Stream(stream) => Some((stream_framing(self)?, stream)),
+            body => {
+                self.body_mut().body = body;
+                None
             }
+        };
+        let mut send_string = response_head(self)?;
+        if let Some((framing, stream)) = stream {
+            return send_stream_body(self, send_string, framing, stream).await;
         }
 
-        if cfg!(feature = "response_file") && self.body().use_file {
+        match &self.body().body {
             #[cfg(feature = "response_file")]
-            {
+            ArenaResponse::File(file) => {
                 use http::header::CONTENT_TYPE;
                 use tokio::{fs, io::AsyncReadExt};
+
+                let zero_copy = file.is_zero_copy();
+                let file_path = file.path().to_string_lossy().into_owned();
+                let file_path = file_path.as_str();
                 self.headers_mut().remove(CONTENT_TYPE);
 
-                if self.body().response_data_len > 0 {
-                    let file_path = unsafe {
-                        let data = std::slice::from_raw_parts(
-                            self.body().response_data_ptr,
-                            self.body().response_data_len,
-                        );
-                        std::str::from_utf8(data)?
-                    };
-
-                    // Arena + 제로카피 조합 처리
-                    if file_path.starts_with("__ZERO_COPY_FILE__:") {
-                        let actual_path = &file_path[19..];
-                        return self
-                            .send_arena_zero_copy_file(actual_path, send_string)
-                            .await;
-                    }
+                if let Some(outcome) = outcome {
+                    return send_file_outcome(self, send_string, outcome).await;
+                }
 
-                    match file_path.split('.').last().unwrap() {
-                        "zip" => {
-                            send_string.push_str("Content-Type: application/zip\r\n");
-                            write!(
-                                send_string,
-                                "content-disposition: attachment; filename={}\r\n",
-                                file_path
-                            )?;
-                        }
-                        _ => {
-                            write!(
-                                send_string,
-                                "Content-Type: {}\r\n",
-                                get_content_type(file_path)
-                            )?;
-                        }
-                    }
+                // Arena + 제로카피 조합 처리
+                if zero_copy {
+                    return self.send_arena_zero_copy_file(file_path, send_string).await;
+                }
 
-                    for (key, value) in self.headers().iter() {
-                        write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
+                match file_path.split('.').last().unwrap() {
+                    "zip" => {
+                        send_string.push_str("Content-Type: application/zip\r\n");
+                        write!(
+                            send_string,
+                            "content-disposition: attachment; filename={}\r\n",
+                            file_path
+                        )?;
                     }
+                    _ => {
+                        write!(
+                            send_string,
+                            "Content-Type: {}\r\n",
+                            get_content_type(file_path)
+                        )?;
+                    }
+                }
 
-                    let mut file = fs::File::open(file_path).await?;
-                    let content_length = file.metadata().await?.len();
-                    write!(send_string, "content-length: {}\r\n", content_length)?;
+                let mut file = fs::File::open(file_path).await?;
+                let content_length = file.metadata().await?.len();
+
+                // 압축 시 chunked 전송 (responser와 동일 정책)
+                #[cfg(feature = "compression")]
+                let encoding = if self.version() == http::Version::HTTP_11 {
+                    let content_type = get_content_type(file_path);
+                    choose_encoding(self, Some(&content_type), content_length as usize)
+                } else {
+                    None
+                };
+
+                for (key, value) in self.headers().iter() {
+                    write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
+                }
 
-                    send_string.push_str("\r\n");
+                // 고정 128KB 스트리밍 버퍼 (responser와 동일 정책)
+                const FILE_STREAM_BUF_SIZE: usize = 128 * 1024;
 
-                    // mutable borrow 문제 해결
+                #[cfg(feature = "compression")]
+                if let Some(encoding) = encoding {
+                    send_string.push_str("transfer-encoding: chunked\r\n\r\n");
+                    let encoder = Encoder::new(encoding, &self.body().options.compression_option);
                     let body = self.body_mut();
                     body.stream.send_bytes(send_string.as_bytes()).await?;
+                    send_compressed_file(
+                        &mut body.stream,
+                        &mut file,
+                        encoder,
+                        FILE_STREAM_BUF_SIZE,
+                    )
+                    .await?;
+                    body.stream.flush().await?;
+                    return Ok(());
+                }
+
+                write!(send_string, "content-length: {}\r\n", content_length)?;
+
+                send_string.push_str("\r\n");
+
+                // mutable borrow 문제 해결
+                let body = self.body_mut();
+                body.stream.send_bytes(send_string.as_bytes()).await?;
+
+                // sendfile 우선 (responser와 동일 정책)
+                #[cfg(all(feature = "sendfile", target_os = "linux"))]
+                let sent = body.options.sendfile
+                    && crate::sendfile::send_file(&body.stream, &file, 0, content_length).await?;
+                #[cfg(not(all(feature = "sendfile", target_os = "linux")))]
+                let sent = false;
 
-                    // 고정 128KB 스트리밍 버퍼 (responser와 동일 정책)
-                    const FILE_STREAM_BUF_SIZE: usize = 128 * 1024;
+                if !sent {
                     let mut buffer = vec![0u8; FILE_STREAM_BUF_SIZE];
-                    while let Ok(len) = file.read(&mut buffer).await {
+                    loop {
+                        let len = file.read(&mut buffer).await?;
                         if len == 0 {
                             break;
                         }
@@ -404,9 +494,17 @@ impl ResponseUtilArena for Response<ArenaWriter> {
                     }
                 }
             }
-        } else {
-            if self.body().response_data_len > 0 {
-                // Arena 메모리로 할당된 응답 데이터 사용
+            _ => {
+                // 압축 결과도 arena 로 옮겨 아래 경로를 그대로 탄다
+                #[cfg(feature = "compression")]
+                if !self.body().get_response_data().is_empty() {
+                    let data = self.body().get_response_data().to_vec();
+                    if let Some(compressed) = compress_body(self, None, &data)? {
+                        self.body_mut().set_arena_bytes(&compressed);
+                    }
+                }
+
+                // Arena 메모리로 할당된 응답 데이터 사용 (없으면 빈 응답)
                 for (key, value) in self.headers().iter() {
                     write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
                 }
@@ -414,7 +512,7 @@ impl ResponseUtilArena for Response<ArenaWriter> {
                 write!(
                     send_string,
                     "content-length: {}\r\n",
-                    self.body().response_data_len
+                    self.body().get_response_data().len()
                 )?;
                 send_string.push_str("\r\n");
 
@@ -422,8 +520,10 @@ impl ResponseUtilArena for Response<ArenaWriter> {
                 let body = self.body_mut();
 
                 // Arena 데이터 직접 전송 (제로카피)
+                // SAFETY: 데이터는 body._bump 소유 — stream 만 빌리는 동안 바뀌지 않는다
                 let response_data = unsafe {
-                    std::slice::from_raw_parts(body.response_data_ptr, body.response_data_len)
+                    let data = body.get_response_data();
+                    std::slice::from_raw_parts(data.as_ptr(), data.len())
                 };
 
                 #[cfg(feature = "vectored_io")]
@@ -448,18 +548,6 @@ impl ResponseUtilArena for Response<ArenaWriter> {
                     body.stream.send_bytes(send_string.as_bytes()).await?;
                     body.stream.send_bytes(response_data).await?;
                 }
-            } else {
-                // 빈 응답
-                for (key, value) in self.headers().iter() {
-                    write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
-                }
-                send_string.push_str("content-length: 0\r\n");
-                send_string.push_str("\r\n");
-
-                self.body_mut()
-                    .stream
-                    .send_bytes(send_string.as_bytes())
-                    .await?;
             }
         }
 
@@ -468,6 +556,12 @@ impl ResponseUtilArena for Response<ArenaWriter> {
         Ok(())
     }
 
+    async fn body_writer_arena(&mut self) -> Result<BodyWriter<'_>, SendableError> {
+        let framing = stream_framing(self)?;
+        let send_string = response_head(self)?;
+        start_body(self, send_string, framing).await
+    }
+
     #[cfg(feature = "arena")]
     async fn send_arena_zero_copy_file(
         &mut self,
@@ -517,6 +611,22 @@ impl ResponseUtilArena for Response<ArenaWriter> {
             }
         }
 
+        #[cfg(feature = "compression")]
+        let compressed = compress_body(self, Some(&get_content_type(file_path)), file_data)?;
+        #[cfg(feature = "compression")]
+        let (file_data, content_length) = match &compressed {
+            Some(compressed) => (compressed.as_slice(), compressed.len()),
+            None => (file_data, content_length),
+        };
+
+        // mmap 한 큰 파일을 압축 없이 보낼 때는 같은 fd 에서 sendfile 로 보낸다
+        #[cfg(all(feature = "sendfile", target_os = "linux"))]
+        let sendfile = file_result
+            .mapped_file()
+            .filter(|_| self.body().options.sendfile);
+        #[cfg(all(feature = "sendfile", target_os = "linux", feature = "compression"))]
+        let sendfile = sendfile.filter(|_| compressed.is_none());
+
         // 추가 헤더들
         for (key, value) in self.headers().iter() {
             write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
@@ -529,6 +639,15 @@ impl ResponseUtilArena for Response<ArenaWriter> {
         // mutable borrow 문제 해결: body_mut()을 한 번만 호출
         let body = self.body_mut();
 
+        #[cfg(all(feature = "sendfile", target_os = "linux"))]
+        if let Some(file) = sendfile {
+            body.stream.send_bytes(send_string.as_bytes()).await?;
+            if !crate::sendfile::send_file(&body.stream, file, 0, content_length as u64).await? {
+                body.stream.send_bytes(file_data).await?;
+            }
+            return Ok(());
+        }
+
         #[cfg(feature = "vectored_io")]
         {
             // Vectored I/O로 헤더와 파일 데이터를 한 번에 전송
@@ -656,6 +775,18 @@ impl SendBytes for tokio::net::tcp::OwnedWriteHalf {
     }
 }
 
+#[async_trait]
+impl SendBytes for crate::socket::OwnedWriteHalf {
+    async fn send_bytes(&mut self, bytes: &[u8]) -> Result<(), SendableError> {
+        send_bytes_generic(self, bytes).await
+    }
+
+    #[cfg(feature = "vectored_io")]
+    async fn send_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> Result<(), SendableError> {
+        send_vectored_generic(self, bufs).await
+    }
+}
+
 // 기존 TcpStream 호환성 유지 (테스트/internal 코드용).
 #[async_trait]
 impl SendBytes for tokio::net::TcpStream {
@@ -681,10 +812,545 @@ pub fn get_content_type(file_name: &str) -> String {
     }
 }
 
-async fn get_body(body: &str) -> (String, String) {
-    let length = body.len();
+/// graceful shutdown 중이면 핸들러가 따로 지정하지 않은 한 `Connection: close` 로 응답해
+/// 클라이언트가 이 연결에 다음 요청을 보내지 않게 한다.
+fn close_on_shutdown(shutting_down: bool, headers: &mut http::HeaderMap) {
+    use http::header::{HeaderValue, CONNECTION};
+    if shutting_down && !headers.contains_key(CONNECTION) {
+        headers.insert(CONNECTION, HeaderValue::from_static("close"));
+    }
+}
+
+/// 응답 헤더 / body 전송에 쓰는 writer 쪽 값 — `Writer` / `ArenaWriter` 공통.
+trait ResponseTarget {
+    #[cfg(any(feature = "connection_pool", feature = "compression"))]
+    fn options(&self) -> &Arc<Options>;
+    fn guard(&self) -> Option<&ConnectionGuard>;
+    /// 소켓과 `sink_state` 를 함께 빌린다 (`BodyWriter` 용).
+    fn sink(&mut self) -> (&mut OwnedWriteHalf, &mut SinkState);
+
+    #[cfg(feature = "response_file")]
+    fn stream_mut(&mut self) -> &mut OwnedWriteHalf {
+        self.sink().0
+    }
+}
+
+impl ResponseTarget for Writer {
+    #[cfg(any(feature = "connection_pool", feature = "compression"))]
+    fn options(&self) -> &Arc<Options> {
+        &self.options
+    }
+
+    fn guard(&self) -> Option<&ConnectionGuard> {
+        self.guard.as_ref()
+    }
+
+    fn sink(&mut self) -> (&mut OwnedWriteHalf, &mut SinkState) {
+        (&mut self.stream, &mut self.sink_state)
+    }
+}
+
+#[cfg(feature = "arena")]
+impl ResponseTarget for ArenaWriter {
+    #[cfg(any(feature = "connection_pool", feature = "compression"))]
+    fn options(&self) -> &Arc<Options> {
+        &self.options
+    }
+
+    fn guard(&self) -> Option<&ConnectionGuard> {
+        self.guard.as_ref()
+    }
+
+    fn sink(&mut self) -> (&mut OwnedWriteHalf, &mut SinkState) {
+        (&mut self.stream, &mut self.sink_state)
+    }
+}
+
+/// status line 과 연결 관리 헤더 (`Connection` / `Keep-Alive`). 나머지 헤더는 호출자가 붙인다.
+fn response_head<B: ResponseTarget>(response: &mut Response<B>) -> Result<String, SendableError> {
+    // 일반적인 응답 헤더 크기 (~512B) 사전 할당으로 재할당 방지
+    let mut send_string = String::with_capacity(512);
+    write!(
+        send_string,
+        "{:?} {}\r\n",
+        response.version(),
+        response.status()
+    )?;
+    let shutting_down = response
+        .body()
+        .guard()
+        .is_some_and(|g| g.is_shutting_down());
+    close_on_shutdown(shutting_down, response.headers_mut());
+
+    #[cfg(feature = "connection_pool")]
+    {
+        use http::header::CONNECTION;
+        let connection_config = &response.body().options().connection_option;
+
+        // Keep-alive 설정이 활성화된 경우에만 헤더 추가
+        if connection_config.enable_keep_alive && !response.headers().contains_key(CONNECTION) {
+            send_string.push_str("Connection: keep-alive\r\n");
+            write!(
+                send_string,
+                "Keep-Alive: timeout={}, max={}\r\n",
+                connection_config.max_idle_time.as_secs(),
+                connection_config.max_connections_per_host
+            )?;
+        } else if !connection_config.enable_keep_alive
+            && !response.headers().contains_key(CONNECTION)
+        {
+            send_string.push_str("Connection: close\r\n");
+        }
+    }
+    Ok(send_string)
+}
+
+/// 응답 압축 협상에 쓰는 writer 쪽 값 — `Writer` / `ArenaWriter` 공통.
+#[cfg(feature = "compression")]
+trait CompressionSource: ResponseTarget {
+    fn accept_encoding(&self) -> Option<&HeaderValue>;
+}
+
+#[cfg(feature = "compression")]
+impl CompressionSource for Writer {
+    fn accept_encoding(&self) -> Option<&HeaderValue> {
+        self.accept_encoding.as_deref()
+    }
+}
+
+#[cfg(all(feature = "compression", feature = "arena"))]
+impl CompressionSource for ArenaWriter {
+    fn accept_encoding(&self) -> Option<&HeaderValue> {
+        self.accept_encoding.as_deref()
+    }
+}
+
+/// `compression::choose` 를 응답에 적용한다. 헤더를 고치는 동안 body 를 빌릴 수 없어 값을 복사해 둔다.
+#[cfg(feature = "compression")]
+fn choose_encoding<B: CompressionSource>(
+    response: &mut Response<B>,
+    content_type: Option<&str>,
+    len: usize,
+) -> Option<Encoding> {
+    let options = Arc::clone(response.body().options());
+    let accept_encoding = response.body().accept_encoding().cloned();
+    let status = response.status();
+    compression::choose(
+        &options.compression_option,
+        accept_encoding.as_ref(),
+        status,
+        response.headers_mut(),
+        content_type,
+        len,
+    )
+}
+
+/// 메모리에 있는 body 를 압축할 수 있으면 압축해 돌려준다. `None` 이면 원본 그대로 보낸다.
+#[cfg(feature = "compression")]
+fn compress_body<B: CompressionSource>(
+    response: &mut Response<B>,
+    content_type: Option<&str>,
+    data: &[u8],
+) -> Result<Option<Vec<u8>>, SendableError> {
+    match choose_encoding(response, content_type, data.len()) {
+        Some(encoding) => Ok(Some(compression::compress(
+            encoding,
+            &response.body().options().compression_option,
+            data,
+        )?)),
+        None => Ok(None),
+    }
+}
+
+/// `Writer` 의 텍스트 / 바이트 body 를 압축할 수 있으면 압축 결과 (`Bytes`) 로 바꾼다.
+#[cfg(feature = "compression")]
+fn compress_writer_body(response: &mut Response<Writer>) -> Result<(), SendableError> {
+    use http::header::CONTENT_LENGTH;
+    let body = std::mem::take(&mut response.body_mut().body);
+    let compressed = match body.as_bytes() {
+        Some(data) if !data.is_empty() => compress_body(response, None, data)?,
+        _ => None,
+    };
+    match compressed {
+        Some(compressed) => {
+            // 길이는 압축 결과로 다시 잰다
+            response.headers_mut().remove(CONTENT_LENGTH);
+            response.body_mut().body = ResponseBody::Bytes(compressed);
+        }
+        None => response.body_mut().body = body,
+    }
+    Ok(())
+}
+
+/// 텍스트 / 바이트 body 를 헤더와 함께 보낸다. `Content-Length` 헤더가 없으면 body 길이로 붙인다.
+async fn send_buffered_body(
+    response: &mut Response<Writer>,
+    mut send_string: String,
+) -> Result<(), SendableError> {
+    use http::header::CONTENT_LENGTH;
+
+    for (key, value) in response.headers().iter() {
+        write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
+    }
+    let len = response.body().body.len().unwrap_or_default();
+    if !response.headers().contains_key(CONTENT_LENGTH) {
+        write!(send_string, "content-length: {}\r\n", len)?;
+    }
+    crate::dev_print!("headers: {}", &send_string);
+    send_string.push_str("\r\n");
+
+    // 헤더 + 바디를 vectored I/O로 한번에 전송 (clone/copy 0회)
+    let writer = response.body_mut();
+    let data = writer.body.as_bytes().unwrap_or_default();
+    #[cfg(feature = "vectored_io")]
+    {
+        use std::io::IoSlice;
+        let bufs = [IoSlice::new(send_string.as_bytes()), IoSlice::new(data)];
+        writer.stream.send_vectored(&bufs).await?;
+    }
+    #[cfg(not(feature = "vectored_io"))]
+    {
+        writer.stream.send_bytes(send_string.as_bytes()).await?;
+        writer.stream.send_bytes(data).await?;
+    }
+    Ok(())
+}
+
+/// 스트리밍 body 의 경계를 정한다. HTTP/1.0 이면 `Connection: close` 를 붙이므로 `response_head` 전에 부른다.
+fn stream_framing<B>(response: &mut Response<B>) -> Result<BodyFraming, SendableError> {
+    use http::header::{CONNECTION, CONTENT_LENGTH};
+    if let Some(len) = response.headers().get(CONTENT_LENGTH) {
+        return Ok(BodyFraming::Length(len.to_str()?.trim().parse()?));
+    }
+    if response.version() >= http::Version::HTTP_11 {
+        return Ok(BodyFraming::Chunked);
+    }
+    response
+        .headers_mut()
+        .insert(CONNECTION, HeaderValue::from_static("close"));
+    Ok(BodyFraming::Close)
+}
+
+/// 나머지 헤더를 붙여 보내고, body 를 쓸 `BodyWriter` 를 돌려준다.
+async fn start_body<B>(
+    response: &mut Response<B>,
+    mut send_string: String,
+    framing: BodyFraming,
+) -> Result<BodyWriter<'_>, SendableError>
+where
+    B: ResponseTarget + Send,
+{
+    if *response.body_mut().sink().1 != SinkState::Idle {
+        return Err("response has already been sent".into());
+    }
+    for (key, value) in response.headers().iter() {
+        write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
+    }
+    if framing == BodyFraming::Chunked {
+        send_string.push_str("transfer-encoding: chunked\r\n");
+    }
+    send_string.push_str("\r\n");
+
+    let (stream, state) = response.body_mut().sink();
+    stream.send_bytes(send_string.as_bytes()).await?;
+    Ok(BodyWriter::new(stream, state, framing))
+}
+
+/// 스트리밍 body 를 다 읽을 때까지 항목마다 보낸다.
+async fn send_stream_body<B>(
+    response: &mut Response<B>,
+    send_string: String,
+    framing: BodyFraming,
+    mut stream: BodyStream,
+) -> Result<(), SendableError>
+where
+    B: ResponseTarget + Send,
+{
+    let mut writer = start_body(response, send_string, framing).await?;
+    while let Some(chunk) = stream.next().await {
+        writer.write_all(&chunk?).await?;
+    }
+    writer.finish().await?;
+    Ok(())
+}
+
+/// 대용량 파일을 압축하며 chunked 로 보낸다 — 압축 후 길이를 미리 알 수 없어서.
+#[cfg(all(feature = "compression", feature = "response_file"))]
+async fn send_compressed_file<W>(
+    stream: &mut W,
+    file: &mut tokio::fs::File,
+    mut encoder: Encoder,
+    buf_size: usize,
+) -> Result<(), SendableError>
+where
+    W: SendBytes + Send,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut buffer = vec![0u8; buf_size];
+    loop {
+        let len = file.read(&mut buffer).await?;
+        if len == 0 {
+            break;
+        }
+        send_chunk(stream, &encoder.write(&buffer[..len])?).await?;
+    }
+    send_chunk(stream, &encoder.finish()?).await?;
+    stream.send_bytes(b"0\r\n\r\n").await
+}
+
+/// chunk 하나를 보낸다. 빈 데이터는 마지막 chunk 로 읽히므로 건너뛴다.
+#[cfg(all(feature = "compression", feature = "response_file"))]
+async fn send_chunk<W>(stream: &mut W, data: &[u8]) -> Result<(), SendableError>
+where
+    W: SendBytes + Send,
+{
+    if data.is_empty() {
+        return Ok(());
+    }
+    let mut frame = format!("{:x}\r\n", data.len()).into_bytes();
+    frame.extend_from_slice(data);
+    frame.extend_from_slice(b"\r\n");
+    stream.send_bytes(&frame).await
+}
+
+// 압축을 켜면 파일 응답도 압축 협상 값을 쓴다 (coding 별 `ETag`)
+#[cfg(all(feature = "response_file", feature = "compression"))]
+use CompressionSource as FileTarget;
+#[cfg(all(feature = "response_file", not(feature = "compression")))]
+use ResponseTarget as FileTarget;
+
+/// 파일 응답의 Range / 조건부 요청 처리에 쓰는 writer 쪽 값 — `Writer` / `ArenaWriter` 공통.
+#[cfg(feature = "response_file")]
+trait FileResponse: FileTarget {
+    fn file(&self) -> Option<&FileBody>;
+    fn range_request(&self) -> Option<&RangeRequest>;
+    fn preconditions(&self) -> Option<&Preconditions>;
+}
+
+#[cfg(feature = "response_file")]
+impl FileResponse for Writer {
+    fn file(&self) -> Option<&FileBody> {
+        Writer::file(self)
+    }
+
+    fn range_request(&self) -> Option<&RangeRequest> {
+        self.range.as_deref()
+    }
+
+    fn preconditions(&self) -> Option<&Preconditions> {
+        self.preconditions.as_deref()
+    }
+}
+
+#[cfg(all(feature = "response_file", feature = "arena"))]
+impl FileResponse for ArenaWriter {
+    fn file(&self) -> Option<&FileBody> {
+        ArenaWriter::file(self)
+    }
+
+    fn range_request(&self) -> Option<&RangeRequest> {
+        self.range.as_deref()
+    }
+
+    fn preconditions(&self) -> Option<&Preconditions> {
+        self.preconditions.as_deref()
+    }
+}
+
+/// 조건부 요청 / Range 평가로 정해진 파일 응답.
+#[cfg(feature = "response_file")]
+enum FileOutcome {
+    /// 304 / 412 — body 없음.
+    Precondition(Precondition),
+    /// 206 / 416. 길이를 잰 그 파일 (캐시 `Arc<[u8]>` 또는 mmap) 을 보낼 때 그대로 자른다.
+    Partial(Partial, FileLoadResult),
+}
+
+/// 파일 응답에 `Accept-Ranges` / `ETag` / `Last-Modified` 를 붙이고 조건 헤더와 `Range` 를
+/// 평가한다. 전체 파일 (200) 을 보낼 게 아니면 status 를 바꾸고 결과를 돌려준다.
+#[cfg(feature = "response_file")]
+fn file_outcome<B: FileResponse>(
+    response: &mut Response<B>,
+) -> Result<Option<FileOutcome>, SendableError> {
+    use http::header::{ACCEPT_RANGES, ETAG, LAST_MODIFIED};
+    use http::{HeaderValue, StatusCode};
+
+    response
+        .headers_mut()
+        .entry(ACCEPT_RANGES)
+        .or_insert(HeaderValue::from_static("bytes"));
+    let Some(path) = response.body().file().map(|f| f.path().to_path_buf()) else {
+        return Ok(None);
+    };
+    let validators = ZeroCopyCache::global().validators(&path).ok();
+
+    // 206 / 416 은 압축하지 않으므로 `If-Range` 는 원본 validator 로 본다
+    let partial = match response.body().range_request() {
+        Some(request) => {
+            let file = ZeroCopyCache::global().load_file(&path)?;
+            request
+                .evaluate(file.len() as u64, validators.as_ref())
+                .map(|partial| (partial, file))
+        }
+        None => None,
+    };
+    // 전체 파일을 압축해 보낼 거면 그 coding 의 `ETag` 를 쓰고 조건 헤더도 그걸로 평가한다
+    #[cfg(feature = "compression")]
+    let validators = match (&partial, validators) {
+        (None, Some(validators)) => match file_encoding(response) {
+            Some(encoding) => Some(validators.encoded(encoding.as_str())),
+            None => Some(validators),
+        },
+        (_, validators) => validators,
+    };
+
+    if let Some(validators) = &validators {
+        let last_modified = httpdate::fmt_http_date(validators.last_modified);
+        let headers = response.headers_mut();
+        headers
+            .entry(ETAG)
+            .or_insert(HeaderValue::from_str(&validators.etag)?);
+        headers
+            .entry(LAST_MODIFIED)
+            .or_insert(HeaderValue::from_str(&last_modified)?);
+    }
+
+    let precondition = match (response.body().preconditions(), &validators) {
+        (Some(preconditions), Some(validators)) => preconditions.evaluate(validators),
+        _ => None,
+    };
+    if let Some(precondition) = precondition {
+        *response.status_mut() = match precondition {
+            Precondition::NotModified => StatusCode::NOT_MODIFIED,
+            Precondition::Failed => StatusCode::PRECONDITION_FAILED,
+        };
+        return Ok(Some(FileOutcome::Precondition(precondition)));
+    }
 
-    let content_length = format!("content-length: {}\r\n", length);
-    crate::dev_print!("content-length: {}\n", &content_length);
-    (body.into(), content_length)
+    let Some((partial, file)) = partial else {
+        return Ok(None);
+    };
+    *response.status_mut() = match partial {
+        Partial::Unsatisfiable => StatusCode::RANGE_NOT_SATISFIABLE,
+        _ => StatusCode::PARTIAL_CONTENT,
+    };
+    Ok(Some(FileOutcome::Partial(partial, file)))
+}
+
+/// 전체 파일 (200) 응답에 `compression::choose` 가 고를 coding. 스트리밍 파일은 HTTP/1.1 에서만
+/// 압축한다 (`responser` / `responser_arena` 와 같은 조건).
+#[cfg(all(feature = "response_file", feature = "compression"))]
+fn file_encoding<B: FileResponse>(response: &Response<B>) -> Option<Encoding> {
+    let file = response.body().file()?;
+    if !file.is_zero_copy() && response.version() != http::Version::HTTP_11 {
+        return None;
+    }
+    compression::select(
+        &response.body().options().compression_option,
+        response.body().accept_encoding(),
+        http::StatusCode::OK,
+        response.headers(),
+        Some(&file.content_type()),
+        file.len() as usize,
+    )
+}
+
+/// `file_outcome` 결과를 보낸다. 304 / 412 는 헤더만, 206 / 416 은 `send_partial_file`.
+#[cfg(feature = "response_file")]
+async fn send_file_outcome<B: FileResponse + Send>(
+    response: &mut Response<B>,
+    mut send_string: String,
+    outcome: FileOutcome,
+) -> Result<(), SendableError> {
+    let precondition = match outcome {
+        FileOutcome::Partial(partial, file) => {
+            return send_partial_file(response, send_string, partial, file).await;
+        }
+        FileOutcome::Precondition(precondition) => precondition,
+    };
+    for (key, value) in response.headers().iter() {
+        write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
+    }
+    // 304 는 body 가 없다고 정해져 있어 길이를 보내지 않는다
+    if precondition == Precondition::Failed {
+        send_string.push_str("content-length: 0\r\n");
+    }
+    send_string.push_str("\r\n");
+
+    let stream = response.body_mut().stream_mut();
+    stream.send_bytes(send_string.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// 206 / 416 응답을 보낸다. 구간은 `file` 에서 복사 없이 잘라 vectored I/O 로 헤더와 함께 보낸다.
+#[cfg(feature = "response_file")]
+async fn send_partial_file<B: FileResponse + Send>(
+    response: &mut Response<B>,
+    mut send_string: String,
+    partial: Partial,
+    file: FileLoadResult,
+) -> Result<(), SendableError> {
+    let data = file.as_bytes();
+    let complete = data.len() as u64;
+    let content_type = response
+        .body()
+        .file()
+        .map(FileBody::content_type)
+        .unwrap_or_default();
+    let slice = |start: u64, end: u64| &data[start as usize..end as usize];
+
+    let multipart;
+    let mut pieces: Vec<&[u8]> = Vec::new();
+    match &partial {
+        Partial::Single(range) => {
+            write!(send_string, "Content-Type: {}\r\n", content_type)?;
+            write!(
+                send_string,
+                "Content-Range: {}\r\n",
+                range.content_range(complete)
+            )?;
+            pieces.push(slice(range.start, range.end));
+        }
+        Partial::Multiple(ranges) => {
+            multipart = Multipart::new(ranges, &content_type, complete);
+            write!(send_string, "Content-Type: {}\r\n", multipart.content_type)?;
+            for (head, range) in &multipart.parts {
+                pieces.push(head.as_bytes());
+                pieces.push(slice(range.start, range.end));
+            }
+            pieces.push(multipart.closing.as_bytes());
+        }
+        Partial::Unsatisfiable => {
+            write!(send_string, "Content-Range: bytes */{}\r\n", complete)?;
+        }
+    }
+
+    for (key, value) in response.headers().iter() {
+        write!(send_string, "{}: {}\r\n", key.as_str(), value.to_str()?)?;
+    }
+    let content_length: usize = pieces.iter().map(|p| p.len()).sum();
+    write!(send_string, "content-length: {}\r\n", content_length)?;
+    send_string.push_str("\r\n");
+
+    let stream = response.body_mut().stream_mut();
+    #[cfg(feature = "vectored_io")]
+    {
+        use std::io::IoSlice;
+        let bufs: Vec<IoSlice> = std::iter::once(send_string.as_bytes())
+            .chain(pieces)
+            .map(IoSlice::new)
+            .collect();
+        stream.send_vectored(&bufs).await?;
+    }
+    #[cfg(not(feature = "vectored_io"))]
+    {
+        stream.send_bytes(send_string.as_bytes()).await?;
+        for piece in pieces {
+            stream.send_bytes(piece).await?;
+        }
+    }
+    stream.flush().await?;
+    Ok(())
 }
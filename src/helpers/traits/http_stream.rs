@@ -1,18 +1,23 @@
 use crate::dev_print;
 use async_trait::async_trait;
 use http::header::CONTENT_TYPE;
-use http::{HeaderMap, Request, Response};
+use http::{HeaderMap, Request, Response, StatusCode};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::io::{self, AsyncReadExt};
+use tokio::io::{self, AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 #[cfg(feature = "tokio_rustls")]
 use tokio_rustls::server::TlsStream;
 
+use crate::chunked::{read_chunked_body, trim_ows};
+#[cfg(feature = "decompression")]
+use crate::decompress::{self, ContentCoding};
+use crate::proxy_protocol::read_preamble;
+use crate::socket::{Peer, Stream};
 #[cfg(feature = "arena")]
 use crate::{ArenaBody, ArenaWriter};
-use crate::{Body, Options, SendableError, Writer};
+use crate::{AutoParseResult, Body, Options, SendableError, Writer};
 
 pub struct Form {
     /// 모든 텍스트 필드(`(name, value)`). 이전 0.13.x의 `text: (String, String)`은
@@ -342,15 +347,7 @@ impl StreamHttp for TcpStream {
         options: Arc<Options>,
         peer: SocketAddr,
     ) -> Result<(Request<Body>, Response<Writer>), SendableError> {
-        self.set_nodelay(options.no_delay)?;
-
-        let (bytes, stream) = get_bytes_from_reader(self, &options).await?;
-
-        let request = get_request(bytes).await?;
-
-        Ok(get_parse_result_from_request(
-            request, stream, options, peer,
-        )?)
+        parse_buffered(Stream::Tcp(self), options, Peer::Tcp(peer)).await
     }
 
     async fn parse_request_streaming(
@@ -358,49 +355,86 @@ impl StreamHttp for TcpStream {
         options: Arc<Options>,
         peer: SocketAddr,
     ) -> Result<(Request<Body>, Response<Writer>), SendableError> {
-        self.set_nodelay(options.no_delay)?;
+        parse_streaming(Stream::Tcp(self), options, Peer::Tcp(peer)).await
+    }
+}
 
-        let HeaderReadResult {
-            header_bytes,
-            leftover,
-            content_length,
-            stream,
-        } = read_headers_only(self, &options).await?;
+/// `StreamHttp::parse_request` 구현 (TCP / UDS 공용).
+pub(crate) async fn parse_buffered(
+    stream: Stream,
+    options: Arc<Options>,
+    mut peer: Peer,
+) -> Result<(Request<Body>, Response<Writer>), SendableError> {
+    stream.set_nodelay(options.no_delay)?;
 
-        // 1) 헤더만으로 Request<Body> 빌드 (parser는 body 부분 비어있어도 OK)
-        let request_buffered = get_request(header_bytes).await?;
-        let (parts, _empty_body) = request_buffered.into_parts();
+    let FullRequest {
+        bytes,
+        trailers,
+        stream,
+        ..
+    } = get_bytes_from_reader(stream, &options, &mut peer).await?;
 
-        // 2) stream을 split — read half는 Body로, write half는 Writer로
-        let (read_half, write_half) = stream.into_split();
+    let mut request = match get_request(bytes, options.max_headers).await {
+        Ok(request) => request,
+        Err(e) => return Err(fail(stream, &options, e).await),
+    };
+    request.body_mut().trailers = trailers.map(Box::new);
 
-        // 3) Body를 streaming 모드로 재구성
-        let streaming_body = Body::new_streaming(
-            leftover,
-            read_half,
-            content_length,
-            Some(peer),
-            options.max_body_size,
-        );
-        let request = Request::from_parts(parts, streaming_body);
-        let version = request.version();
-
-        // 4) Writer는 write half + 빈 응답
-        Ok((
-            request,
-            Response::builder()
-                .version(version)
-                .header(CONTENT_TYPE, "application/json")
-                .status(400)
-                .body(Writer {
-                    stream: write_half,
-                    body: String::new(),
-                    bytes: vec![],
-                    use_file: false,
-                    options,
-                })?,
-        ))
-    }
+    get_parse_result_from_request(request, stream, options, peer)
+}
+
+/// `StreamHttp::parse_request_streaming` 구현 (TCP / UDS 공용).
+pub(crate) async fn parse_streaming(
+    stream: Stream,
+    options: Arc<Options>,
+    mut peer: Peer,
+) -> Result<(Request<Body>, Response<Writer>), SendableError> {
+    stream.set_nodelay(options.no_delay)?;
+
+    let HeaderReadResult {
+        header_bytes,
+        leftover,
+        framing,
+        stream,
+    } = read_headers_only(stream, &options, &mut peer).await?;
+
+    // 1) 헤더만으로 Request<Body> 빌드 (parser는 body 부분 비어있어도 OK)
+    let request_buffered = match get_request(header_bytes, options.max_headers).await {
+        Ok(request) => request,
+        Err(e) => return Err(fail(stream, &options, e).await),
+    };
+    let (parts, _empty_body) = request_buffered.into_parts();
+
+    // 2) stream을 split — read half는 Body로, write half는 Writer로
+    let (read_half, write_half) = stream.into_split();
+
+    // 3) Body를 streaming 모드로 재구성
+    let streaming_body = Body::new_streaming(
+        leftover,
+        read_half,
+        framing,
+        peer.addr(),
+        options.max_body_size,
+        options.max_trailer_size,
+    );
+    let mut request = Request::from_parts(parts, streaming_body);
+    peer.tag(&mut request, &options);
+    let version = request.version();
+
+    // 4) Writer는 write half + 빈 응답
+    let writer = Writer::new(write_half, options);
+    #[cfg(feature = "compression")]
+    let writer = writer.with_accept_encoding(request.headers());
+    #[cfg(feature = "response_file")]
+    let writer = writer.with_file_request(request.method(), request.headers());
+    Ok((
+        request,
+        Response::builder()
+            .version(version)
+            .header(CONTENT_TYPE, "application/json")
+            .status(400)
+            .body(writer)?,
+    ))
 }
 
 #[cfg(feature = "tokio_rustls")]
@@ -411,16 +445,7 @@ impl StreamHttp for TlsStream<TcpStream> {
         options: Arc<Options>,
         peer: SocketAddr,
     ) -> Result<(Request<Body>, Response<Writer>), SendableError> {
-        let stream = self.into_inner().0;
-        stream.set_nodelay(options.no_delay)?;
-
-        let (bytes, stream) = get_bytes_from_reader(stream, &options).await?;
-
-        let request = get_request(bytes).await?;
-
-        Ok(get_parse_result_from_request(
-            request, stream, options, peer,
-        )?)
+        StreamHttp::parse_request(self.into_inner().0, options, peer).await
     }
 
     async fn parse_request_streaming(
@@ -440,16 +465,23 @@ impl StreamHttp for TlsStream<TcpStream> {
 
 pub(crate) fn get_parse_result_from_request(
     mut request: Request<Body>,
-    stream: TcpStream,
+    stream: Stream,
     options: Arc<Options>,
-    peer: SocketAddr,
+    peer: Peer,
 ) -> Result<(Request<Body>, Response<Writer>), SendableError> {
     let version = request.version();
-    request.body_mut().ip = Some(peer);
-
-    // 0.14.0: body는 이미 buffered 모드로 다 읽혔으므로 read half는 버리고
-    // write half 만 Writer 로. (streaming 경로는 parse_request_streaming 별도 함수.)
-    let (_read_half, write_half) = stream.into_split();
+    request.body_mut().ip = peer.addr();
+    peer.tag(&mut request, &options);
+
+    // 0.14.0: body는 이미 buffered 모드로 다 읽혔으므로 read half는 Writer에 보관만 하고
+    // (keep-alive 재조립용) write half 로 응답. (streaming 경로는 parse_request_streaming 별도 함수.)
+    let (read_half, write_half) = stream.into_split();
+    let mut writer = Writer::new(write_half, options);
+    writer.read_half = Some(read_half);
+    #[cfg(feature = "compression")]
+    let writer = writer.with_accept_encoding(request.headers());
+    #[cfg(feature = "response_file")]
+    let writer = writer.with_file_request(request.method(), request.headers());
 
     Ok((
         request,
@@ -457,32 +489,26 @@ pub(crate) fn get_parse_result_from_request(
             .version(version)
             .header(CONTENT_TYPE, "application/json")
             .status(400)
-            .body(Writer {
-                stream: write_half,
-                body: String::new(),
-                bytes: vec![],
-                use_file: false,
-                options,
-            })?,
+            .body(writer)?,
     ))
 }
 /// `read_headers_only` 결과 — 헤더 부분 + body 앞부분으로 미리 들어온 leftover +
-/// Content-Length(있을 때) + 후속 stream.
+/// body 경계 (`Framing`) + 후속 stream.
 pub(crate) struct HeaderReadResult {
     pub header_bytes: Vec<u8>,
     pub leftover: Vec<u8>,
-    pub content_length: Option<usize>,
-    pub stream: TcpStream,
+    pub framing: Framing,
+    pub stream: Stream,
 }
 
 /// `read_headers_only` 이후 남은 body를 마저 읽어 단일 Vec로 반환.
 /// `parse_request_auto` 의 arena 경로에서 사용 (작은 요청은 통째 buffered 가 필요).
 pub(crate) async fn read_remaining_body(
     initial_leftover: Vec<u8>,
-    mut stream: TcpStream,
+    mut stream: Stream,
     content_length: Option<usize>,
     options: &Options,
-) -> Result<(Vec<u8>, TcpStream), SendableError> {
+) -> Result<(Vec<u8>, Stream), SendableError> {
     const BODY_READ_CHUNK: usize = 64 * 1024;
     let total_expected = content_length.unwrap_or(0);
     let mut final_buffer = initial_leftover;
@@ -502,29 +528,43 @@ pub(crate) async fn read_remaining_body(
         )
         .await
         {
+            // 덜 읽은 body 를 완성된 것으로 넘기면 나머지가 다음 요청으로 해석된다 — 연결을 닫는다
+            Ok(Ok(0)) => return Err(fail(stream, options, ParseError::BodyIncomplete).await),
             Ok(Ok(n)) => {
-                if n == 0 {
-                    break;
-                }
                 total_read += n;
                 retry_count = 0;
             }
-            _ => {
-                retry_count += 1;
-            }
+            Ok(Err(e)) => return Err(ParseError::Io(e).into()),
+            Err(_) => retry_count += 1,
         }
     }
+    if total_read < total_expected {
+        return Err(fail(stream, options, ParseError::BodyTimeout).await);
+    }
     final_buffer.truncate(total_read);
     Ok((final_buffer, stream))
 }
 
 /// **헤더만 읽고** body는 socket에 그대로 남겨둔 채 반환한다.
 /// `parse_request_streaming` 전용. body 부분이 미리 들어와 있으면 `leftover` 로 분리.
+/// 연결의 첫 요청용 — `Options::proxy_protocol` 이 켜져 있으면 PROXY preamble 을 먼저 소비하고
+/// `peer` 를 원래 클라이언트로 바꾼다.
 pub(crate) async fn read_headers_only(
-    mut stream: TcpStream,
+    mut stream: Stream,
     options: &Options,
+    peer: &mut Peer,
+) -> Result<HeaderReadResult, SendableError> {
+    let prefix = read_preamble(&mut stream, options, peer).await?;
+    read_headers_with_prefix(stream, options, prefix).await
+}
+
+/// `read_headers_only` 와 동일하되, 이전 요청을 읽다가 이미 받아둔 바이트(`prefix`)를
+/// 버퍼 앞에 두고 시작한다. prefix 안에 헤더가 통째로 있으면 socket read 없이 끝난다.
+async fn read_headers_with_prefix(
+    mut stream: Stream,
+    options: &Options,
+    prefix: Vec<u8>,
 ) -> Result<HeaderReadResult, SendableError> {
-    const MAX_HEADER_SIZE: usize = 64 * 1024;
     const INITIAL_READ_SIZE: usize = 4096;
     const HEADER_END_MARKER: &[u8] = b"\r\n\r\n";
 
@@ -533,9 +573,12 @@ pub(crate) async fn read_headers_only(
         n => n,
     };
 
-    let mut buffer: Vec<u8> = Vec::with_capacity(initial_read);
-    let mut content_length = None;
-    let mut header_end_pos: Option<usize> = None;
+    let mut buffer: Vec<u8> = prefix;
+    buffer.reserve(initial_read);
+    let mut header_end_pos = match scan_header_end(&buffer, 0) {
+        Ok(end) => end,
+        Err(e) => return Err(fail(stream, options, ParseError::Framing(e)).await),
+    };
     let mut retry_count = 0;
     let max_retry = options.read_max_retry;
     let read_timeout = Duration::from_millis(options.read_timeout_milliseconds);
@@ -544,13 +587,17 @@ pub(crate) async fn read_headers_only(
         .unwrap_or_else(|| options.read_timeout_milliseconds * (max_retry as u64 + 1));
     let header_start = Instant::now();
 
-    while header_end_pos.is_none() && buffer.len() < MAX_HEADER_SIZE && retry_count < max_retry {
+    while header_end_pos.is_none()
+        && buffer.len() < options.max_header_size
+        && retry_count < max_retry
+    {
         if header_start.elapsed().as_millis() as u64 > header_deadline_ms {
-            return Err(format!(
-                "Header read deadline exceeded ({} ms) — possible slowloris",
-                header_deadline_ms
+            return Err(fail(
+                stream,
+                options,
+                ParseError::HeaderDeadline(header_deadline_ms),
             )
-            .into());
+            .await);
         }
         let current_len = buffer.len();
         let chunk = if current_len == 0 {
@@ -558,7 +605,7 @@ pub(crate) async fn read_headers_only(
         } else {
             determine_next_read_size(current_len)
         };
-        let new_size = (current_len + chunk).min(MAX_HEADER_SIZE);
+        let new_size = (current_len + chunk).min(options.max_header_size);
         buffer.resize(new_size, 0);
 
         match tokio::time::timeout(read_timeout, stream.read(&mut buffer[current_len..])).await {
@@ -571,11 +618,13 @@ pub(crate) async fn read_headers_only(
                 buffer.truncate(new_len);
 
                 let search_start = current_len.saturating_sub(HEADER_END_MARKER.len() - 1);
-                if let Some(rel) = find_header_end_optimized(&buffer[search_start..]) {
-                    let end = search_start + rel + 4;
-                    header_end_pos = Some(end);
-                    content_length = extract_content_length_simple(&buffer[..end]);
-                    break;
+                match scan_header_end(&buffer, search_start) {
+                    Ok(Some(end)) => {
+                        header_end_pos = Some(end);
+                        break;
+                    }
+                    Ok(None) => {}
+                    Err(e) => return Err(fail(stream, options, ParseError::Framing(e)).await),
                 }
                 retry_count = 0;
             }
@@ -583,7 +632,7 @@ pub(crate) async fn read_headers_only(
                 buffer.truncate(current_len);
                 continue;
             }
-            Ok(Err(e)) => return Err(e.into()),
+            Ok(Err(e)) => return Err(ParseError::Io(e).into()),
             Err(_) => {
                 buffer.truncate(current_len);
                 retry_count += 1;
@@ -592,57 +641,252 @@ pub(crate) async fn read_headers_only(
         }
     }
 
-    if buffer.is_empty() {
-        return Err("No data received".into());
-    }
-
     let header_end = match header_end_pos {
         Some(p) => p,
-        None => return Err("Headers incomplete: \\r\\n\\r\\n not found".into()),
+        None => {
+            let error = incomplete_headers(&buffer, options, retry_count >= max_retry);
+            return Err(fail(stream, options, error).await);
+        }
+    };
+    if let Err(e) = check_header_limits(&buffer[..header_end], options) {
+        return Err(fail(stream, options, e).await);
+    }
+    let framing = match parse_framing(&buffer[..header_end]) {
+        Ok(framing) => framing,
+        Err(e) => return Err(fail(stream, options, ParseError::Framing(e)).await),
     };
 
     // 광고된 Content-Length가 cap을 넘으면 streaming 시작 전에 거부.
-    if let (Some(cl), Some(cap)) = (content_length, options.max_body_size) {
+    if let (Some(cl), Some(cap)) = (framing.content_length, options.max_body_size) {
         if cl > cap {
-            return Err(format!(
-                "Request body too large: content-length={} exceeds max_body_size={}",
-                cl, cap
-            )
-            .into());
+            let error = ParseError::ContentLengthTooLarge {
+                content_length: cl,
+                limit: cap,
+            };
+            return Err(fail(stream, options, error).await);
         }
     }
 
+    #[cfg(feature = "decompression")]
+    let framing = match with_content_coding(&buffer[..header_end], framing, options) {
+        Ok(framing) => framing,
+        Err(e) => return Err(fail(stream, options, e).await),
+    };
+
     // 헤더 read 시 body 앞부분이 같이 들어왔을 수 있음 → leftover로 분리
     let leftover = buffer.split_off(header_end);
     let header_bytes = buffer;
+    // streaming body 는 읽으면서 해제하므로 헤더를 미리 맞춰 둔다
+    #[cfg(feature = "decompression")]
+    let header_bytes = match framing.content_coding {
+        Some(_) => decompress::strip_coding_headers(&header_bytes),
+        None => header_bytes,
+    };
 
     Ok(HeaderReadResult {
         header_bytes,
         leftover,
-        content_length,
+        framing,
         stream,
     })
 }
 
+/// keep-alive 연결(`Connection`) 전용 헤더 읽기. pipelining 클라이언트는 응답을 기다리지
+/// 않고 다음 요청을 이어 보내므로, 헤더 read에 이번 요청 body 뒤의 바이트까지 딸려올 수 있다.
+/// 이번 요청 body 길이(Content-Length, 없으면 0)를 넘는 부분은 `leftover` 에서 잘라내
+/// 두 번째 반환값으로 돌려준다 — 호출자는 이를 다음 요청의 `prefix` 로 넘긴다.
+/// Content-Length 가 없는 요청은 body 길이 0 으로 확정해 `content_length = Some(0)`
+/// (RFC 9112 §6.3 — 연결 종료까지 읽으면 다음 요청을 body로 삼켜버림).
+///
+/// chunked 요청은 종료 chunk 를 디코드해 봐야 경계를 알 수 있으므로 여기서 자르지 않는다 —
+/// body 를 읽는 쪽 (`read_full_request` / `Body`) 이 종료 chunk 뒤 바이트를 돌려준다.
+pub(crate) async fn read_pipelined_headers(
+    stream: Stream,
+    options: &Options,
+    prefix: Vec<u8>,
+) -> Result<(HeaderReadResult, Vec<u8>), SendableError> {
+    stream.set_nodelay(options.no_delay)?;
+    let mut head = read_headers_with_prefix(stream, options, prefix).await?;
+    if head.framing.chunked {
+        return Ok((head, Vec::new()));
+    }
+    let body_len = *head.framing.content_length.get_or_insert(0);
+    let surplus = if head.leftover.len() > body_len {
+        head.leftover.split_off(body_len)
+    } else {
+        Vec::new()
+    };
+    Ok((head, surplus))
+}
+
+/// `read_full_request` / `get_bytes_from_reader` 결과 — 헤더 + body 를 합친 단일 버퍼 + 후속 stream.
+#[derive(Debug)]
+pub(crate) struct FullRequest {
+    pub bytes: Vec<u8>,
+    /// 헤더 길이 (= body 시작 오프셋).
+    #[cfg(any(feature = "arena", feature = "websocket"))]
+    pub header_end: usize,
+    /// chunked body 의 trailer. chunked 가 아니면 `None`.
+    pub trailers: Option<HeaderMap>,
+    /// chunked body 종료 뒤에 딸려 읽힌 바이트 (pipelining 된 다음 요청).
+    pub surplus: Vec<u8>,
+    pub stream: Stream,
+}
+
+/// `read_headers_only` 결과를 받아 body를 마저 읽고 헤더+body 단일 버퍼로 합친다.
+/// chunked body 는 디코드해서 붙인다.
+pub(crate) async fn read_full_request(
+    head: HeaderReadResult,
+    options: &Options,
+) -> Result<FullRequest, SendableError> {
+    let HeaderReadResult {
+        header_bytes,
+        leftover,
+        framing,
+        mut stream,
+    } = head;
+    send_continue(&mut stream, &framing, leftover.len()).await?;
+    let (body, trailers, surplus, stream) = if framing.chunked {
+        let (body, trailers, surplus, stream) =
+            read_chunked_body(leftover, stream, options).await?;
+        (body, Some(trailers), surplus, stream)
+    } else {
+        let (body, stream) =
+            read_remaining_body(leftover, stream, framing.content_length, options).await?;
+        (body, None, Vec::new(), stream)
+    };
+    let header_end = header_bytes.len();
+    let mut bytes = header_bytes;
+    bytes.extend_from_slice(&body);
+    #[cfg(feature = "decompression")]
+    let header_end = match decode_content(&mut bytes, header_end, &framing, options) {
+        Ok(end) => end,
+        Err(e) => return Err(fail(stream, options, e).await),
+    };
+    // 헤더 길이는 arena / websocket 경로만 쓴다
+    #[cfg(not(any(feature = "arena", feature = "websocket")))]
+    let _ = header_end;
+    Ok(FullRequest {
+        bytes,
+        #[cfg(any(feature = "arena", feature = "websocket"))]
+        header_end,
+        trailers,
+        surplus,
+        stream,
+    })
+}
+
+/// `Accept::parse_request_auto_with_cap` 구현.
+/// 헤더만 먼저 읽고 CL ≤ `arena_cap` 이면 arena (zero-copy parsing), 초과/미상이면 streaming.
+/// arena feature 비활성화 시 항상 `AutoParseResult::Streaming` 반환.
+pub(crate) async fn parse_request_auto(
+    stream: Stream,
+    options: Arc<Options>,
+    mut peer: Peer,
+    arena_cap: usize,
+) -> Result<AutoParseResult, SendableError> {
+    stream.set_nodelay(options.no_delay)?;
+    let head = read_headers_only(stream, &options, &mut peer).await?;
+    parse_request_auto_from_head(head, options, peer, arena_cap).await
+}
+
+/// `parse_request_auto` 의 헤더 이후 단계. `Connection::next_request_auto_with_cap` 도 공유.
+pub(crate) async fn parse_request_auto_from_head(
+    head: HeaderReadResult,
+    options: Arc<Options>,
+    peer: Peer,
+    arena_cap: usize,
+) -> Result<AutoParseResult, SendableError> {
+    // arena 경로 분기: feature 있고, CL 있고, cap 이하
+    #[cfg(feature = "arena")]
+    if let Some(cl) = head.framing.content_length {
+        if cl <= arena_cap {
+            // 남은 body 마저 읽어 헤더 + body 합친 뒤 ArenaBody 생성 (bump 안으로 복사)
+            let FullRequest {
+                bytes,
+                header_end,
+                stream,
+                ..
+            } = read_full_request(head, &options).await?;
+            let arena_body = ArenaBody::new(&bytes, header_end, header_end);
+
+            let request = match parse_http_request_arena(arena_body, options.max_headers) {
+                Ok(request) => request,
+                Err(e) => return Err(fail(stream, &options, e).await),
+            };
+            let (request, response) =
+                get_parse_result_arena_writer(request, stream, options, peer)?;
+            return Ok(AutoParseResult::Arena { request, response });
+        }
+    }
+    let HeaderReadResult {
+        header_bytes,
+        leftover,
+        framing,
+        stream,
+    } = head;
+
+    // arena_cap 인자가 streaming 경로에선 안 쓰이지만 BREAKING 인자 없애지 않으려고 명시 변수화.
+    let _ = arena_cap;
+
+    // streaming 경로
+    let request_buffered = match get_request(header_bytes, options.max_headers).await {
+        Ok(request) => request,
+        Err(e) => return Err(fail(stream, &options, e).await),
+    };
+    let (parts, _empty_body) = request_buffered.into_parts();
+
+    let (read_half, write_half) = stream.into_split();
+    let body = Body::new_streaming(
+        leftover,
+        read_half,
+        framing,
+        peer.addr(),
+        options.max_body_size,
+        options.max_trailer_size,
+    );
+    let mut request = Request::from_parts(parts, body);
+    peer.tag(&mut request, &options);
+    let version = request.version();
+
+    let writer = Writer::new(write_half, options);
+    #[cfg(feature = "compression")]
+    let writer = writer.with_accept_encoding(request.headers());
+    #[cfg(feature = "response_file")]
+    let writer = writer.with_file_request(request.method(), request.headers());
+    let response = Response::builder()
+        .version(version)
+        .header(CONTENT_TYPE, "application/json")
+        .status(400)
+        .body(writer)?;
+    Ok(AutoParseResult::Streaming { request, response })
+}
+
+/// 요청 전체 (헤더 + Content-Length 만큼의 body) 를 한 버퍼로 읽는다. 연결의 첫 요청용이며
+/// PROXY preamble 처리는 `read_headers_only` 와 같다. 단일 요청 경로라 `surplus` 는 항상 비어 있다.
 pub(crate) async fn get_bytes_from_reader(
-    mut stream: TcpStream,
+    mut stream: Stream,
     options: &Options,
-) -> Result<(Vec<u8>, TcpStream), SendableError> {
-    const MAX_HEADER_SIZE: usize = 64 * 1024; // 64KB 헤더 cap (RFC 표준 헤더 한도)
+    peer: &mut Peer,
+) -> Result<FullRequest, SendableError> {
     const INITIAL_READ_SIZE: usize = 4096; // 첫 읽기 4KB
     const HEADER_END_MARKER: &[u8] = b"\r\n\r\n";
 
     // 사용자 설정값이 있으면 그것을 첫 읽기로, 아니면 4KB.
-    // MAX_HEADER_SIZE는 cap에만 사용 (이전: min으로도 작동해 매 요청 64KB zero-init).
+    // max_header_size는 cap에만 사용 (이전: min으로도 작동해 매 요청 64KB zero-init).
     let initial_read = match options.read_buffer_size {
         0 => INITIAL_READ_SIZE,
         n => n,
     };
 
-    // 1단계: 점진적 grow로 헤더 읽기 (작은 요청은 4KB 1회로 끝)
-    let mut header_buffer: Vec<u8> = Vec::with_capacity(initial_read);
-    let mut content_length = None;
-    let mut header_end_pos = None;
+    // 1단계: 점진적 grow로 헤더 읽기 (작은 요청은 4KB 1회로 끝).
+    // PROXY preamble 뒤에 딸려 읽힌 바이트가 있으면 그 뒤에 이어 읽는다.
+    let mut header_buffer: Vec<u8> = read_preamble(&mut stream, options, peer).await?;
+    header_buffer.reserve(initial_read);
+    let mut header_end_pos = match scan_header_end(&header_buffer, 0) {
+        Ok(end) => end,
+        Err(e) => return Err(fail(stream, options, ParseError::Framing(e)).await),
+    };
     let mut retry_count = 0;
     let max_retry = options.read_max_retry;
     let read_timeout = Duration::from_millis(options.read_timeout_milliseconds);
@@ -655,15 +899,16 @@ pub(crate) async fn get_bytes_from_reader(
     let header_start = Instant::now();
 
     while header_end_pos.is_none()
-        && header_buffer.len() < MAX_HEADER_SIZE
+        && header_buffer.len() < options.max_header_size
         && retry_count < max_retry
     {
         if header_start.elapsed().as_millis() as u64 > header_deadline_ms {
-            return Err(format!(
-                "Header read deadline exceeded ({} ms) — possible slowloris",
-                header_deadline_ms
+            return Err(fail(
+                stream,
+                options,
+                ParseError::HeaderDeadline(header_deadline_ms),
             )
-            .into());
+            .await);
         }
         let current_len = header_buffer.len();
         let chunk = if current_len == 0 {
@@ -671,7 +916,7 @@ pub(crate) async fn get_bytes_from_reader(
         } else {
             determine_next_read_size(current_len)
         };
-        let new_size = (current_len + chunk).min(MAX_HEADER_SIZE);
+        let new_size = (current_len + chunk).min(options.max_header_size);
         header_buffer.resize(new_size, 0);
 
         match tokio::time::timeout(read_timeout, stream.read(&mut header_buffer[current_len..]))
@@ -689,13 +934,13 @@ pub(crate) async fn get_bytes_from_reader(
 
                     // 새로 추가된 부분부터 헤더 끝 찾기 (중복 스캔 방지)
                     let search_start = current_len.saturating_sub(HEADER_END_MARKER.len() - 1);
-                    if let Some(relative_pos) =
-                        find_header_end_optimized(&header_buffer[search_start..])
-                    {
-                        let end = search_start + relative_pos + 4; // +4 for "\r\n\r\n"
-                        header_end_pos = Some(end);
-                        content_length = extract_content_length_simple(&header_buffer[..end]);
-                        break;
+                    match scan_header_end(&header_buffer, search_start) {
+                        Ok(Some(end)) => {
+                            header_end_pos = Some(end);
+                            break;
+                        }
+                        Ok(None) => {}
+                        Err(e) => return Err(fail(stream, options, ParseError::Framing(e)).await),
                     }
                     retry_count = 0;
                 }
@@ -703,7 +948,7 @@ pub(crate) async fn get_bytes_from_reader(
                     header_buffer.truncate(current_len);
                     continue;
                 }
-                Err(e) => return Err(e.into()),
+                Err(e) => return Err(ParseError::Io(e).into()),
             },
             Err(_) => {
                 header_buffer.truncate(current_len);
@@ -713,23 +958,68 @@ pub(crate) async fn get_bytes_from_reader(
         }
     }
 
-    if header_buffer.is_empty() {
-        return Err("No data received".into());
+    // 헤더 끝 전에 연결이 닫혔으면 best-effort 로 버퍼 전체를 헤더로 본다 (기존 동작 유지).
+    // 아무것도 못 받았거나, 한도까지 찼거나, 시간 초과면 거부.
+    let limits = match header_end_pos {
+        Some(end) => check_header_limits(&header_buffer[..end], options),
+        None => match incomplete_headers(&header_buffer, options, retry_count >= max_retry) {
+            ParseError::HeadersIncomplete => Ok(()),
+            error => Err(error),
+        },
+    };
+    if let Err(e) = limits {
+        return Err(fail(stream, options, e).await);
     }
-
     let header_end = header_end_pos.unwrap_or(header_buffer.len());
+    let framing = match header_end_pos.map(|end| parse_framing(&header_buffer[..end])) {
+        Some(Ok(framing)) => framing,
+        Some(Err(e)) => return Err(fail(stream, options, ParseError::Framing(e)).await),
+        None => Framing::default(),
+    };
+    #[cfg(feature = "decompression")]
+    let framing = match with_content_coding(&header_buffer[..header_end], framing, options) {
+        Ok(framing) => framing,
+        Err(e) => return Err(fail(stream, options, e).await),
+    };
+    let content_length = framing.content_length;
+
+    // chunked body 는 디코드해서 헤더 뒤에 붙인다.
+    // 단일 요청 경로라 종료 chunk 뒤에 딸려온 바이트는 버린다.
+    if framing.chunked {
+        send_continue(&mut stream, &framing, header_buffer.len() - header_end).await?;
+        let rest = header_buffer.split_off(header_end);
+        let (body, trailers, _, stream) = read_chunked_body(rest, stream, options).await?;
+        header_buffer.extend_from_slice(&body);
+        #[cfg(feature = "decompression")]
+        let header_end = match decode_content(&mut header_buffer, header_end, &framing, options) {
+            Ok(end) => end,
+            Err(e) => return Err(fail(stream, options, e).await),
+        };
+        #[cfg(not(any(feature = "arena", feature = "websocket")))]
+        let _ = header_end;
+        return Ok(FullRequest {
+            bytes: header_buffer,
+            #[cfg(any(feature = "arena", feature = "websocket"))]
+            header_end,
+            trailers: Some(trailers),
+            surplus: Vec::new(),
+            stream,
+        });
+    }
 
     // DoS 방어: 광고된 Content-Length가 cap을 넘으면 즉시 거부 (대용량 alloc 방지)
     if let (Some(cl), Some(cap)) = (content_length, options.max_body_size) {
         if cl > cap {
-            return Err(format!(
-                "Request body too large: content-length={} exceeds max_body_size={}",
-                cl, cap
-            )
-            .into());
+            let error = ParseError::ContentLengthTooLarge {
+                content_length: cl,
+                limit: cap,
+            };
+            return Err(fail(stream, options, error).await);
         }
     }
 
+    send_continue(&mut stream, &framing, header_buffer.len() - header_end).await?;
+
     // 2단계: 전체 크기 계산. 사전 alloc 하지 않고 들어오는 만큼만 점진 grow.
     // (Content-Length 만큼 미리 alloc 하면 100GB CL 헤더만으로 OOM 가능.)
     let total_expected_size = header_end + content_length.unwrap_or(0);
@@ -768,6 +1058,11 @@ pub(crate) async fn get_bytes_from_reader(
 
     // 실제 읽은 크기로 조정
     final_buffer.truncate(total_read);
+    #[cfg(feature = "decompression")]
+    let header_end = match decode_content(&mut final_buffer, header_end, &framing, options) {
+        Ok(end) => end,
+        Err(e) => return Err(fail(stream, options, e).await),
+    };
 
     dev_print!(
         "HTTP 파싱 완료: 헤더={}B, 바디={}B, 총={}B",
@@ -776,7 +1071,71 @@ pub(crate) async fn get_bytes_from_reader(
         total_read
     );
 
-    Ok((final_buffer, stream))
+    Ok(FullRequest {
+        bytes: final_buffer,
+        #[cfg(any(feature = "arena", feature = "websocket"))]
+        header_end,
+        trailers: None,
+        surplus: Vec::new(),
+        stream,
+    })
+}
+
+/// 헤더 끝을 찾지 못하고 읽기를 멈춘 이유. `timed_out` 은 read 재시도를 다 쓴 경우.
+fn incomplete_headers(buffer: &[u8], options: &Options, timed_out: bool) -> ParseError {
+    if buffer.is_empty() {
+        ParseError::NoData
+    } else if let Err(e) = check_header_limits(buffer, options) {
+        e
+    } else if buffer.len() >= options.max_header_size {
+        ParseError::HeadersTooLarge(options.max_header_size)
+    } else if timed_out {
+        ParseError::HeaderTimeout
+    } else {
+        ParseError::HeadersIncomplete
+    }
+}
+
+/// 헤더 블록이 `Options` 의 크기 한도 안인지. 끝나지 않은 마지막 줄도 지금까지 받은 길이로
+/// 검사하므로 헤더가 덜 들어온 버퍼에도 쓸 수 있다 (한도를 넘었으면 더 받아도 넘는다).
+fn check_header_limits(headers: &[u8], options: &Options) -> Result<(), ParseError> {
+    let mut lines = headers
+        .split(|&b| b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line));
+    // 요청 라인 앞의 빈 줄은 무시 (RFC 9112 §2.2)
+    let request_line = lines
+        .by_ref()
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    if request_target(request_line).len() > options.max_uri_length {
+        return Err(ParseError::UriTooLong(options.max_uri_length));
+    }
+    let mut count = 0;
+    for line in lines.take_while(|line| !line.is_empty()) {
+        if line.len() > options.max_header_line {
+            return Err(ParseError::HeaderLineTooLong(options.max_header_line));
+        }
+        count += 1;
+        if count > options.max_headers {
+            return Err(ParseError::TooManyHeaders(options.max_headers));
+        }
+    }
+    if headers.len() > options.max_header_size {
+        return Err(ParseError::HeadersTooLarge(options.max_header_size));
+    }
+    Ok(())
+}
+
+/// `GET /path HTTP/1.1` → `/path`. 버전이 아직 안 들어온 조각이면 첫 공백 뒤 전부.
+fn request_target(request_line: &[u8]) -> &[u8] {
+    let Some(start) = request_line.iter().position(|&b| b == b' ') else {
+        return &[];
+    };
+    let rest = &request_line[start + 1..];
+    match rest.iter().position(|&b| b == b' ') {
+        Some(end) => &rest[..end],
+        None => rest,
+    }
 }
 
 /// 헤더 읽기 시 다음 read 청크 크기. 점진 grow로 작은 요청은 4KB 1회로 끝.
@@ -793,76 +1152,417 @@ fn determine_next_read_size(current_size: usize) -> usize {
 // 기존 외부 호출자(websocket.rs)는 이 모듈에서 임포트 가능하도록 re-export 유지.
 pub(crate) use crate::helpers::traits::bytes::find_header_end as find_header_end_optimized;
 
-// ✅ Content-Length 추출 — 라인 단위 스캔 (O(n))
-// HTTP 헤더는 \r\n 구분이므로 각 라인의 시작 위치에서만 prefix 비교.
-// 이전의 모든 오프셋에서 풀 needle을 비교하던 O(n·m) 패턴을 제거.
-fn extract_content_length_simple(headers: &[u8]) -> Option<usize> {
-    const PATTERN: &[u8] = b"content-length:";
-
-    let mut line_start = 0;
-    while line_start + PATTERN.len() <= headers.len() {
-        // 라인 끝 찾기 (\n까지)
-        let mut line_end = line_start;
-        while line_end < headers.len() && headers[line_end] != b'\n' {
-            line_end += 1;
-        }
-
-        // prefix가 PATTERN과 일치하는지 무할당 case-insensitive 비교
-        let line_prefix = &headers[line_start..line_start + PATTERN.len()];
-        let mut matched = true;
-        for j in 0..PATTERN.len() {
-            // PATTERN은 이미 소문자, 입력만 to_ascii_lowercase
-            if line_prefix[j].to_ascii_lowercase() != PATTERN[j] {
-                matched = false;
-                break;
+/// 에러 응답 전송 제한 시간. 느린 클라이언트가 연결을 붙잡지 못하게.
+const REJECT_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 요청 body 경계 (framing) 가 모호하거나 잘못되어 거부한 이유 — request smuggling 방어.
+///
+/// 앞단 proxy 와 이 서버가 body 길이를 다르게 해석하면 body 일부가 다음 요청으로 읽힐 수 있으므로
+/// 추측하지 않고 거부한다. 헤더를 읽는 단계에서 `400 Bad Request` + `Connection: close` 로 응답하고
+/// 연결을 닫은 뒤 `ParseError::Framing` 으로 반환한다. 핸들러가 반환해도 `serve*` 는 400 으로 응답한다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FramingError {
+    /// `Content-Length` 와 `Transfer-Encoding` 이 함께 있음.
+    ContentLengthWithTransferEncoding,
+    /// `Content-Length` 헤더가 여러 줄이거나, 콤마 목록의 값이 서로 다름.
+    ConflictingContentLength,
+    /// `Content-Length` 값이 10진 숫자가 아니거나 `usize` 를 넘음.
+    InvalidContentLength,
+    /// 마지막 transfer coding 이 `chunked` 가 아님.
+    UnsupportedTransferEncoding,
+    /// 공백/탭으로 시작하는 헤더 연속 줄 (obs-fold, RFC 9112 §5.2).
+    ObsFold,
+    /// `\r` 없이 `\n` 으로 끝나는 줄.
+    BareLf,
+}
+
+impl std::fmt::Display for FramingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::ContentLengthWithTransferEncoding => {
+                "both content-length and transfer-encoding present"
             }
+            Self::ConflictingContentLength => "conflicting content-length values",
+            Self::InvalidContentLength => "invalid content-length value",
+            Self::UnsupportedTransferEncoding => {
+                "unsupported transfer-encoding (final coding must be chunked)"
+            }
+            Self::ObsFold => "obsolete header line folding",
+            Self::BareLf => "bare LF line ending",
+        })
+    }
+}
+
+impl std::error::Error for FramingError {}
+
+/// 헤더 블록에서 읽은 body 경계.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Framing {
+    pub content_length: Option<usize>,
+    pub chunked: bool,
+    /// HTTP/1.1 `Expect: 100-continue` — 클라이언트가 `100 Continue` 를 받아야 body 를 보낸다.
+    pub expect_continue: bool,
+    /// 해제할 `Content-Encoding`. `Options::decompress_request_body` 가 켜져 있을 때만 채운다.
+    #[cfg(feature = "decompression")]
+    pub content_coding: Option<ContentCoding>,
+}
+
+/// `Expect: 100-continue` 에 대한 interim 응답.
+pub(crate) const CONTINUE_RESPONSE: &[u8] = b"HTTP/1.1 100 Continue\r\n\r\n";
+
+impl Framing {
+    /// 읽어야 할 body 가 있는지 (Content-Length 0 / 없음이면 `false`).
+    fn has_body(&self) -> bool {
+        self.chunked || self.content_length.is_some_and(|cl| cl > 0)
+    }
+
+    /// body 를 읽기 전에 `100 Continue` 를 보내야 하는지. `received` 는 헤더와 함께 이미 들어온
+    /// body 바이트 수 — 클라이언트가 기다리지 않고 보냈다면 보낼 필요 없음.
+    pub(crate) fn needs_continue(&self, received: usize) -> bool {
+        self.expect_continue && self.has_body() && received == 0
+    }
+}
+
+/// buffered / arena 경로는 핸들러 전에 body 를 통째로 읽으므로 헤더 직후 바로 `100 Continue` 를 보낸다.
+async fn send_continue(
+    stream: &mut Stream,
+    framing: &Framing,
+    received: usize,
+) -> Result<(), SendableError> {
+    if framing.needs_continue(received) {
+        stream.write_all(CONTINUE_RESPONSE).await?;
+    }
+    Ok(())
+}
+
+/// body 가 있고 `Options::decompress_request_body` 가 켜져 있으면 `Content-Encoding` 을 기록한다.
+/// 지원하지 않는 coding 은 body 를 읽기 전에 `415`.
+#[cfg(feature = "decompression")]
+fn with_content_coding(
+    headers: &[u8],
+    mut framing: Framing,
+    options: &Options,
+) -> Result<Framing, ParseError> {
+    if options.decompress_request_body && framing.has_body() {
+        framing.content_coding = decompress::content_coding(headers)?;
+    }
+    Ok(framing)
+}
+
+/// buffered / arena 경로: 헤더 + body 버퍼의 body 를 해제하고 coding 헤더를 지운다.
+/// 해제된 크기에 `max_body_size` 를 적용한다. 새 헤더 길이를 돌려준다.
+#[cfg(feature = "decompression")]
+fn decode_content(
+    bytes: &mut Vec<u8>,
+    header_end: usize,
+    framing: &Framing,
+    options: &Options,
+) -> Result<usize, ParseError> {
+    match framing.content_coding {
+        Some(coding) => {
+            decompress::decode_request(bytes, header_end, coding, options.max_body_size)
         }
+        None => Ok(header_end),
+    }
+}
+
+/// 헤더 블록 (요청 라인 ~ 빈 줄) 을 검사해 body 경계를 정한다. 라인 단위로 한 번만 훑으며
+/// 헤더 이름은 라인 시작에서만 case-insensitive 비교. 빈 줄 이후 (body) 는 보지 않는다.
+pub(crate) fn parse_framing(headers: &[u8]) -> Result<Framing, FramingError> {
+    const CONTENT_LENGTH: &[u8] = b"content-length:";
+    const TRANSFER_ENCODING: &[u8] = b"transfer-encoding:";
+    const EXPECT: &[u8] = b"expect:";
 
-        if matched {
-            // 값 추출: PATTERN 이후 공백/탭 스킵 → 숫자 슬라이스
-            let mut pos = line_start + PATTERN.len();
-            while pos < line_end && (headers[pos] == b' ' || headers[pos] == b'\t') {
-                pos += 1;
+    let mut content_length = None;
+    let mut transfer_encoding = false;
+    let mut expect_continue = false;
+    let mut http10 = false;
+    let mut last_coding: &[u8] = b"";
+    let mut request_line = true;
+    for raw in headers.split_inclusive(|&b| b == b'\n') {
+        // 마지막 미완성 조각
+        let Some(line) = raw.strip_suffix(b"\n") else {
+            break;
+        };
+        let line = line.strip_suffix(b"\r").ok_or(FramingError::BareLf)?;
+        if line.is_empty() {
+            // 요청 라인 앞의 빈 줄은 무시 (RFC 9112 §2.2), 그 외엔 헤더 끝
+            if request_line {
+                continue;
             }
-            let number_start = pos;
-            while pos < line_end && headers[pos].is_ascii_digit() {
-                pos += 1;
+            break;
+        }
+        if request_line {
+            request_line = false;
+            http10 = line.ends_with(b"HTTP/1.0");
+            continue;
+        }
+        if matches!(line[0], b' ' | b'\t') {
+            return Err(FramingError::ObsFold);
+        }
+        if starts_with_ignore_case(line, CONTENT_LENGTH) {
+            if content_length.is_some() {
+                return Err(FramingError::ConflictingContentLength);
             }
-            if pos > number_start {
-                // 숫자만 있으므로 from_utf8 안전, 직접 파싱
-                let mut value: usize = 0;
-                for &b in &headers[number_start..pos] {
-                    value = value.checked_mul(10)?.checked_add((b - b'0') as usize)?;
+            content_length = Some(parse_content_length(&line[CONTENT_LENGTH.len()..])?);
+        } else if starts_with_ignore_case(line, EXPECT) {
+            expect_continue = trim_ows(&line[EXPECT.len()..]).eq_ignore_ascii_case(b"100-continue");
+        } else if starts_with_ignore_case(line, TRANSFER_ENCODING) {
+            // 여러 줄이면 이어 붙인 목록으로 보고 마지막 coding 을 본다
+            transfer_encoding = true;
+            last_coding = b"";
+            for coding in line[TRANSFER_ENCODING.len()..].split(|&b| b == b',') {
+                let coding = trim_ows(coding);
+                if !coding.is_empty() {
+                    last_coding = coding;
                 }
-                return Some(value);
             }
-            return None;
         }
+    }
 
-        // 다음 라인으로 (line_end가 끝을 가리키면 종료)
-        if line_end >= headers.len() {
-            break;
+    // HTTP/1.0 클라이언트에는 100 을 보내면 안 된다 (RFC 9110 §10.1.1)
+    let expect_continue = expect_continue && !http10;
+    if !transfer_encoding {
+        return Ok(Framing {
+            content_length,
+            chunked: false,
+            expect_continue,
+            #[cfg(feature = "decompression")]
+            content_coding: None,
+        });
+    }
+    if content_length.is_some() {
+        return Err(FramingError::ContentLengthWithTransferEncoding);
+    }
+    if !last_coding.eq_ignore_ascii_case(b"chunked") {
+        return Err(FramingError::UnsupportedTransferEncoding);
+    }
+    Ok(Framing {
+        content_length: None,
+        chunked: true,
+        expect_continue,
+        #[cfg(feature = "decompression")]
+        content_coding: None,
+    })
+}
+
+pub(crate) fn starts_with_ignore_case(line: &[u8], prefix: &[u8]) -> bool {
+    line.len() >= prefix.len() && line[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+/// `Content-Length` 값. `5, 5` 처럼 같은 값의 목록은 허용 (RFC 9110 §8.6).
+fn parse_content_length(value: &[u8]) -> Result<usize, FramingError> {
+    let mut length = None;
+    for item in value.split(|&b| b == b',') {
+        let item = trim_ows(item);
+        if item.is_empty() || !item.iter().all(u8::is_ascii_digit) {
+            return Err(FramingError::InvalidContentLength);
         }
-        line_start = line_end + 1;
+        let mut parsed: usize = 0;
+        for &b in item {
+            parsed = parsed
+                .checked_mul(10)
+                .and_then(|v| v.checked_add((b - b'0') as usize))
+                .ok_or(FramingError::InvalidContentLength)?;
+        }
+        if length.is_some_and(|prev| prev != parsed) {
+            return Err(FramingError::ConflictingContentLength);
+        }
+        length = Some(parsed);
+    }
+    length.ok_or(FramingError::InvalidContentLength)
+}
+
+/// `buffer[from..]` 에서 헤더 끝 (`\r\n\r\n`) 을 찾아 헤더 길이를 반환한다. 아직 없는데 bare LF 가
+/// 보이면 (`\n\n` 으로 끝나는 요청 등) 더 기다리지 않고 거부.
+fn scan_header_end(buffer: &[u8], from: usize) -> Result<Option<usize>, FramingError> {
+    if let Some(rel) = find_header_end_optimized(&buffer[from..]) {
+        return Ok(Some(from + rel + 4));
+    }
+    let bare_lf =
+        (from..buffer.len()).any(|i| buffer[i] == b'\n' && (i == 0 || buffer[i - 1] != b'\r'));
+    if bare_lf {
+        return Err(FramingError::BareLf);
+    }
+    Ok(None)
+}
+
+/// 요청을 읽다가 실패한 이유. 헤더 / body 읽기 함수들은 이 에러를 `SendableError` 로 감싸
+/// 반환하므로 `err.downcast_ref::<ParseError>()` 로 꺼내 `status()` 를 보면 된다.
+///
+/// `Options::error_responses` 가 켜져 있으면 (기본) `status()` 가 있는 에러는 반환 전에
+/// 최소 응답 (`{"error": ...}` + `Connection: close`) 을 쓰고 연결을 닫는다.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// 요청 바이트를 하나도 받기 전에 연결이 닫히거나 시간이 다 됨. 응답하지 않는다.
+    #[error("No data received")]
+    NoData,
+    /// `Options::header_read_deadline_ms` 안에 헤더를 다 받지 못함.
+    #[error("Header read deadline exceeded ({0} ms) — possible slowloris")]
+    HeaderDeadline(u64),
+    /// 헤더 도중 read 가 `read_max_retry` 번 연속 시간 초과.
+    #[error("Header read timed out")]
+    HeaderTimeout,
+    /// 헤더 끝 (`\r\n\r\n`) 전에 연결이 닫힘.
+    #[error("Headers incomplete: \\r\\n\\r\\n not found")]
+    HeadersIncomplete,
+    /// 요청 라인 + 헤더가 `Options::max_header_size` 를 넘음.
+    #[error("Request header section exceeds {0} bytes")]
+    HeadersTooLarge(usize),
+    /// 헤더 필드 수가 `Options::max_headers` 를 넘음.
+    #[error("Too many request header fields (limit {0})")]
+    TooManyHeaders(usize),
+    /// 헤더 한 줄이 `Options::max_header_line` 을 넘음.
+    #[error("Request header field exceeds {0} bytes")]
+    HeaderLineTooLong(usize),
+    /// request-target 이 `Options::max_uri_length` 를 넘음.
+    #[error("Request-target exceeds {0} bytes")]
+    UriTooLong(usize),
+    /// 요청 라인 / 헤더 문법 오류. 헤더 슬롯 초과 (`TooManyHeaders`) 는 431.
+    #[error("Malformed request: {0}")]
+    Malformed(#[from] httparse::Error),
+    /// body 경계가 모호하거나 잘못됨 (request smuggling 방어).
+    #[error(transparent)]
+    Framing(#[from] FramingError),
+    /// 광고된 `Content-Length` 가 한도를 넘음. body 를 읽기 전에 거부.
+    #[error(
+        "Request body too large: content-length={content_length} exceeds max_body_size={limit}"
+    )]
+    ContentLengthTooLarge { content_length: usize, limit: usize },
+    /// 읽은 (chunked 는 디코드된) body 가 한도를 넘음.
+    #[error("Request body too large: body exceeds max_body_size={limit}")]
+    BodyTooLarge { limit: usize },
+    /// 지원하지 않는 요청 `Content-Encoding` (`Options::decompress_request_body` 가 켜진 경우).
+    #[error("Unsupported content-encoding: {0}")]
+    UnsupportedEncoding(String),
+    /// 압축된 요청 body 를 풀 수 없음 (손상, 잘림, 스트림 뒤 쓰레기 바이트).
+    #[error("Malformed compressed body: {0}")]
+    Decompression(&'static str),
+    /// chunked body 의 trailer 섹션이 `Options::max_trailer_size` 를 넘음.
+    #[error("Request trailer section exceeds {0} bytes")]
+    TrailersTooLarge(usize),
+    /// chunked body 문법 오류.
+    #[error("Malformed chunked body: {0}")]
+    Chunked(&'static str),
+    /// `Content-Length` 만큼 / 종료 chunk 까지 받기 전에 연결이 닫힘.
+    #[error("Request body ended before it was complete")]
+    BodyIncomplete,
+    /// body 도중 read 가 `read_max_retry` 번 연속 시간 초과.
+    #[error("Request body read timed out")]
+    BodyTimeout,
+    /// socket 에러. 응답하지 않는다.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl ParseError {
+    /// 이 실패에 맞는 응답 상태 코드. 응답을 쓸 수 없거나 의미 없는 경우 (`NoData`, `Io`) `None`.
+    pub fn status(&self) -> Option<StatusCode> {
+        Some(match self {
+            Self::NoData | Self::Io(_) => return None,
+            Self::HeaderDeadline(_) | Self::HeaderTimeout | Self::BodyTimeout => {
+                StatusCode::REQUEST_TIMEOUT
+            }
+            Self::HeadersTooLarge(_)
+            | Self::TooManyHeaders(_)
+            | Self::HeaderLineTooLong(_)
+            | Self::TrailersTooLarge(_)
+            | Self::Malformed(httparse::Error::TooManyHeaders) => {
+                StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+            }
+            Self::UriTooLong(_) => StatusCode::URI_TOO_LONG,
+            Self::UnsupportedEncoding(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::ContentLengthTooLarge { .. } | Self::BodyTooLarge { .. } => {
+                StatusCode::PAYLOAD_TOO_LARGE
+            }
+            Self::HeadersIncomplete
+            | Self::Malformed(_)
+            | Self::Framing(_)
+            | Self::Chunked(_)
+            | Self::Decompression(_)
+            | Self::BodyIncomplete => StatusCode::BAD_REQUEST,
+        })
     }
+}
+
+/// 요청 읽기 실패를 마무리한다. `error` 가 상태 코드가 있는 `ParseError` 이고
+/// `Options::error_responses` 가 켜져 있으면 최소 응답을 쓰고 연결을 닫는다.
+pub(crate) async fn fail(
+    mut stream: Stream,
+    options: &Options,
+    error: impl Into<SendableError>,
+) -> SendableError {
+    let error = error.into();
+    let status = error
+        .downcast_ref::<ParseError>()
+        .and_then(ParseError::status);
+    let Some(status) = status.filter(|_| options.error_responses) else {
+        return error;
+    };
+    let body = serde_json::json!({ "error": error.to_string() }).to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        status.as_str(),
+        status.canonical_reason().unwrap_or_default(),
+        body.len(),
+        body
+    );
+    let _ = tokio::time::timeout(REJECT_WRITE_TIMEOUT, async {
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    })
+    .await;
+    error
+}
 
-    None
+/// 이 개수까지는 httparse 헤더 슬롯을 스택에 둔다 (`Options::max_headers` 기본값).
+const STACK_HEADER_SLOTS: usize = 64;
+
+/// httparse 헤더 슬롯. `max_headers` 가 `STACK_HEADER_SLOTS` 이하면 스택 배열, 넘을 때만 `Vec` 을 할당한다.
+pub(crate) struct HeaderSlots<'h> {
+    stack: [httparse::Header<'h>; STACK_HEADER_SLOTS],
+    heap: Vec<httparse::Header<'h>>,
+    len: usize,
 }
 
-/// httparse 기반 헤더 슬롯 개수.
-/// 일반적인 브라우저 요청은 20개 내외, 여유 있게 64개로 설정.
-const MAX_PARSED_HEADERS: usize = 64;
+impl<'h> HeaderSlots<'h> {
+    pub(crate) fn new(max_headers: usize) -> Self {
+        let heap = if max_headers > STACK_HEADER_SLOTS {
+            vec![httparse::EMPTY_HEADER; max_headers]
+        } else {
+            Vec::new()
+        };
+        Self {
+            stack: [httparse::EMPTY_HEADER; STACK_HEADER_SLOTS],
+            heap,
+            len: max_headers,
+        }
+    }
 
-pub(crate) async fn get_request(bytes: Vec<u8>) -> Result<Request<Body>, SendableError> {
+    pub(crate) fn as_mut(&mut self) -> &mut [httparse::Header<'h>] {
+        if self.len > STACK_HEADER_SLOTS {
+            &mut self.heap
+        } else {
+            &mut self.stack[..self.len]
+        }
+    }
+}
+
+/// `max_headers` 는 httparse 헤더 슬롯 개수 (`Options::max_headers`).
+pub(crate) async fn get_request(
+    bytes: Vec<u8>,
+    max_headers: usize,
+) -> Result<Request<Body>, SendableError> {
     dev_print!("bytes len: {:?}", &bytes.len());
 
     // httparse로 헤더 파싱 (zero-copy 슬라이스 반환)
     let mut builder = Request::builder();
     let body_start: usize;
     {
-        let mut headers_buf = [httparse::EMPTY_HEADER; MAX_PARSED_HEADERS];
-        let mut req = httparse::Request::new(&mut headers_buf);
-        let status = req.parse(&bytes)?;
+        let mut headers_buf = HeaderSlots::new(max_headers);
+        let mut req = httparse::Request::new(headers_buf.as_mut());
+        let status = req.parse(&bytes).map_err(ParseError::Malformed)?;
 
         body_start = match status {
             httparse::Status::Complete(n) => n,
@@ -933,10 +1633,15 @@ impl StreamHttpArena for TcpStream {
         options: Arc<Options>,
         peer: SocketAddr,
     ) -> Result<(Request<ArenaBody>, Response<Writer>), SendableError> {
-        self.set_nodelay(options.no_delay)?;
+        let stream = Stream::Tcp(self);
+        stream.set_nodelay(options.no_delay)?;
 
-        let (arena_body, stream) = get_bytes_arena_direct(self, &options).await?;
-        let request = parse_http_request_arena(arena_body)?;
+        let mut peer = Peer::Tcp(peer);
+        let (arena_body, stream) = get_bytes_arena_direct(stream, &options, &mut peer).await?;
+        let request = match parse_http_request_arena(arena_body, options.max_headers) {
+            Ok(request) => request,
+            Err(e) => return Err(fail(stream, &options, e).await),
+        };
 
         Ok(get_parse_result_arena(request, stream, options, peer)?)
     }
@@ -944,12 +1649,12 @@ impl StreamHttpArena for TcpStream {
 
 #[cfg(feature = "arena")]
 pub(crate) async fn get_bytes_arena_direct(
-    mut stream: TcpStream,
+    mut stream: Stream,
     options: &Options,
-) -> Result<(ArenaBody, TcpStream), SendableError> {
+    peer: &mut Peer,
+) -> Result<(ArenaBody, Stream), SendableError> {
     use std::time::Duration;
     use tokio::io::AsyncReadExt;
-    const MAX_HEADER_SIZE: usize = 64 * 1024;
     const INITIAL_ARENA_SIZE: usize = 4096;
     const HEADER_END_MARKER: &[u8] = b"\r\n\r\n";
 
@@ -959,10 +1664,13 @@ pub(crate) async fn get_bytes_arena_direct(
         n => n,
     };
 
-    // 1단계: 헤더만 먼저 읽어서 Content-Length 파악
-    let mut temp_header_buf: Vec<u8> = Vec::with_capacity(initial_read);
-    let mut header_end_pos = None;
-    let mut content_length = None;
+    // 1단계: 헤더만 먼저 읽어서 Content-Length 파악 (PROXY preamble 뒤 바이트부터)
+    let mut temp_header_buf: Vec<u8> = read_preamble(&mut stream, options, peer).await?;
+    temp_header_buf.reserve(initial_read);
+    let mut header_end_pos = match scan_header_end(&temp_header_buf, 0) {
+        Ok(end) => end,
+        Err(e) => return Err(fail(stream, options, ParseError::Framing(e)).await),
+    };
     let mut retry_count = 0;
     let max_retry = options.read_max_retry;
     let read_timeout = Duration::from_millis(options.read_timeout_milliseconds);
@@ -975,15 +1683,16 @@ pub(crate) async fn get_bytes_arena_direct(
 
     // 헤더 읽기 및 파싱
     while header_end_pos.is_none()
-        && temp_header_buf.len() < MAX_HEADER_SIZE
+        && temp_header_buf.len() < options.max_header_size
         && retry_count < max_retry
     {
         if header_start.elapsed().as_millis() as u64 > header_deadline_ms {
-            return Err(format!(
-                "Header read deadline exceeded ({} ms) — possible slowloris",
-                header_deadline_ms
+            return Err(fail(
+                stream,
+                options,
+                ParseError::HeaderDeadline(header_deadline_ms),
             )
-            .into());
+            .await);
         }
         let next_chunk_size = determine_next_read_size(temp_header_buf.len());
         let current_len = temp_header_buf.len();
@@ -1007,11 +1716,13 @@ pub(crate) async fn get_bytes_arena_direct(
 
                     // 헤더 끝 찾기
                     let search_start = current_len.saturating_sub(HEADER_END_MARKER.len() - 1);
-                    if let Some(pos) = find_header_end_optimized(&temp_header_buf[search_start..]) {
-                        let end = search_start + pos + 4;
-                        header_end_pos = Some(end);
-                        content_length = extract_content_length_simple(&temp_header_buf[..end]);
-                        break;
+                    match scan_header_end(&temp_header_buf, search_start) {
+                        Ok(Some(end)) => {
+                            header_end_pos = Some(end);
+                            break;
+                        }
+                        Ok(None) => {}
+                        Err(e) => return Err(fail(stream, options, ParseError::Framing(e)).await),
                     }
                     retry_count = 0;
                 }
@@ -1019,7 +1730,7 @@ pub(crate) async fn get_bytes_arena_direct(
                     temp_header_buf.truncate(current_len);
                     continue;
                 }
-                Err(e) => return Err(e.into()),
+                Err(e) => return Err(ParseError::Io(e).into()),
             },
             Err(_) => {
                 temp_header_buf.truncate(current_len);
@@ -1029,19 +1740,58 @@ pub(crate) async fn get_bytes_arena_direct(
         }
     }
 
+    let limits = match header_end_pos {
+        Some(end) => check_header_limits(&temp_header_buf[..end], options),
+        None => match incomplete_headers(&temp_header_buf, options, retry_count >= max_retry) {
+            ParseError::HeadersIncomplete => Ok(()),
+            error => Err(error),
+        },
+    };
+    if let Err(e) = limits {
+        return Err(fail(stream, options, e).await);
+    }
     let header_end = header_end_pos.unwrap_or(temp_header_buf.len());
+    let framing = match header_end_pos.map(|end| parse_framing(&temp_header_buf[..end])) {
+        Some(Ok(framing)) => framing,
+        Some(Err(e)) => return Err(fail(stream, options, ParseError::Framing(e)).await),
+        None => Framing::default(),
+    };
+    #[cfg(feature = "decompression")]
+    let framing = match with_content_coding(&temp_header_buf[..header_end], framing, options) {
+        Ok(framing) => framing,
+        Err(e) => return Err(fail(stream, options, e).await),
+    };
+    let content_length = framing.content_length;
+
+    // chunked body 는 디코드해서 헤더 뒤에 붙인 뒤 arena 로 복사
+    if framing.chunked {
+        send_continue(&mut stream, &framing, temp_header_buf.len() - header_end).await?;
+        let rest = temp_header_buf.split_off(header_end);
+        let (body, _, _, stream) = read_chunked_body(rest, stream, options).await?;
+        temp_header_buf.extend_from_slice(&body);
+        #[cfg(feature = "decompression")]
+        let header_end = match decode_content(&mut temp_header_buf, header_end, &framing, options) {
+            Ok(end) => end,
+            Err(e) => return Err(fail(stream, options, e).await),
+        };
+        return Ok((
+            ArenaBody::new(&temp_header_buf, header_end, header_end),
+            stream,
+        ));
+    }
 
     // DoS 방어: 광고된 Content-Length가 cap 초과 시 즉시 거부
     if let (Some(cl), Some(cap)) = (content_length, options.max_body_size) {
         if cl > cap {
-            return Err(format!(
-                "Request body too large: content-length={} exceeds max_body_size={}",
-                cl, cap
-            )
-            .into());
+            let error = ParseError::ContentLengthTooLarge {
+                content_length: cl,
+                limit: cap,
+            };
+            return Err(fail(stream, options, error).await);
         }
     }
 
+    send_continue(&mut stream, &framing, temp_header_buf.len() - header_end).await?;
     let total_size = header_end + content_length.unwrap_or(0);
 
     // 2단계: 사전 alloc 하지 않고 들어오는 만큼만 점진 grow.
@@ -1087,6 +1837,11 @@ pub(crate) async fn get_bytes_arena_direct(
     }
 
     final_buffer.truncate(total_read);
+    #[cfg(feature = "decompression")]
+    let header_end = match decode_content(&mut final_buffer, header_end, &framing, options) {
+        Ok(end) => end,
+        Err(e) => return Err(fail(stream, options, e).await),
+    };
 
     // 4단계: ArenaBody 생성 (per-request Bump 사용)
     let body_start = header_end;
@@ -1105,15 +1860,16 @@ pub(crate) async fn get_bytes_arena_direct(
 #[cfg(feature = "arena")]
 pub(crate) fn parse_http_request_arena(
     mut body: ArenaBody,
+    max_headers: usize,
 ) -> Result<Request<ArenaBody>, SendableError> {
     let mut builder = Request::builder();
     {
         // get_headers()는 \r\n\r\n 마커를 포함한 헤더 슬라이스를 반환하므로
         // httparse가 Status::Complete를 반환할 수 있음.
         let headers_bytes = body.get_headers();
-        let mut headers_buf = [httparse::EMPTY_HEADER; MAX_PARSED_HEADERS];
-        let mut req = httparse::Request::new(&mut headers_buf);
-        let _ = req.parse(headers_bytes)?;
+        let mut headers_buf = HeaderSlots::new(max_headers);
+        let mut req = httparse::Request::new(headers_buf.as_mut());
+        let _ = req.parse(headers_bytes).map_err(ParseError::Malformed)?;
 
         if let Some(m) = req.method {
             if let Ok(method) = m.parse::<http::Method>() {
@@ -1151,14 +1907,21 @@ pub(crate) fn parse_http_request_arena(
 #[cfg(feature = "arena")]
 fn get_parse_result_arena(
     mut request: Request<ArenaBody>,
-    stream: TcpStream,
+    stream: Stream,
     options: Arc<Options>,
-    peer: SocketAddr,
+    peer: Peer,
 ) -> Result<(Request<ArenaBody>, Response<Writer>), SendableError> {
     let version = request.version();
-    request.body_mut().ip = Some(peer);
+    request.body_mut().ip = peer.addr();
+    peer.tag(&mut request, &options);
 
-    let (_read_half, write_half) = stream.into_split();
+    let (read_half, write_half) = stream.into_split();
+    let mut writer = Writer::new(write_half, options);
+    writer.read_half = Some(read_half);
+    #[cfg(feature = "compression")]
+    let writer = writer.with_accept_encoding(request.headers());
+    #[cfg(feature = "response_file")]
+    let writer = writer.with_file_request(request.method(), request.headers());
 
     Ok((
         request,
@@ -1166,13 +1929,7 @@ fn get_parse_result_arena(
             .version(version)
             .header(CONTENT_TYPE, "application/json")
             .status(400)
-            .body(Writer {
-                stream: write_half,
-                body: String::new(),
-                bytes: vec![],
-                use_file: false,
-                options,
-            })?,
+            .body(writer)?,
     ))
 }
 
@@ -1194,28 +1951,46 @@ impl StreamHttpArenaWriter for TcpStream {
         options: Arc<Options>,
         peer: SocketAddr,
     ) -> Result<(Request<ArenaBody>, Response<ArenaWriter>), SendableError> {
-        self.set_nodelay(options.no_delay)?;
+        parse_arena_writer(Stream::Tcp(self), options, Peer::Tcp(peer)).await
+    }
+}
 
-        let (arena_body, stream) = get_bytes_arena_direct(self, &options).await?;
-        let request = parse_http_request_arena(arena_body)?;
+/// `StreamHttpArenaWriter::parse_request_arena_writer` 구현 (TCP / UDS 공용).
+#[cfg(feature = "arena")]
+pub(crate) async fn parse_arena_writer(
+    stream: Stream,
+    options: Arc<Options>,
+    mut peer: Peer,
+) -> Result<(Request<ArenaBody>, Response<ArenaWriter>), SendableError> {
+    stream.set_nodelay(options.no_delay)?;
 
-        Ok(get_parse_result_arena_writer(
-            request, stream, options, peer,
-        )?)
-    }
+    let (arena_body, stream) = get_bytes_arena_direct(stream, &options, &mut peer).await?;
+    let request = match parse_http_request_arena(arena_body, options.max_headers) {
+        Ok(request) => request,
+        Err(e) => return Err(fail(stream, &options, e).await),
+    };
+
+    get_parse_result_arena_writer(request, stream, options, peer)
 }
 
 #[cfg(feature = "arena")]
 pub(crate) fn get_parse_result_arena_writer(
     mut request: Request<ArenaBody>,
-    stream: TcpStream,
+    stream: Stream,
     options: Arc<Options>,
-    peer: SocketAddr,
+    peer: Peer,
 ) -> Result<(Request<ArenaBody>, Response<ArenaWriter>), SendableError> {
     let version = request.version();
-    request.body_mut().ip = Some(peer);
+    request.body_mut().ip = peer.addr();
+    peer.tag(&mut request, &options);
 
-    let (_read_half, write_half) = stream.into_split();
+    let (read_half, write_half) = stream.into_split();
+    let mut writer = ArenaWriter::new(write_half, options);
+    writer.read_half = Some(read_half);
+    #[cfg(feature = "compression")]
+    let writer = writer.with_accept_encoding(request.headers());
+    #[cfg(feature = "response_file")]
+    let writer = writer.with_file_request(request.method(), request.headers());
 
     Ok((
         request,
@@ -1223,7 +1998,7 @@ pub(crate) fn get_parse_result_arena_writer(
             .version(version)
             .header(CONTENT_TYPE, "application/json")
             .status(400)
-            .body(ArenaWriter::new(write_half, options))?,
+            .body(writer)?,
     ))
 }
 
@@ -1262,20 +2037,24 @@ mod tests {
     #[test]
     fn test_content_length_extraction() {
         let headers = b"GET / HTTP/1.1\r\nHost: example.com\r\nContent-Length: 1234\r\nUser-Agent: test\r\n\r\n";
-        let result = extract_content_length_simple(headers);
+        let result = parse_framing(headers).unwrap().content_length;
         assert_eq!(result, Some(1234));
 
         // 대소문자 혼합
         let headers2 = b"GET / HTTP/1.1\r\nHost: example.com\r\nContent-LENGTH: 5678\r\n\r\n";
-        let result2 = extract_content_length_simple(headers2);
+        let result2 = parse_framing(headers2).unwrap().content_length;
         assert_eq!(result2, Some(5678));
 
         // 없는 경우
         let headers3 = b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n";
-        let result3 = extract_content_length_simple(headers3);
+        let result3 = parse_framing(headers3).unwrap().content_length;
         assert_eq!(result3, None);
     }
 
+    fn local_peer() -> Peer {
+        "127.0.0.1:40000".parse::<SocketAddr>().unwrap().into()
+    }
+
     // 실제 TCP 소켓 페어 — get_bytes_from_reader는 진짜 TcpStream 만 받기 때문에
     // mock 대신 임시 포트 listener + 즉시 connect로 한 쌍을 만든다.
     async fn socket_pair() -> (tokio::net::TcpStream, tokio::net::TcpStream) {
@@ -1286,6 +2065,57 @@ mod tests {
         (client_res.unwrap(), server_res.unwrap().0)
     }
 
+    #[tokio::test]
+    async fn proxy_protocol_preamble_replaces_peer_for_trusted_upstream() {
+        use tokio::io::AsyncWriteExt;
+
+        let mut options = Options::new();
+        options.proxy_protocol = crate::ProxyProtocol::Required;
+        options.proxy_protocol_trusted = vec!["127.0.0.0/8".parse().unwrap()];
+
+        // preamble 과 요청이 한 번에 들어와도 요청 바이트는 그대로 남는다
+        let (mut client, server) = socket_pair().await;
+        client
+            .write_all(
+                b"PROXY TCP4 203.0.113.9 127.0.0.1 55000 80\r\n\
+                  POST /x HTTP/1.1\r\nHost: x\r\nContent-Length: 2\r\n\r\nok",
+            )
+            .await
+            .unwrap();
+        let mut peer = local_peer();
+        let bytes = get_bytes_from_reader(server.into(), &options, &mut peer)
+            .await
+            .unwrap()
+            .bytes;
+        assert!(bytes.starts_with(b"POST /x"));
+        assert!(bytes.ends_with(b"\r\n\r\nok"));
+        assert_eq!(peer.addr(), Some("203.0.113.9:55000".parse().unwrap()));
+
+        // required 인데 preamble 없음 → 거부
+        let (mut client, server) = socket_pair().await;
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        let result = read_headers_only(server.into(), &options, &mut local_peer()).await;
+        assert!(result.is_err());
+
+        // 신뢰 대역 밖의 peer 가 보낸 preamble 은 믿지 않는다
+        options.proxy_protocol = crate::ProxyProtocol::Optional;
+        options.proxy_protocol_trusted = vec!["10.0.0.0/8".parse().unwrap()];
+        let (mut client, server) = socket_pair().await;
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        let mut peer = local_peer();
+        let head = read_headers_only(server.into(), &options, &mut peer)
+            .await
+            .unwrap();
+        assert!(head.header_bytes.starts_with(b"GET /"));
+        assert_eq!(peer, local_peer());
+    }
+
     #[tokio::test]
     async fn body_exceeds_max_body_size_is_rejected_by_content_length() {
         // 핵심 회귀 방지: 광고된 Content-Length가 max_body_size를 초과하면
@@ -1301,7 +2131,7 @@ mod tests {
         options.read_timeout_milliseconds = 200;
         options.read_max_retry = 1;
 
-        let result = get_bytes_from_reader(server, &options).await;
+        let result = get_bytes_from_reader(server.into(), &options, &mut local_peer()).await;
         assert!(result.is_err(), "expected rejection of CL > cap");
         let err = result.unwrap_err().to_string();
         assert!(
@@ -1330,9 +2160,10 @@ mod tests {
         options.read_timeout_milliseconds = 200;
         options.read_max_retry = 1;
 
-        let (buf, _stream) = get_bytes_from_reader(server, &options)
+        let buf = get_bytes_from_reader(server.into(), &options, &mut local_peer())
             .await
-            .expect("should accept body within cap");
+            .expect("should accept body within cap")
+            .bytes;
         // 헤더 + body 가 모두 들어왔는지 확인 (body=hello 가 buf 끝부분에 있어야 함)
         assert!(
             buf.ends_with(b"hello"),
@@ -1368,10 +2199,12 @@ mod tests {
         options.read_timeout_milliseconds = 1000;
         options.read_max_retry = 10;
 
-        let hr = read_headers_only(server, &options).await.unwrap();
+        let hr = read_headers_only(server.into(), &options, &mut local_peer())
+            .await
+            .unwrap();
         let (read_half, _write_half) = hr.stream.into_split();
         let mut body_streaming =
-            crate::Body::new_streaming(hr.leftover, read_half, hr.content_length, None, None);
+            crate::Body::new_streaming(hr.leftover, read_half, hr.framing, None, None, 64 * 1024);
 
         let mut collected = Vec::new();
         while let Some(chunk) = body_streaming.read_chunk().await.unwrap() {
@@ -1382,6 +2215,633 @@ mod tests {
         assert_eq!(collected, body, "received bytes don't match sent body");
     }
 
+    #[tokio::test]
+    async fn chunked_body_is_decoded_on_buffered_and_streaming_paths() {
+        use tokio::io::AsyncWriteExt;
+        let mut options = Options::new();
+        options.read_timeout_milliseconds = 1000;
+        options.read_max_retry = 3;
+
+        // buffered: 헤더 뒤에 디코드된 body 가 붙는다 (Content-Length 는 무시)
+        let (mut client, server) = socket_pair().await;
+        client
+            .write_all(b"POST / HTTP/1.1\r\nHost: x\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n")
+            .await
+            .unwrap();
+        let buf = get_bytes_from_reader(server.into(), &options, &mut local_peer())
+            .await
+            .unwrap()
+            .bytes;
+        assert!(
+            buf.ends_with(b"\r\n\r\nhello"),
+            "{:?}",
+            String::from_utf8_lossy(&buf)
+        );
+
+        // streaming: chunk 경계가 read 경계와 어긋나도 디코드 + trailer 보관
+        let (mut client, server) = socket_pair().await;
+        tokio::spawn(async move {
+            let raw: &[u8] =
+                b"PUT / HTTP/1.1\r\nHost: x\r\nTransfer-Encoding: gzip, chunked\r\n\r\n\
+                4;ext=1\r\nwiki\r\n5\r\npedia\r\ne\r\n in\r\n\r\nchunks.\r\n0\r\nDigest: x\r\n\r\n";
+            for piece in raw.chunks(7) {
+                client.write_all(piece).await.unwrap();
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+            }
+        });
+        let hr = read_headers_only(server.into(), &options, &mut local_peer())
+            .await
+            .unwrap();
+        assert!(hr.framing.chunked);
+        let (read_half, _write_half) = hr.stream.into_split();
+        let mut body = crate::Body::new_streaming(
+            hr.leftover,
+            read_half,
+            hr.framing,
+            None,
+            Some(64),
+            64 * 1024,
+        );
+        assert_eq!(body.content_length(), None);
+        assert!(body.trailers().is_none());
+        assert_eq!(
+            body.bytes(None).await.unwrap(),
+            b"wikipedia in\r\n\r\nchunks."
+        );
+        assert_eq!(body.trailers().unwrap()["digest"], "x");
+        assert!(body.is_complete());
+
+        // 마지막 coding 이 chunked 가 아니면 body 경계를 알 수 없으므로 거부
+        let (mut client, server) = socket_pair().await;
+        client
+            .write_all(b"POST / HTTP/1.1\r\nHost: x\r\nTransfer-Encoding: chunked, gzip\r\n\r\n")
+            .await
+            .unwrap();
+        let err = read_headers_only(server.into(), &options, &mut local_peer())
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("transfer-encoding"));
+    }
+
+    #[test]
+    fn framing_rejects_ambiguous_requests() {
+        let framing = |raw: &str| parse_framing(raw.as_bytes());
+        assert_eq!(
+            framing("\r\nPOST / HTTP/1.1\r\nContent-Length: 5, 5\r\n\r\nbody\nwith\nLF"),
+            Ok(Framing {
+                content_length: Some(5),
+                chunked: false,
+                expect_continue: false,
+                #[cfg(feature = "decompression")]
+                content_coding: None,
+            })
+        );
+        assert_eq!(
+            framing(
+                "POST / HTTP/1.1\r\nTransfer-Encoding: gzip\r\nTransfer-Encoding: chunked\r\n\r\n"
+            ),
+            Ok(Framing {
+                content_length: None,
+                chunked: true,
+                expect_continue: false,
+                #[cfg(feature = "decompression")]
+                content_coding: None,
+            })
+        );
+
+        let cases = [
+            (
+                "POST / HTTP/1.1\r\nContent-Length: 3\r\nTransfer-Encoding: chunked\r\n\r\n",
+                FramingError::ContentLengthWithTransferEncoding,
+            ),
+            (
+                "POST / HTTP/1.1\r\nContent-Length: 3\r\nContent-Length: 3\r\n\r\n",
+                FramingError::ConflictingContentLength,
+            ),
+            (
+                "POST / HTTP/1.1\r\nContent-Length: 3, 4\r\n\r\n",
+                FramingError::ConflictingContentLength,
+            ),
+            (
+                "POST / HTTP/1.1\r\nContent-Length: +3\r\n\r\n",
+                FramingError::InvalidContentLength,
+            ),
+            (
+                "POST / HTTP/1.1\r\nContent-Length: 3 3\r\n\r\n",
+                FramingError::InvalidContentLength,
+            ),
+            (
+                "POST / HTTP/1.1\r\nContent-Length:\r\n\r\n",
+                FramingError::InvalidContentLength,
+            ),
+            (
+                "POST / HTTP/1.1\r\nContent-Length: 99999999999999999999999\r\n\r\n",
+                FramingError::InvalidContentLength,
+            ),
+            (
+                "POST / HTTP/1.1\r\nTransfer-Encoding: chunked, identity\r\n\r\n",
+                FramingError::UnsupportedTransferEncoding,
+            ),
+            (
+                "GET / HTTP/1.1\r\nX-Long: a\r\n b\r\n\r\n",
+                FramingError::ObsFold,
+            ),
+            (
+                "GET / HTTP/1.1\r\nHost: x\nContent-Length: 3\r\n\r\n",
+                FramingError::BareLf,
+            ),
+            ("GET / HTTP/1.1\nHost: x\r\n\r\n", FramingError::BareLf),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(framing(raw), Err(expected), "{:?}", raw);
+        }
+    }
+
+    #[tokio::test]
+    async fn malformed_framing_is_answered_with_400_and_closed() {
+        use tokio::io::AsyncWriteExt;
+        let mut options = Options::new();
+        options.read_timeout_milliseconds = 2000;
+        options.read_max_retry = 3;
+
+        // LF 만으로 끝나는 요청은 헤더 끝 (\r\n\r\n) 을 기다리지 않고 즉시 거부
+        for raw in [
+            &b"POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 1\r\nContent-Length: 2\r\n\r\nab"[..],
+            &b"GET / HTTP/1.1\nHost: x\n\n"[..],
+        ] {
+            let (mut client, server) = socket_pair().await;
+            client.write_all(raw).await.unwrap();
+            let start = Instant::now();
+            let err = read_headers_only(server.into(), &options, &mut local_peer())
+                .await
+                .err()
+                .unwrap();
+            assert!(start.elapsed() < Duration::from_millis(1000));
+            assert!(
+                matches!(
+                    err.downcast_ref::<ParseError>(),
+                    Some(ParseError::Framing(_))
+                ),
+                "{}",
+                err
+            );
+
+            let mut response = Vec::new();
+            client.read_to_end(&mut response).await.unwrap();
+            let response = String::from_utf8_lossy(&response);
+            assert!(
+                response.starts_with("HTTP/1.1 400 Bad Request\r\n"),
+                "{}",
+                response
+            );
+            assert!(response.contains("connection: close"));
+        }
+    }
+
+    #[tokio::test]
+    async fn read_failures_are_answered_with_matching_status() {
+        use tokio::io::AsyncWriteExt;
+
+        async fn status_line(raw: &[u8], options: &Options) -> (Option<StatusCode>, String) {
+            let (mut client, server) = socket_pair().await;
+            client.write_all(raw).await.unwrap();
+            let err = parse_buffered(server.into(), Arc::new(options.clone()), local_peer())
+                .await
+                .err()
+                .unwrap();
+            let status = err
+                .downcast_ref::<ParseError>()
+                .and_then(ParseError::status);
+            let mut response = Vec::new();
+            let _ = tokio::time::timeout(Duration::from_secs(2), client.read_to_end(&mut response))
+                .await;
+            let response = String::from_utf8_lossy(&response);
+            (status, response.lines().next().unwrap_or("").to_string())
+        }
+
+        let mut options = Options::new();
+        options.read_timeout_milliseconds = 100;
+        options.read_max_retry = 2;
+        options.max_body_size = Some(16);
+
+        // 헤더 한도 (64KB) 를 정확히 채워 서버가 남김없이 읽게 한다 (안 읽은 바이트가 있으면 RST)
+        let mut huge_header = b"GET / HTTP/1.1\r\nX-Big: ".to_vec();
+        huge_header.resize(64 * 1024, b'a');
+        let cases: [(&[u8], StatusCode); 5] = [
+            (
+                b"POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 17\r\n\r\n",
+                StatusCode::PAYLOAD_TOO_LARGE,
+            ),
+            (
+                b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n20\r\n",
+                StatusCode::PAYLOAD_TOO_LARGE,
+            ),
+            (
+                b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n",
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                b"GET / HTTP/1.1\r\nHost: x\r\n",
+                StatusCode::REQUEST_TIMEOUT,
+            ),
+            (&huge_header, StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE),
+        ];
+        for (raw, expected) in cases {
+            let (status, line) = status_line(raw, &options).await;
+            assert_eq!(status, Some(expected));
+            assert_eq!(
+                line,
+                format!(
+                    "HTTP/1.1 {} {}",
+                    expected.as_str(),
+                    expected.canonical_reason().unwrap()
+                )
+            );
+        }
+
+        // 끄면 에러만 반환하고 응답 없이 닫는다
+        options.error_responses = false;
+        let (status, line) = status_line(cases[0].0, &options).await;
+        assert_eq!(status, Some(StatusCode::PAYLOAD_TOO_LARGE));
+        assert_eq!(line, "");
+    }
+
+    #[test]
+    fn header_limits_come_from_options() {
+        let mut options = Options::new();
+        options.max_headers = 2;
+        options.max_header_line = 16;
+        options.max_uri_length = 8;
+        options.max_header_size = 64;
+        let check = |raw: &[u8]| {
+            check_header_limits(raw, &options)
+                .err()
+                .map(|e| e.to_string())
+        };
+
+        assert_eq!(check(b"GET /short HTTP/1.1\r\nA: 1\r\nB: 2\r\n\r\n"), None);
+        assert_eq!(
+            check(b"GET /too-long-uri HTTP/1.1\r\n\r\n"),
+            Some(ParseError::UriTooLong(8).to_string())
+        );
+        // 아직 덜 들어온 요청 라인 / 헤더 줄도 받은 만큼으로 판정
+        assert_eq!(
+            check(b"GET /too-long-u"),
+            Some(ParseError::UriTooLong(8).to_string())
+        );
+        assert_eq!(
+            check(b"GET / HTTP/1.1\r\nX-Long: 0123456789"),
+            Some(ParseError::HeaderLineTooLong(16).to_string())
+        );
+        assert_eq!(
+            check(b"GET / HTTP/1.1\r\nA: 1\r\nB: 2\r\nC: 3\r\n\r\n"),
+            Some(ParseError::TooManyHeaders(2).to_string())
+        );
+        let mut big = b"GET / HTTP/1.1\r\n".to_vec();
+        for _ in 0..2 {
+            big.extend_from_slice(b"X: 0123456789ab\r\n");
+        }
+        big.extend_from_slice(b"Y: 0123456789ab\r\n\r\n");
+        options.max_headers = 8;
+        let check = |raw: &[u8]| {
+            check_header_limits(raw, &options)
+                .err()
+                .map(|e| e.to_string())
+        };
+        assert_eq!(
+            check(&big),
+            Some(ParseError::HeadersTooLarge(64).to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn header_slots_grow_past_the_stack_array() {
+        let mut raw = b"GET / HTTP/1.1\r\n".to_vec();
+        for i in 0..100 {
+            raw.extend_from_slice(format!("X-{}: {}\r\n", i, i).as_bytes());
+        }
+        raw.extend_from_slice(b"\r\n");
+
+        let request = get_request(raw.clone(), 128).await.unwrap();
+        assert_eq!(request.headers().len(), 100);
+        assert_eq!(request.headers()["x-99"], "99");
+        // 스택 슬롯 (64) 으로는 모자라다
+        assert!(get_request(raw, 64).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn header_limits_are_answered_with_414_and_431() {
+        use tokio::io::AsyncWriteExt;
+        let mut options = Options::new();
+        options.max_uri_length = 32;
+        options.max_headers = 3;
+        options.read_timeout_milliseconds = 200;
+        options.read_max_retry = 1;
+
+        let long_uri = format!("GET /{} HTTP/1.1\r\nHost: x\r\n\r\n", "a".repeat(40));
+        let many = "GET / HTTP/1.1\r\nA: 1\r\nB: 2\r\nC: 3\r\nD: 4\r\n\r\n";
+        for (raw, expected) in [
+            (long_uri.as_str(), "HTTP/1.1 414 URI Too Long\r\n"),
+            (many, "HTTP/1.1 431 Request Header Fields Too Large\r\n"),
+        ] {
+            // streaming / 표준 경로 모두 같은 한도
+            for streaming in [true, false] {
+                let (mut client, server) = socket_pair().await;
+                client.write_all(raw.as_bytes()).await.unwrap();
+                let options = Arc::new(options.clone());
+                let result = if streaming {
+                    parse_streaming(server.into(), options, local_peer()).await
+                } else {
+                    parse_buffered(server.into(), options, local_peer()).await
+                };
+                assert!(result.is_err());
+                let mut response = Vec::new();
+                client.read_to_end(&mut response).await.unwrap();
+                let response = String::from_utf8_lossy(&response);
+                assert!(response.starts_with(expected), "{}", response);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn buffered_requests_keep_chunked_trailers() {
+        use tokio::io::AsyncWriteExt;
+        let raw = b"POST /upload HTTP/1.1\r\nTransfer-Encoding: chunked\r\nTrailer: Digest\r\n\r\n\
+            5\r\nhello\r\n0\r\nDigest: sha-256=abc\r\n\r\n";
+        let (mut client, server) = socket_pair().await;
+        client.write_all(raw).await.unwrap();
+        let (request, _response) =
+            parse_buffered(server.into(), Arc::new(Options::new()), local_peer())
+                .await
+                .unwrap();
+        assert_eq!(request.body().buffered_bytes(), b"hello");
+        assert_eq!(request.body().trailers().unwrap()["digest"], "sha-256=abc");
+
+        // trailer 한도 초과는 431
+        let mut options = Options::new();
+        options.max_trailer_size = 8;
+        let (mut client, server) = socket_pair().await;
+        client.write_all(raw).await.unwrap();
+        let err = parse_buffered(server.into(), Arc::new(options), local_peer())
+            .await
+            .err()
+            .unwrap();
+        assert!(
+            matches!(
+                err.downcast_ref::<ParseError>(),
+                Some(ParseError::TrailersTooLarge(8))
+            ),
+            "{}",
+            err
+        );
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).await.unwrap();
+        assert!(response.starts_with(b"HTTP/1.1 431 "));
+    }
+
+    #[cfg(feature = "decompression")]
+    #[tokio::test]
+    async fn compressed_request_bodies_are_decoded_when_enabled() {
+        use std::io::Write;
+        use tokio::io::AsyncWriteExt;
+
+        fn gzip(data: &[u8]) -> Vec<u8> {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data).unwrap();
+            encoder.finish().unwrap()
+        }
+        fn request(coding: &str, body: &[u8]) -> Vec<u8> {
+            let mut raw = format!(
+                "POST /json HTTP/1.1\r\nHost: x\r\nContent-Encoding: {}\r\nContent-Length: {}\r\n\r\n",
+                coding,
+                body.len()
+            )
+            .into_bytes();
+            raw.extend_from_slice(body);
+            raw
+        }
+
+        let json = br#"{"device":"ios","events":[1,2,3]}"#;
+        let mut options = Options::new();
+        options.decompress_request_body = true;
+        options.max_body_size = Some(1024 * 1024);
+        let options = Arc::new(options);
+
+        // buffered: 풀린 body + coding 헤더 제거
+        let (mut client, server) = socket_pair().await;
+        client
+            .write_all(&request("gzip", &gzip(json)))
+            .await
+            .unwrap();
+        let (request_buffered, _response) =
+            parse_buffered(server.into(), options.clone(), local_peer())
+                .await
+                .unwrap();
+        assert_eq!(request_buffered.body().buffered_bytes(), json);
+        assert!(request_buffered.headers().get("content-encoding").is_none());
+        assert!(request_buffered.headers().get("content-length").is_none());
+        assert_eq!(request_buffered.headers()["host"], "x");
+
+        // arena
+        #[cfg(feature = "arena")]
+        {
+            let (mut client, server) = socket_pair().await;
+            client
+                .write_all(&request("gzip", &gzip(json)))
+                .await
+                .unwrap();
+            let (arena_body, _stream) =
+                get_bytes_arena_direct(server.into(), &options, &mut local_peer())
+                    .await
+                    .unwrap();
+            assert_eq!(arena_body.get_body_bytes(), json);
+        }
+
+        // streaming: chunked 위의 gzip 을 조각으로 받아도 해제
+        let compressed = gzip(json);
+        let mut raw =
+            b"POST /json HTTP/1.1\r\nContent-Encoding: gzip\r\nTransfer-Encoding: chunked\r\n\r\n"
+                .to_vec();
+        for piece in compressed.chunks(9) {
+            raw.extend_from_slice(format!("{:x}\r\n", piece.len()).as_bytes());
+            raw.extend_from_slice(piece);
+            raw.extend_from_slice(b"\r\n");
+        }
+        raw.extend_from_slice(b"0\r\n\r\n");
+        let (mut client, server) = socket_pair().await;
+        tokio::spawn(async move {
+            for piece in raw.chunks(11) {
+                client.write_all(piece).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        });
+        let (mut request_streaming, _response) =
+            parse_streaming(server.into(), options.clone(), local_peer())
+                .await
+                .unwrap();
+        assert!(request_streaming
+            .headers()
+            .get("content-encoding")
+            .is_none());
+        assert_eq!(
+            request_streaming.body_mut().bytes(None).await.unwrap(),
+            json
+        );
+
+        // 모르는 coding 은 415, 해제 크기가 한도를 넘으면 413
+        let bomb = gzip(&vec![0u8; 4 * 1024 * 1024]);
+        let cases: [(Vec<u8>, StatusCode); 2] = [
+            (request("zstd", b"abc"), StatusCode::UNSUPPORTED_MEDIA_TYPE),
+            (request("gzip", &bomb), StatusCode::PAYLOAD_TOO_LARGE),
+        ];
+        for (raw, expected) in cases {
+            let (mut client, server) = socket_pair().await;
+            client.write_all(&raw).await.unwrap();
+            let err = parse_buffered(server.into(), options.clone(), local_peer())
+                .await
+                .err()
+                .unwrap();
+            let status = err
+                .downcast_ref::<ParseError>()
+                .and_then(ParseError::status);
+            assert_eq!(status, Some(expected), "{}", err);
+            let mut response = Vec::new();
+            client.read_to_end(&mut response).await.unwrap();
+            assert!(response.starts_with(format!("HTTP/1.1 {} ", expected.as_str()).as_bytes()));
+        }
+
+        // 기본값 (꺼짐) 은 그대로 전달
+        let (mut client, server) = socket_pair().await;
+        let compressed = gzip(json);
+        client
+            .write_all(&request("gzip", &compressed))
+            .await
+            .unwrap();
+        let (request_raw, _response) =
+            parse_buffered(server.into(), Arc::new(Options::new()), local_peer())
+                .await
+                .unwrap();
+        assert_eq!(request_raw.body().buffered_bytes(), compressed);
+        assert_eq!(request_raw.headers()["content-encoding"], "gzip");
+    }
+
+    /// `100 Continue` 를 받을 때까지 읽은 뒤 body 를 보내는 클라이언트. 받은 바이트를 돌려준다.
+    async fn send_after_continue(
+        mut client: tokio::net::TcpStream,
+        head: &'static [u8],
+        body: &'static [u8],
+    ) -> (tokio::net::TcpStream, Vec<u8>) {
+        use tokio::io::AsyncWriteExt;
+        client.write_all(head).await.unwrap();
+        let mut received = Vec::new();
+        while !received.ends_with(b"\r\n\r\n") {
+            let mut byte = [0u8; 1];
+            if client.read(&mut byte).await.unwrap() == 0 {
+                break;
+            }
+            received.push(byte[0]);
+        }
+        if received == CONTINUE_RESPONSE {
+            client.write_all(body).await.unwrap();
+        }
+        (client, received)
+    }
+
+    #[tokio::test]
+    async fn expect_continue_is_sent_lazily_on_first_body_read() {
+        let (client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let upload = tokio::spawn(send_after_continue(
+            client,
+            b"PUT /up HTTP/1.1\r\nHost: x\r\nContent-Length: 5\r\nExpect: 100-continue\r\n\r\n",
+            b"hello",
+        ));
+
+        let (mut request, _response) =
+            StreamHttp::parse_request_streaming(server, Arc::new(Options::new()), peer)
+                .await
+                .unwrap();
+        assert!(request.body().expects_continue());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(
+            !upload.is_finished(),
+            "100 must not be sent before the body is read"
+        );
+
+        assert_eq!(request.body_mut().bytes(Some(64)).await.unwrap(), b"hello");
+        assert!(!request.body().expects_continue());
+        let (_client, received) = upload.await.unwrap();
+        assert_eq!(received, CONTINUE_RESPONSE);
+    }
+
+    #[tokio::test]
+    async fn expect_continue_can_be_refused_without_reading_body() {
+        use crate::ResponseUtil;
+        let (client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let upload = tokio::spawn(send_after_continue(
+            client,
+            b"POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 1000000\r\nExpect: 100-continue\r\n\r\n",
+            b"never sent",
+        ));
+
+        let (request, mut response) =
+            StreamHttp::parse_request_streaming(server, Arc::new(Options::new()), peer)
+                .await
+                .unwrap();
+        assert!(request.body().expects_continue());
+        *response.status_mut() = http::StatusCode::PAYLOAD_TOO_LARGE;
+        response.body_mut().body = "too large".into();
+        response.responser().await.unwrap();
+
+        let (_client, received) = upload.await.unwrap();
+        let received = String::from_utf8_lossy(&received);
+        assert!(received.starts_with("HTTP/1.1 413"), "{}", received);
+    }
+
+    #[tokio::test]
+    async fn buffered_paths_send_continue_before_reading_body() {
+        let (client, server) = socket_pair().await;
+        let upload = tokio::spawn(send_after_continue(
+            client,
+            b"POST / HTTP/1.1\r\nHost: x\r\nTransfer-Encoding: chunked\r\nExpect: 100-Continue\r\n\r\n",
+            b"3\r\nabc\r\n0\r\n\r\n",
+        ));
+        let buf = get_bytes_from_reader(server.into(), &Options::new(), &mut local_peer())
+            .await
+            .unwrap()
+            .bytes;
+        assert!(buf.ends_with(b"\r\n\r\nabc"));
+        assert_eq!(upload.await.unwrap().1, CONTINUE_RESPONSE);
+
+        // HTTP/1.0 클라이언트에는 보내지 않는다
+        let framing =
+            parse_framing(b"POST / HTTP/1.0\r\nContent-Length: 3\r\nExpect: 100-continue\r\n\r\n")
+                .unwrap();
+        assert!(!framing.expect_continue);
+    }
+
+    #[tokio::test]
+    async fn chunked_body_enforces_max_body_size_on_decoded_size() {
+        use tokio::io::AsyncWriteExt;
+        let (mut client, server) = socket_pair().await;
+        client
+            .write_all(b"POST / HTTP/1.1\r\nHost: x\r\nTransfer-Encoding: chunked\r\n\r\n8\r\n12345678\r\n8\r\n12345678\r\n0\r\n\r\n")
+            .await
+            .unwrap();
+        let mut options = Options::new();
+        options.max_body_size = Some(10);
+        options.read_timeout_milliseconds = 200;
+        options.read_max_retry = 1;
+        let err = get_bytes_from_reader(server.into(), &options, &mut local_peer())
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("max_body_size"), "{}", err);
+    }
+
     #[tokio::test]
     async fn auto_branches_to_arena_when_content_length_within_cap() {
         // 작은 요청: CL=20, cap=1MB → AutoParseResult::Arena
@@ -1399,7 +2859,7 @@ mod tests {
         });
 
         let options = std::sync::Arc::new(crate::Options::new());
-        let peer = "127.0.0.1:1234".parse().unwrap();
+        let peer: SocketAddr = "127.0.0.1:1234".parse().unwrap();
         let accept = crate::Accept::new(server, options, peer);
 
         let result = accept
@@ -1439,7 +2899,7 @@ mod tests {
         });
 
         let options = std::sync::Arc::new(crate::Options::new());
-        let peer = "127.0.0.1:1234".parse().unwrap();
+        let peer: SocketAddr = "127.0.0.1:1234".parse().unwrap();
         let accept = crate::Accept::new(server, options, peer);
 
         let result = accept.parse_request_auto().await.unwrap();
@@ -1480,7 +2940,7 @@ mod tests {
         opt.read_timeout_milliseconds = 1000;
         opt.read_max_retry = 10;
         let options = std::sync::Arc::new(opt);
-        let peer = "127.0.0.1:1234".parse().unwrap();
+        let peer: SocketAddr = "127.0.0.1:1234".parse().unwrap();
         let accept = crate::Accept::new(server, options, peer);
 
         let result = accept
@@ -1521,7 +2981,7 @@ mod tests {
         });
 
         let options = std::sync::Arc::new(crate::Options::new());
-        let peer = "127.0.0.1:1234".parse().unwrap();
+        let peer: SocketAddr = "127.0.0.1:1234".parse().unwrap();
         let accept = crate::Accept::new(server, options, peer);
 
         match accept.stream_parse_auto().await.unwrap() {
@@ -1561,7 +3021,7 @@ mod tests {
         opt.read_timeout_milliseconds = 1000;
         opt.read_max_retry = 10;
         let options = std::sync::Arc::new(opt);
-        let peer = "127.0.0.1:1234".parse().unwrap();
+        let peer: SocketAddr = "127.0.0.1:1234".parse().unwrap();
         let accept = crate::Accept::new(server, options, peer);
 
         match accept.stream_parse_auto_with_cap(1024).await.unwrap() {
@@ -1597,7 +3057,7 @@ mod tests {
         });
 
         let options = std::sync::Arc::new(crate::Options::new());
-        let peer = "127.0.0.1:1234".parse().unwrap();
+        let peer: SocketAddr = "127.0.0.1:1234".parse().unwrap();
         let accept = crate::Accept::new(server, options, peer);
 
         match accept.stream_parse_auto().await.unwrap() {
@@ -1651,10 +3111,12 @@ mod tests {
         options.read_timeout_milliseconds = 2000;
         options.read_max_retry = 20;
 
-        let hr = read_headers_only(server, &options).await.unwrap();
+        let hr = read_headers_only(server.into(), &options, &mut local_peer())
+            .await
+            .unwrap();
         let (read_half, _write_half) = hr.stream.into_split();
         let body_streaming =
-            crate::Body::new_streaming(hr.leftover, read_half, hr.content_length, None, None);
+            crate::Body::new_streaming(hr.leftover, read_half, hr.framing, None, None, 64 * 1024);
 
         let mut mp = body_streaming.into_multipart(boundary.to_string());
         let mut got_field1 = None;
@@ -1697,7 +3159,7 @@ mod tests {
         options.read_max_retry = 10; // retry는 deadline보다 오래 가도록 충분히 크게
 
         let start = std::time::Instant::now();
-        let result = get_bytes_from_reader(server, &options).await;
+        let result = get_bytes_from_reader(server.into(), &options, &mut local_peer()).await;
         let elapsed = start.elapsed();
 
         assert!(result.is_err(), "expected slowloris rejection");
@@ -1736,7 +3198,9 @@ mod tests {
         let header_end = find_header_end_optimized(large_header.as_bytes());
         assert!(header_end.is_some());
 
-        let content_length = extract_content_length_simple(large_header.as_bytes());
+        let content_length = parse_framing(large_header.as_bytes())
+            .unwrap()
+            .content_length;
         assert_eq!(content_length, Some(100));
 
         println!(
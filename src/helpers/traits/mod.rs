@@ -44,7 +44,7 @@ impl StringUtil for String {
 }
 
 // 헤더 끝 찾기와 Content-Length 추출은 `bytes::find_header_end` 와
-// `http_stream::extract_content_length_simple` 로 통합되었습니다.
+// `http_stream::parse_framing` 으로 통합되었습니다.
 // 외부 호환을 위해 thin wrapper만 유지.
 pub use crate::helpers::traits::bytes::find_header_end as find_header_end_optimized;
 
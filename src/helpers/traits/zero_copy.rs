@@ -145,6 +145,36 @@ impl<'a> Iterator for LineIterator<'a> {
     }
 }
 
+/// 조건부 요청 (`If-None-Match` / `If-Modified-Since` 등) 에 쓰는 파일 validator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileValidators {
+    /// strong entity-tag, 따옴표 포함 (`"<수정 시각 ns>-<크기>"`, 16진수).
+    pub etag: Arc<str>,
+    pub last_modified: SystemTime,
+}
+
+impl FileValidators {
+    pub fn new(len: u64, last_modified: SystemTime) -> Self {
+        let nanos = last_modified
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        Self {
+            etag: format!("\"{:x}-{:x}\"", nanos, len).into(),
+            last_modified,
+        }
+    }
+
+    /// `coding` (`gzip` / `br`) 으로 압축한 representation 의 validator. 원본과 다른 strong
+    /// `ETag` (`"<태그>-gzip"`) 를 준다 — 같은 태그가 바이트가 다른 두 응답을 가리키지 않게.
+    pub fn encoded(&self, coding: &str) -> Self {
+        let tag = self.etag.trim_end_matches('"');
+        Self {
+            etag: format!("{}-{}\"", tag, coding).into(),
+            last_modified: self.last_modified,
+        }
+    }
+}
+
 /// 캐시된 파일 데이터.
 /// `Arc<[u8]>`로 보관하여 핸드오프 시 참조 카운트 증가만 발생 (zero-copy).
 #[derive(Debug)]
@@ -154,6 +184,7 @@ pub struct CachedFileData {
     modified_time: SystemTime,
     file_path: PathBuf,
     original_size: usize,
+    validators: FileValidators,
 }
 
 impl CachedFileData {
@@ -165,9 +196,14 @@ impl CachedFileData {
             modified_time,
             file_path,
             original_size,
+            validators: FileValidators::new(original_size as u64, modified_time),
         }
     }
 
+    pub fn validators(&self) -> &FileValidators {
+        &self.validators
+    }
+
     pub fn get_info(&self) -> (PathBuf, usize) {
         (self.file_path.clone(), self.original_size)
     }
@@ -306,6 +342,20 @@ impl ZeroCopyCache {
         Ok(FileLoadResult::DirectMemoryMap(zero_copy_file))
     }
 
+    /// 파일의 `ETag` / `Last-Modified`. 메모리 캐시에 있고 바뀌지 않았으면 캐시된 값을 쓴다.
+    pub fn validators<P: AsRef<Path>>(&self, path: P) -> Result<FileValidators, SendableError> {
+        let metadata = std::fs::metadata(path.as_ref())?;
+        let modified_time = metadata.modified()?;
+        if let Some(cached_data) = self.memory_cache.get(path.as_ref()) {
+            if cached_data.modified_time == modified_time
+                && cached_data.original_size as u64 == metadata.len()
+            {
+                return Ok(cached_data.validators.clone());
+            }
+        }
+        Ok(FileValidators::new(metadata.len(), modified_time))
+    }
+
     /// 메모리 캐시에서 파일 로드
     fn load_from_memory_cache(
         &self,
@@ -511,6 +561,15 @@ impl FileLoadResult {
     pub fn is_memory_mapped(&self) -> bool {
         matches!(self, FileLoadResult::DirectMemoryMap(_))
     }
+
+    /// mmap 의 원본 파일. `sendfile` 이 같은 fd 에서 보내 mmap 과 같은 내용을 쓴다.
+    #[cfg(all(feature = "sendfile", target_os = "linux"))]
+    pub(crate) fn mapped_file(&self) -> Option<&File> {
+        match self {
+            FileLoadResult::MemoryCache(_) => None,
+            FileLoadResult::DirectMemoryMap(mmap_file) => Some(&mmap_file._file),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -663,4 +722,39 @@ mod tests {
         std::fs::remove_file(&file_path).ok();
         std::fs::remove_dir(&dir).ok();
     }
+
+    #[test]
+    fn test_validators_follow_cache_and_modification() {
+        let cache = create_test_cache();
+        let dir = std::env::temp_dir().join("atomic_http_test_validators");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file_path = dir.join("test_validators.txt");
+        std::fs::write(&file_path, b"data").unwrap();
+
+        let before = cache.validators(&file_path).unwrap();
+        assert!(before.etag.starts_with('"') && before.etag.ends_with('"'));
+        cache.load_file(&file_path).unwrap();
+        let cached = cache
+            .memory_cache
+            .get(&file_path)
+            .unwrap()
+            .validators()
+            .clone();
+        assert_eq!(cached, before);
+        assert_eq!(cache.validators(&file_path).unwrap(), before);
+
+        // mtime 이 바뀌면 캐시 항목이 남아 있어도 새 ETag
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&file_path)
+            .unwrap();
+        file.set_modified(before.last_modified + Duration::from_secs(5))
+            .unwrap();
+        drop(file);
+        let after = cache.validators(&file_path).unwrap();
+        assert_ne!(after.etag, before.etag);
+
+        std::fs::remove_file(&file_path).ok();
+        std::fs::remove_dir(&dir).ok();
+    }
 }
@@ -11,6 +11,44 @@ use serde::{Deserialize, Serialize};
 
 pub mod helpers;
 
+pub mod connection;
+
+pub mod connection_limit;
+
+pub mod shutdown;
+
+pub mod socket;
+
+pub mod serve;
+
+pub mod middleware;
+
+pub mod proxy_protocol;
+
+pub mod forwarded;
+
+mod chunked;
+
+pub mod response_body;
+
+#[cfg(feature = "decompression")]
+mod decompress;
+
+#[cfg(feature = "compression")]
+pub mod compression;
+
+#[cfg(feature = "response_file")]
+mod conditional;
+
+#[cfg(feature = "response_file")]
+mod range;
+
+#[cfg(all(feature = "sendfile", target_os = "linux"))]
+mod sendfile;
+
+#[cfg(test)]
+mod test_support;
+
 #[cfg(feature = "connection_pool")]
 pub mod connection_pool;
 
@@ -20,25 +58,53 @@ pub mod websocket;
 #[cfg(feature = "router")]
 pub mod router;
 
-pub use helpers::traits::http_request::RequestUtils;
 #[cfg(feature = "arena")]
 pub use helpers::traits::http_request::RequestUtilsArena;
+pub use helpers::traits::http_request::{RequestUtils, TrailersExt};
 pub use helpers::traits::http_response::ResponseUtil;
 #[cfg(feature = "arena")]
 pub use helpers::traits::http_response::ResponseUtilArena;
-pub use helpers::traits::http_stream::StreamHttp;
+pub use helpers::traits::http_stream::{FramingError, ParseError, StreamHttp};
 
 #[cfg(feature = "arena")]
 pub use helpers::traits::http_stream::{StreamHttpArena, StreamHttpArenaWriter};
 
+pub use connection::Connection;
+
+pub use connection_limit::ConnectionLimitPolicy;
+
+pub use shutdown::{ConnectionGuard, ShutdownHandle};
+
+#[cfg(unix)]
+pub use socket::UCred;
+pub use socket::{Listener, ListenerId, Peer, Stream};
+
+pub use serve::{Handler, StatusError};
+
+pub use middleware::{Chain, Flow, Middleware};
+
+#[cfg(feature = "response_file")]
+pub use response_body::FileBody;
+pub use response_body::{BodyStream, BodyWriter, ResponseBody};
+
+pub use proxy_protocol::{IpCidr, ProxyProtocol};
+
+pub use forwarded::{ClientInfo, ClientInfoExt};
+
+#[cfg(feature = "websocket")]
+pub use serve::WebSocketHandler;
+
 pub use helpers::traits::zero_copy::{
-    parse_json_file, CacheConfig, CacheStats, CachedFileData, FileLoadResult, ZeroCopyCache,
-    ZeroCopyFile,
+    parse_json_file, CacheConfig, CacheStats, CachedFileData, FileLoadResult, FileValidators,
+    ZeroCopyCache, ZeroCopyFile,
 };
 
 #[cfg(feature = "connection_pool")]
 pub use connection_pool::{ConnectionPool, ConnectionPoolConfig, ConnectionStats};
 
+#[cfg(feature = "compression")]
+pub use compression::CompressionConfig;
+
 #[cfg(feature = "websocket")]
 pub use websocket::StreamResult;
 
@@ -79,8 +145,12 @@ macro_rules! dev_print {
 
 use tokio::net::TcpListener;
 
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
-use tokio::net::TcpStream;
+use chunked::ChunkedDecoder;
+use connection_limit::AcceptGate;
+use helpers::traits::http_stream::{Framing, CONTINUE_RESPONSE};
+use response_body::SinkState;
+
+use socket::{AcceptSet, OwnedReadHalf, OwnedWriteHalf};
 
 pub use bytes::Bytes;
 
@@ -109,14 +179,34 @@ pub enum AutoParseResult {
     },
 }
 
+impl AutoParseResult {
+    pub(crate) fn set_guard(&mut self, guard: Option<ConnectionGuard>) {
+        match self {
+            #[cfg(feature = "arena")]
+            AutoParseResult::Arena { response, .. } => response.body_mut().guard = guard,
+            AutoParseResult::Streaming { response, .. } => response.body_mut().guard = guard,
+        }
+    }
+}
+
 pub struct Server {
-    pub listener: TcpListener,
+    /// 바인드된 TCP / (unix) UDS listener 들. 0번 (`ListenerId::PRIMARY`) 이
+    /// `Server::new` / `Server::bind_unix` 로 만든 기본 listener, 이후는 `add_listener*` 순서.
+    listeners: Vec<Listener>,
+    /// `listeners` 와 같은 순서의 listener 전용 옵션. `None` 이면 `options` 를 따른다.
+    listener_options: Vec<Option<Arc<Options>>>,
+    /// 다음 `accept()` 에서 가장 먼저 poll 할 listener (round-robin).
+    next_listener: usize,
     /// 공유 옵션. 요청마다 `Arc::clone`만 하면 됨 (full clone 없음).
     /// 설정 변경 시 `Arc::make_mut`로 단일 소유일 때만 in-place 수정,
     /// 공유 중이면 자동으로 copy-on-write.
     pub options: Arc<Options>,
     #[cfg(feature = "connection_pool")]
     pub connection_pool: Option<Arc<ConnectionPool>>,
+    /// graceful shutdown 트리거 + `accept()` 로 발급된 연결 추적.
+    shutdown: ShutdownHandle,
+    /// 동시 연결 한도. 첫 `accept()` 때 옵션으로부터 생성 (이후 옵션 변경은 반영 안 됨).
+    gate: Option<AcceptGate>,
 }
 
 #[derive(Debug, Clone)]
@@ -132,8 +222,53 @@ pub struct Options {
     /// 헤더 전체 수신 데드라인 (밀리초). `None`이면 자동 = read_timeout * (read_max_retry + 1).
     /// Slowloris 방어 — 클라이언트가 헤더를 1바이트씩 천천히 흘려도 이 시간 내 완료되지 않으면 거부.
     pub header_read_deadline_ms: Option<u64>,
+    /// 요청 헤더 필드 최대 개수. 넘으면 `431`. 기본 64.
+    pub max_headers: usize,
+    /// 요청 라인 + 헤더 전체 최대 크기 (바이트). 넘으면 `431`. 기본 64KB.
+    pub max_header_size: usize,
+    /// 헤더 한 줄 (`name: value`) 최대 길이 (바이트). 넘으면 `431`. 기본 8KB.
+    pub max_header_line: usize,
+    /// request-target (URI) 최대 길이 (바이트). 넘으면 `414`. 기본 8KB.
+    pub max_uri_length: usize,
+    /// chunked body 의 trailer 섹션 최대 크기 (바이트). 넘으면 `431`. 기본 64KB.
+    pub max_trailer_size: usize,
     pub zero_copy_threshold: usize,
     pub enable_file_cache: bool,
+    /// 동시 연결 최대 수 (전체). `None`이면 무제한 (기존 동작).
+    /// `accept()` 가 발급한 연결이 닫힐 때까지 (`Writer` / `Connection` drop) 슬롯을 차지한다.
+    pub max_connections: Option<usize>,
+    /// peer IP 당 동시 연결 최대 수. `None`이면 무제한.
+    pub max_connections_per_ip: Option<usize>,
+    /// 한도 초과 시 대기할지 (`Wait`, 기본) 503 으로 즉시 거절할지 (`Reject`).
+    pub connection_limit_policy: ConnectionLimitPolicy,
+    /// `Wait` 정책에서 IP별 한도로 대기할 수 있는 연결 수. 넘으면 503. 기본 1024.
+    pub max_parked_connections: usize,
+    /// `Server::serve*` 가 shutdown 트리거 후 연결 정리를 기다리는 최대 시간 (밀리초).
+    pub shutdown_deadline_ms: u64,
+    /// 연결 첫머리의 PROXY protocol (v1/v2) preamble 처리. 기본 `Off`.
+    /// 켜면 preamble 의 원래 클라이언트 주소가 `peer` / `Body::ip` 가 된다.
+    pub proxy_protocol: ProxyProtocol,
+    /// PROXY preamble 을 믿을 upstream (load balancer) 대역. 비어 있으면 어떤 TCP peer 도
+    /// 믿지 않는다 (모두 믿으려면 `0.0.0.0/0`, `::/0`). UDS peer 는 항상 신뢰.
+    pub proxy_protocol_trusted: Vec<IpCidr>,
+    /// `Forwarded` / `X-Forwarded-*` 를 믿을 reverse proxy 대역. 비어 있으면 헤더를 보지 않고
+    /// `ClientInfoExt::client_info()` 가 TCP peer 를 그대로 돌려준다.
+    pub trusted_proxies: Vec<IpCidr>,
+    /// 요청을 읽다가 실패하면 (`ParseError`) 상태 코드에 맞는 최소 응답 (400 / 408 / 413 / 431)
+    /// 을 쓰고 연결을 닫는다. 기본 `true`. `false` 면 응답 없이 닫고 에러만 반환.
+    pub error_responses: bool,
+    /// 요청 `Content-Encoding` (gzip / deflate / br) 을 풀어 핸들러에 넘긴다. 기본 `false`.
+    /// `max_body_size` 는 해제된 크기에 적용되고 (압축 폭탄 방어), 모르는 coding 은 `415`.
+    /// 해제한 요청에서는 `Content-Encoding` / `Content-Length` 헤더를 지운다.
+    #[cfg(feature = "decompression")]
+    pub decompress_request_body: bool,
+    /// 응답 압축 (`Accept-Encoding` 협상, gzip / br). 기본 꺼짐.
+    #[cfg(feature = "compression")]
+    pub compression_option: CompressionConfig,
+    /// 큰 파일 응답을 `sendfile(2)` 로 커널이 파일에서 소켓으로 바로 보낸다. 기본 `false` (켜려면 `true`).
+    /// Linux 의 평문 TCP 연결에서만 쓰이고, TLS / UDS / 압축 응답은 기존 경로로 보낸다.
+    #[cfg(feature = "sendfile")]
+    pub sendfile: bool,
 
     // Connection pooling configuration
     #[cfg(feature = "connection_pool")]
@@ -149,10 +284,30 @@ impl Options {
             root_path: current_dir().unwrap_or_else(|_| PathBuf::from(".")),
             read_buffer_size: 4096,
             read_max_retry: 3,
-            max_body_size: None,              // 기본 무제한 (기존 동작 보존)
-            header_read_deadline_ms: None,    // 기본: read_timeout * (max_retry+1)
+            max_body_size: None,           // 기본 무제한 (기존 동작 보존)
+            header_read_deadline_ms: None, // 기본: read_timeout * (max_retry+1)
+            max_headers: 64,
+            max_header_size: 64 * 1024,
+            max_header_line: 8 * 1024,
+            max_uri_length: 8 * 1024,
+            max_trailer_size: 64 * 1024,
             zero_copy_threshold: 1024 * 1024, // 1MB 이상 파일에 제로카피 적용
             enable_file_cache: true,
+            max_connections: None,        // 기본 무제한 (기존 동작 보존)
+            max_connections_per_ip: None, // 기본 무제한
+            connection_limit_policy: ConnectionLimitPolicy::Wait,
+            max_parked_connections: 1024,
+            shutdown_deadline_ms: 30_000,
+            proxy_protocol: ProxyProtocol::Off,
+            proxy_protocol_trusted: Vec::new(),
+            trusted_proxies: Vec::new(),
+            error_responses: true,
+            #[cfg(feature = "decompression")]
+            decompress_request_body: false,
+            #[cfg(feature = "compression")]
+            compression_option: CompressionConfig::default(),
+            #[cfg(feature = "sendfile")]
+            sendfile: false,
 
             // Connection pooling enabled by default with nginx-like settings
             #[cfg(feature = "connection_pool")]
@@ -203,6 +358,36 @@ impl Options {
                 }
             }
 
+            if let Ok(data) = env::var("MAX_HEADERS") {
+                if let Ok(data) = data.parse::<usize>() {
+                    _options.max_headers = data;
+                }
+            }
+
+            if let Ok(data) = env::var("MAX_HEADER_SIZE") {
+                if let Ok(data) = data.parse::<usize>() {
+                    _options.max_header_size = data;
+                }
+            }
+
+            if let Ok(data) = env::var("MAX_HEADER_LINE") {
+                if let Ok(data) = data.parse::<usize>() {
+                    _options.max_header_line = data;
+                }
+            }
+
+            if let Ok(data) = env::var("MAX_URI_LENGTH") {
+                if let Ok(data) = data.parse::<usize>() {
+                    _options.max_uri_length = data;
+                }
+            }
+
+            if let Ok(data) = env::var("MAX_TRAILER_SIZE") {
+                if let Ok(data) = data.parse::<usize>() {
+                    _options.max_trailer_size = data;
+                }
+            }
+
             if let Ok(data) = env::var("ZERO_COPY_THRESHOLD") {
                 if let Ok(data) = data.parse::<usize>() {
                     _options.zero_copy_threshold = data;
@@ -215,6 +400,103 @@ impl Options {
                 }
             }
 
+            if let Ok(data) = env::var("MAX_CONNECTIONS") {
+                if let Ok(data) = data.parse::<usize>() {
+                    _options.max_connections = Some(data);
+                }
+            }
+
+            if let Ok(data) = env::var("MAX_CONNECTIONS_PER_IP") {
+                if let Ok(data) = data.parse::<usize>() {
+                    _options.max_connections_per_ip = Some(data);
+                }
+            }
+
+            if let Ok(data) = env::var("CONNECTION_LIMIT_POLICY") {
+                // wait, reject
+                if let Ok(data) = data.parse::<ConnectionLimitPolicy>() {
+                    _options.connection_limit_policy = data;
+                }
+            }
+
+            if let Ok(data) = env::var("MAX_PARKED_CONNECTIONS") {
+                if let Ok(data) = data.parse::<usize>() {
+                    _options.max_parked_connections = data;
+                }
+            }
+
+            if let Ok(data) = env::var("SHUTDOWN_DEADLINE_MS") {
+                if let Ok(data) = data.parse::<u64>() {
+                    _options.shutdown_deadline_ms = data;
+                }
+            }
+
+            if let Ok(data) = env::var("PROXY_PROTOCOL") {
+                // off, optional, required
+                if let Ok(data) = data.parse::<ProxyProtocol>() {
+                    _options.proxy_protocol = data;
+                }
+            }
+
+            if let Ok(data) = env::var("PROXY_PROTOCOL_TRUSTED") {
+                // 10.0.0.0/8,192.168.0.0/16
+                _options.proxy_protocol_trusted = data
+                    .split(',')
+                    .filter_map(|cidr| cidr.parse::<IpCidr>().ok())
+                    .collect();
+            }
+
+            if let Ok(data) = env::var("TRUSTED_PROXIES") {
+                // 10.0.0.0/8,::1
+                _options.trusted_proxies = data
+                    .split(',')
+                    .filter_map(|cidr| cidr.parse::<IpCidr>().ok())
+                    .collect();
+            }
+
+            if let Ok(data) = env::var("ERROR_RESPONSES") {
+                if let Ok(data) = data.parse::<bool>() {
+                    _options.error_responses = data;
+                }
+            }
+
+            #[cfg(feature = "decompression")]
+            if let Ok(data) = env::var("DECOMPRESS_REQUEST_BODY") {
+                if let Ok(data) = data.parse::<bool>() {
+                    _options.decompress_request_body = data;
+                }
+            }
+
+            #[cfg(feature = "compression")]
+            {
+                if let Ok(data) = env::var("COMPRESSION") {
+                    if let Ok(data) = data.parse::<bool>() {
+                        _options.compression_option.enabled = data;
+                    }
+                }
+
+                if let Ok(data) = env::var("COMPRESSION_MIN_SIZE") {
+                    if let Ok(data) = data.parse::<usize>() {
+                        _options.compression_option.min_size = data;
+                    }
+                }
+
+                if let Ok(data) = env::var("COMPRESSION_CONTENT_TYPES") {
+                    _options.compression_option.content_types = data
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                }
+            }
+
+            #[cfg(feature = "sendfile")]
+            if let Ok(data) = env::var("SENDFILE") {
+                if let Ok(data) = data.parse::<bool>() {
+                    _options.sendfile = data;
+                }
+            }
+
             // Connection pooling environment variables
             #[cfg(feature = "connection_pool")]
             {
@@ -297,33 +579,170 @@ impl Server {
     pub async fn new(address: &str) -> Result<Server, SendableError> {
         dev_print!("✅ Server initialized with Arena support");
 
-        let mut server = Server {
-            listener: TcpListener::bind(address).await?,
-            options: Arc::new(Options::new()),
-            #[cfg(feature = "connection_pool")]
-            connection_pool: None,
-        };
-
-        // Auto-enable connection pool if enabled in default options
-        #[cfg(feature = "connection_pool")]
-        if server.options.is_connection_pool_enabled() {
-            if let Err(e) = server.enable_connection_pool() {
-                dev_print!("⚠️  Failed to enable connection pool: {}", e);
-            }
-        }
-
-        Ok(server)
+        let listener = TcpListener::bind(address).await?;
+        Ok(Self::from_parts(listener.into(), Options::new()))
     }
 
     /// Create server with custom options (including connection pool configuration)
     pub async fn with_options(address: &str, options: Options) -> Result<Server, SendableError> {
         dev_print!("✅ Server initialized with custom options");
 
+        let listener = TcpListener::bind(address).await?;
+        Ok(Self::from_parts(listener.into(), options))
+    }
+
+    /// Unix domain socket 에 바인드 (로컬 reverse proxy 뒤에서 사용).
+    /// 이전 프로세스가 남긴 socket 파일은 지우고 다시 바인드하며, 파일은 `Server` drop 시 삭제된다.
+    /// 연결의 peer 는 `Peer::Unix` (uid/gid/pid) 이고 요청 extensions 에 `UCred` 가 들어간다.
+    #[cfg(unix)]
+    pub async fn bind_unix(path: impl AsRef<std::path::Path>) -> Result<Server, SendableError> {
+        Self::bind_unix_with_options(path, Options::new()).await
+    }
+
+    /// `bind_unix` + 사용자 옵션.
+    #[cfg(unix)]
+    pub async fn bind_unix_with_options(
+        path: impl AsRef<std::path::Path>,
+        options: Options,
+    ) -> Result<Server, SendableError> {
+        dev_print!("✅ Server bound to unix socket {}", path.as_ref().display());
+        let listener = socket::UnixSocketListener::bind(path)?;
+        Ok(Self::from_parts(Listener::Unix(listener), options))
+    }
+
+    /// 미리 열어둔 listener 로 서버 생성 (권한 분리 bind, 무중단 재시작 시 fd 상속 등).
+    ///
+    /// ```rust,no_run
+    /// # use atomic_http::*;
+    /// # async fn run() -> Result<(), SendableError> {
+    /// let std_listener = std::net::TcpListener::bind("0.0.0.0:80")?;
+    /// // ... 권한 내려놓기
+    /// let server = Server::from_listener(Listener::from_std(std_listener)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_listener(listener: Listener) -> Server {
+        Self::from_parts(listener, Options::new())
+    }
+
+    /// `from_listener` + 사용자 옵션.
+    pub fn from_listener_with_options(listener: Listener, options: Options) -> Server {
+        Self::from_parts(listener, options)
+    }
+
+    /// systemd socket activation 으로 넘겨받은 listener 들로 서버 생성.
+    /// 첫 fd 가 `ListenerId::PRIMARY`, 나머지는 순서대로 추가 listener (서버 옵션 공유).
+    /// 넘겨받은 socket 이 없으면 에러 — 직접 bind 로 fallback 하려면 `socket::listen_fds` 사용.
+    #[cfg(unix)]
+    pub fn from_systemd() -> Result<Server, SendableError> {
+        Self::from_systemd_with_options(Options::new())
+    }
+
+    /// `from_systemd` + 사용자 옵션.
+    #[cfg(unix)]
+    pub fn from_systemd_with_options(options: Options) -> Result<Server, SendableError> {
+        let mut listeners = socket::listen_fds()?.into_iter();
+        let primary = listeners.next().ok_or("no sockets passed via LISTEN_FDS")?;
+        let mut server = Self::from_parts(primary, options);
+        for listener in listeners {
+            server.attach_listener(listener, None);
+        }
+        dev_print!(
+            "✅ Server initialized from {} activated socket(s)",
+            server.listeners.len()
+        );
+        Ok(server)
+    }
+
+    /// `SO_REUSEPORT` 로 같은 `address` 에 listener 를 `acceptors` 개 열고, 각각을 독립된 `Server`
+    /// 로 돌려준다. 커널이 새 연결을 listener 들에 분산하므로 서버마다 별도 task 에서
+    /// `serve*` (또는 accept 루프) 를 돌리면 accept 경합 없이 코어 수만큼 확장된다.
+    ///
+    /// 옵션 (`Arc<Options>`), connection pool, 연결 한도, shutdown handle 은 모두 공유한다.
+    /// 아무 서버의 `shutdown_handle().trigger()` 로 전체가 멈추며, 각 `serve*` 는 모든 연결이
+    /// 정리될 때까지 기다린다. 생성 후 `options_mut()` 은 해당 서버에만 적용된다 (copy-on-write).
+    ///
+    /// ```rust,no_run
+    /// # use atomic_http::*;
+    /// # async fn run<H: Handler + Clone>(handler: H) -> Result<(), SendableError> {
+    /// let acceptors = std::thread::available_parallelism().map_or(1, |n| n.get());
+    /// let servers = Server::bind_reuseport("0.0.0.0:8080", acceptors, Options::new()).await?;
+    /// let tasks: Vec<_> = servers
+    ///     .into_iter()
+    ///     .map(|server| tokio::spawn(server.serve(handler.clone())))
+    ///     .collect();
+    /// for task in tasks {
+    ///     task.await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(all(
+        unix,
+        not(any(target_os = "solaris", target_os = "illumos", target_os = "cygwin"))
+    ))]
+    pub async fn bind_reuseport(
+        address: &str,
+        acceptors: usize,
+        options: Options,
+    ) -> Result<Vec<Server>, SendableError> {
+        let mut addr = tokio::net::lookup_host(address)
+            .await?
+            .next()
+            .ok_or_else(|| format!("could not resolve {}", address))?;
+
+        let mut listeners = Vec::with_capacity(acceptors.max(1));
+        for _ in 0..acceptors.max(1) {
+            let socket = if addr.is_ipv4() {
+                tokio::net::TcpSocket::new_v4()?
+            } else {
+                tokio::net::TcpSocket::new_v6()?
+            };
+            socket.set_reuseaddr(true)?;
+            socket.set_reuseport(true)?;
+            socket.bind(addr)?;
+            let listener = socket.listen(1024)?;
+            // 포트 0 이면 첫 listener 가 받은 포트로 나머지를 연다
+            addr = listener.local_addr()?;
+            listeners.push(listener);
+        }
+        dev_print!(
+            "✅ Server bound to {} with {} SO_REUSEPORT acceptor(s)",
+            addr,
+            listeners.len()
+        );
+
+        let mut listeners = listeners.into_iter();
+        let mut first = Self::from_parts(listeners.next().unwrap().into(), options);
+        let gate = AcceptGate::new(&first.options);
+        let mut servers = Vec::with_capacity(acceptors.max(1));
+        for listener in listeners {
+            servers.push(Server {
+                listeners: vec![listener.into()],
+                listener_options: vec![None],
+                next_listener: 0,
+                options: Arc::clone(&first.options),
+                #[cfg(feature = "connection_pool")]
+                connection_pool: first.connection_pool.clone(),
+                shutdown: first.shutdown.clone(),
+                gate: Some(gate.share()),
+            });
+        }
+        first.gate = Some(gate);
+        servers.insert(0, first);
+        Ok(servers)
+    }
+
+    fn from_parts(listener: Listener, options: Options) -> Server {
         let mut server = Server {
-            listener: TcpListener::bind(address).await?,
+            listeners: vec![listener],
+            listener_options: vec![None],
+            next_listener: 0,
             options: Arc::new(options),
             #[cfg(feature = "connection_pool")]
             connection_pool: None,
+            shutdown: ShutdownHandle::new(),
+            gate: None,
         };
 
         // Auto-enable connection pool if enabled in options
@@ -334,7 +753,77 @@ impl Server {
             }
         }
 
-        Ok(server)
+        server
+    }
+
+    /// `address` 에 listener 를 하나 더 바인드 (공개 포트 + 관리 포트, `0.0.0.0` + `[::]` 등).
+    /// 옵션은 서버 옵션 (`options`) 을 그대로 따른다.
+    pub async fn add_listener(&mut self, address: &str) -> Result<ListenerId, SendableError> {
+        let listener = TcpListener::bind(address).await?;
+        Ok(self.attach_listener(listener.into(), None))
+    }
+
+    /// `add_listener` + 이 listener 로 들어온 연결에만 쓸 옵션 (`max_body_size`, timeout 등).
+    /// 연결 한도 (`max_connections*`) 와 connection pool 은 서버 옵션 기준으로 전체 listener 가 공유한다.
+    pub async fn add_listener_with_options(
+        &mut self,
+        address: &str,
+        options: Options,
+    ) -> Result<ListenerId, SendableError> {
+        let listener = TcpListener::bind(address).await?;
+        Ok(self.attach_listener(listener.into(), Some(options)))
+    }
+
+    /// Unix domain socket listener 추가 (`bind_unix` 와 같은 stale 파일 처리).
+    #[cfg(unix)]
+    pub fn add_unix_listener(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<ListenerId, SendableError> {
+        let listener = socket::UnixSocketListener::bind(path)?;
+        Ok(self.attach_listener(Listener::Unix(listener), None))
+    }
+
+    /// `add_unix_listener` + listener 전용 옵션.
+    #[cfg(unix)]
+    pub fn add_unix_listener_with_options(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+        options: Options,
+    ) -> Result<ListenerId, SendableError> {
+        let listener = socket::UnixSocketListener::bind(path)?;
+        Ok(self.attach_listener(Listener::Unix(listener), Some(options)))
+    }
+
+    /// 이미 열린 listener 추가 (`Listener::from_std`, `socket::listen_fds` 등).
+    /// `options` 가 `None` 이면 서버 옵션을 따른다.
+    pub fn attach_listener(&mut self, listener: Listener, options: Option<Options>) -> ListenerId {
+        dev_print!("✅ Listener #{} added", self.listeners.len());
+        self.listeners.push(listener);
+        self.listener_options.push(options.map(Arc::new));
+        ListenerId(self.listeners.len() - 1)
+    }
+
+    /// 기본 listener (`ListenerId::PRIMARY`).
+    pub fn listener(&self) -> &Listener {
+        &self.listeners[ListenerId::PRIMARY.0]
+    }
+
+    /// 바인드된 모든 listener.
+    pub fn listeners(&self) -> impl Iterator<Item = (ListenerId, &Listener)> {
+        self.listeners
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (ListenerId(i), l))
+    }
+
+    /// `id` listener 로 들어온 연결이 쓰는 옵션.
+    /// 없는 id 거나 전용 옵션이 없으면 서버 옵션.
+    pub fn listener_options(&self, id: ListenerId) -> &Arc<Options> {
+        match self.listener_options.get(id.0) {
+            Some(Some(options)) => options,
+            _ => &self.options,
+        }
     }
 
     /// 단일 소유 상태일 때만 in-place 수정. 공유 중이면 copy-on-write.
@@ -406,10 +895,22 @@ impl Server {
         }
     }
 
+    /// 다음 연결 수락. shutdown 이 시작되면 대기 중이던 호출도 즉시 에러를 반환하므로
+    /// accept 루프는 `is_shutting_down()` 으로 빠져나온 뒤 `shutdown(deadline)` 을 호출한다.
     pub async fn accept(&mut self) -> Result<Accept, SendableError> {
         use std::time::Duration;
 
-        let (stream, addr) = match self.listener.accept().await {
+        let gate = self
+            .gate
+            .get_or_insert_with(|| AcceptGate::new(&self.options));
+        let listeners = AcceptSet::new(&self.listeners, self.next_listener);
+        self.next_listener = (self.next_listener + 1) % self.listeners.len();
+        let accepted = tokio::select! {
+            biased;
+            _ = self.shutdown.triggered() => return Err("server is shutting down".into()),
+            accepted = gate.accept(&listeners) => accepted,
+        };
+        let (stream, peer, listener, permit) = match accepted {
             Ok(data) => data,
             Err(e) => {
                 if is_connection_error(&e) {
@@ -421,7 +922,51 @@ impl Server {
             }
         };
         // Options는 이미 Arc — 요청마다 atomic increment 1회. peer는 Accept에 별도 저장.
-        Ok(Accept::new(stream, Arc::clone(&self.options), addr))
+        let options = Arc::clone(self.listener_options(listener));
+        let mut accept = Accept::new(stream, options, peer);
+        accept.listener = listener;
+        accept.guard = Some(self.shutdown.track_with(permit));
+        Ok(accept)
+    }
+
+    /// 외부 (시그널 task, 관리 API 등) 에서 shutdown 을 트리거할 handle.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown.is_shutting_down()
+    }
+
+    /// graceful shutdown. listener를 닫아 새 연결을 막고, connection pool (cleanup task +
+    /// pooled socket) 을 정리한 뒤 `accept()` 로 나간 연결이 끝나길 `deadline` 까지 기다린다.
+    ///
+    /// shutdown 중 keep-alive `Connection` 은 다음 응답에 `Connection: close` 를 붙이고,
+    /// idle 상태면 즉시 닫힌다. WebSocket 처럼 핸들러가 소유한 연결은
+    /// `ConnectionGuard::triggered()` 를 보고 스스로 닫아야 한다.
+    /// 반환값은 deadline 시점에 남아있는 연결 수 (0이면 모두 정리됨).
+    pub async fn shutdown(self, deadline: std::time::Duration) -> usize {
+        self.shutdown.trigger();
+        let Server {
+            listeners,
+            #[cfg(feature = "connection_pool")]
+            connection_pool,
+            shutdown,
+            ..
+        } = self;
+        drop(listeners);
+
+        #[cfg(feature = "connection_pool")]
+        if let Some(pool) = connection_pool {
+            pool.shutdown().await;
+        }
+
+        let remaining = shutdown.drain(deadline).await;
+        dev_print!(
+            "graceful shutdown finished, {} connection(s) left",
+            remaining
+        );
+        remaining
     }
 
     pub fn set_no_delay(&mut self, no_delay: bool) {
@@ -436,36 +981,58 @@ impl Server {
 }
 
 pub struct Accept {
-    pub tcp_stream: TcpStream,
+    pub stream: Stream,
     pub option: Arc<Options>,
-    /// 요청 송신자 주소. 요청별 값이므로 Options와 분리하여 여기에 보관.
-    pub peer: SocketAddr,
+    /// 요청 송신자 (TCP 주소 또는 UDS peer credentials). 요청별 값이므로 Options와 분리하여 여기에 보관.
+    pub peer: Peer,
+    /// 연결이 들어온 listener. `Accept::new` 로 직접 만들면 `ListenerId::PRIMARY`.
+    pub listener: ListenerId,
+    /// `Server::accept` 로 받은 연결의 shutdown 추적 토큰. 파싱 후 `Writer` 등으로 옮겨진다.
+    pub(crate) guard: Option<ConnectionGuard>,
 }
 
 impl Accept {
-    pub fn new(tcp_stream: TcpStream, option: Arc<Options>, peer: SocketAddr) -> Self {
+    pub fn new(stream: impl Into<Stream>, option: Arc<Options>, peer: impl Into<Peer>) -> Self {
         Self {
-            tcp_stream,
+            stream: stream.into(),
             option,
-            peer,
+            peer: peer.into(),
+            listener: ListenerId::PRIMARY,
+            guard: None,
         }
     }
 
+    /// 이 연결의 shutdown 추적 토큰 (`Server::accept` 로 받은 경우).
+    /// clone 해서 보유하면 그동안 `Server::shutdown` 이 연결을 살아있는 것으로 센다.
+    pub fn connection_guard(&self) -> Option<ConnectionGuard> {
+        self.guard.clone()
+    }
+
     /// 요청 클라이언트의 IP 주소를 문자열로 반환 (`Options::get_request_ip` 대체).
+    /// UDS 연결이면 `Peer` 의 표시 문자열 (`unix(uid=.., pid=..)`).
     pub fn get_request_ip(&self) -> String {
-        self.peer.ip().to_string()
+        match self.peer.addr() {
+            Some(addr) => addr.ip().to_string(),
+            None => self.peer.to_string(),
+        }
+    }
+
+    /// 요청 클라이언트의 SocketAddr 반환. UDS 연결이면 `None` (`peer_credentials` 사용).
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        self.peer.addr()
     }
 
-    /// 요청 클라이언트의 SocketAddr 반환.
-    pub fn peer_addr(&self) -> SocketAddr {
-        self.peer
+    /// UDS 상대 프로세스의 credentials. TCP 연결이면 `None`.
+    #[cfg(unix)]
+    pub fn peer_credentials(&self) -> Option<UCred> {
+        self.peer.credentials()
     }
 
     pub async fn parse_request(self) -> Result<(Request<Body>, Response<Writer>), SendableError> {
-        Ok(self
-            .tcp_stream
-            .parse_request(self.option, self.peer)
-            .await?)
+        use crate::helpers::traits::http_stream::parse_buffered;
+        let (request, mut response) = parse_buffered(self.stream, self.option, self.peer).await?;
+        response.body_mut().guard = self.guard;
+        Ok((request, response))
     }
 
     /// 0.14.0 신규 — body를 streaming 모드로 받기. 헤더만 먼저 읽고
@@ -474,10 +1041,10 @@ impl Accept {
     pub async fn parse_request_streaming(
         self,
     ) -> Result<(Request<Body>, Response<Writer>), SendableError> {
-        use crate::helpers::traits::http_stream::StreamHttp;
-        self.tcp_stream
-            .parse_request_streaming(self.option, self.peer)
-            .await
+        use crate::helpers::traits::http_stream::parse_streaming;
+        let (request, mut response) = parse_streaming(self.stream, self.option, self.peer).await?;
+        response.body_mut().guard = self.guard;
+        Ok((request, response))
     }
 
     /// 0.14.0 신규 — 헤더만 먼저 읽고 `Content-Length` 보고 자동 분기.
@@ -495,85 +1062,35 @@ impl Accept {
         self,
         arena_cap: usize,
     ) -> Result<AutoParseResult, SendableError> {
-        use crate::helpers::traits::http_stream::{read_headers_only, HeaderReadResult};
-
-        self.tcp_stream.set_nodelay(self.option.no_delay)?;
-        let HeaderReadResult {
-            header_bytes,
-            leftover,
-            content_length,
-            stream,
-        } = read_headers_only(self.tcp_stream, &self.option).await?;
-
-        // arena 경로 분기: feature 있고, CL 있고, cap 이하
-        #[cfg(feature = "arena")]
-        if let Some(cl) = content_length {
-            if cl <= arena_cap {
-                use crate::helpers::traits::http_stream::{
-                    get_parse_result_arena_writer, parse_http_request_arena, read_remaining_body,
-                };
-
-                // 남은 body 마저 읽어 단일 Vec로 모으기
-                let (full_body, stream2) =
-                    read_remaining_body(leftover, stream, content_length, &self.option).await?;
-
-                // 헤더 + body 합쳐 ArenaBody 생성 (bump 안으로 복사)
-                let header_end = header_bytes.len();
-                let mut full = Vec::with_capacity(header_end + full_body.len());
-                full.extend_from_slice(&header_bytes);
-                full.extend_from_slice(&full_body);
-                let arena_body = ArenaBody::new(&full, header_end, header_end);
-
-                let request = parse_http_request_arena(arena_body)?;
-                let (request, response) =
-                    get_parse_result_arena_writer(request, stream2, self.option, self.peer)?;
-                return Ok(AutoParseResult::Arena { request, response });
-            }
-        }
-        // arena_cap 인자가 streaming 경로에선 안 쓰이지만 BREAKING 인자 없애지 않으려고 명시 변수화.
-        let _ = arena_cap;
-
-        // streaming 경로
-        use crate::helpers::traits::http_stream::get_request;
-        let request_buffered = get_request(header_bytes).await?;
-        let (parts, _empty_body) = request_buffered.into_parts();
+        use crate::helpers::traits::http_stream::parse_request_auto;
+        let mut result = parse_request_auto(self.stream, self.option, self.peer, arena_cap).await?;
+        result.set_guard(self.guard);
+        Ok(result)
+    }
 
-        let (read_half, write_half) = stream.into_split();
-        let body = Body::new_streaming(
-            leftover,
-            read_half,
-            content_length,
-            Some(self.peer),
-            self.option.max_body_size,
-        );
-        let request = Request::from_parts(parts, body);
-        let version = request.version();
-
-        let response = Response::builder()
-            .version(version)
-            .header(http::header::CONTENT_TYPE, "application/json")
-            .status(400)
-            .body(Writer {
-                stream: write_half,
-                body: String::new(),
-                bytes: vec![],
-                use_file: false,
-                options: self.option,
-            })?;
-        Ok(AutoParseResult::Streaming { request, response })
+    /// HTTP/1.1 keep-alive 드라이버로 전환. 같은 소켓에서 요청을 연속으로 처리하려면
+    /// `parse_request*` 대신 이것을 사용하고 응답 후 `Connection::recycle*` 로 소켓을 돌려준다.
+    pub fn into_connection(self) -> Connection {
+        let mut connection = Connection::new(self.stream, self.option, self.peer);
+        connection.listener = self.listener;
+        connection.guard = self.guard;
+        connection
     }
 
     #[cfg(feature = "websocket")]
     pub async fn stream_parse(self) -> Result<StreamResult, SendableError> {
-        self.tcp_stream.set_nodelay(self.option.no_delay)?;
-        websocket::try_upgrade(self.tcp_stream, self.option, self.peer).await
+        self.stream.set_nodelay(self.option.no_delay)?;
+        let mut result = websocket::try_upgrade(self.stream, self.option, self.peer).await?;
+        result.set_guard(self.guard);
+        Ok(result)
     }
 
     /// 0.14.0 신규 — WebSocket 분기 + HTTP auto (arena/streaming) 분기를 한 번에.
     /// `DEFAULT_AUTO_ARENA_CAP` (50 MiB) 사용. 커스텀 cap은 `stream_parse_auto_with_cap`.
     #[cfg(feature = "websocket")]
     pub async fn stream_parse_auto(self) -> Result<StreamResultAuto, SendableError> {
-        self.stream_parse_auto_with_cap(DEFAULT_AUTO_ARENA_CAP).await
+        self.stream_parse_auto_with_cap(DEFAULT_AUTO_ARENA_CAP)
+            .await
     }
 
     /// `stream_parse_auto` 의 명시적 cap 버전.
@@ -582,26 +1099,31 @@ impl Accept {
         self,
         arena_cap: usize,
     ) -> Result<StreamResultAuto, SendableError> {
-        self.tcp_stream.set_nodelay(self.option.no_delay)?;
-        websocket::try_upgrade_auto(self.tcp_stream, self.option, self.peer, arena_cap).await
+        self.stream.set_nodelay(self.option.no_delay)?;
+        let mut result =
+            websocket::try_upgrade_auto(self.stream, self.option, self.peer, arena_cap).await?;
+        result.set_guard(self.guard);
+        Ok(result)
     }
 
     #[cfg(all(feature = "websocket", feature = "arena"))]
     pub async fn stream_parse_arena(self) -> Result<StreamResultArena, SendableError> {
-        self.tcp_stream.set_nodelay(self.option.no_delay)?;
-        websocket::try_upgrade_arena(self.tcp_stream, self.option, self.peer).await
+        self.stream.set_nodelay(self.option.no_delay)?;
+        let mut result = websocket::try_upgrade_arena(self.stream, self.option, self.peer).await?;
+        result.set_guard(self.guard);
+        Ok(result)
     }
 
     #[cfg(feature = "arena")]
     pub async fn parse_request_arena_writer(
         self,
     ) -> Result<(Request<ArenaBody>, Response<ArenaWriter>), SendableError> {
-        use crate::helpers::traits::http_stream::StreamHttpArenaWriter;
+        use crate::helpers::traits::http_stream::parse_arena_writer;
 
-        Ok(self
-            .tcp_stream
-            .parse_request_arena_writer(self.option, self.peer)
-            .await?)
+        let (request, mut response) =
+            parse_arena_writer(self.stream, self.option, self.peer).await?;
+        response.body_mut().guard = self.guard;
+        Ok((request, response))
     }
 }
 
@@ -619,8 +1141,19 @@ pub struct Body {
     /// 남은 body를 스트리밍할 socket의 read half. None이면 EOF 또는 이미 소비됨.
     stream: Option<OwnedReadHalf>,
     /// Content-Length 헤더 값. `None`이면 헤더가 없거나 chunked.
-    /// (0.14.0 현재 chunked transfer encoding은 미지원 — 정확한 길이를 모르면 connection close까지 읽음.)
+    /// 둘 다 아니면 connection close까지 읽음.
     content_length: Option<usize>,
+    /// `Transfer-Encoding: chunked` 요청이면 디코더. `leftover` 와 socket 에서 읽은 원본을
+    /// 디코드해 돌려주며, 종료 chunk 이후 바이트는 `leftover` 에 남는다 (다음 요청).
+    chunked: Option<ChunkedDecoder>,
+    /// buffered 경로에서 미리 디코드한 chunked body 의 trailer (streaming 은 `chunked` 디코더가 보관).
+    trailers: Option<Box<http::HeaderMap>>,
+    /// `Options::decompress_request_body` 로 해제하는 `Content-Encoding` 디코더. chunked 디코드 뒤에 적용.
+    #[cfg(feature = "decompression")]
+    decoder: Option<Box<decompress::ContentDecoder>>,
+    /// 클라이언트가 `Expect: 100-continue` 로 body 전송을 기다리는 중. 첫 socket read 직전에
+    /// `100 Continue` 를 보내므로, body 를 읽지 않고 최종 응답하면 클라이언트는 body 를 보내지 않는다.
+    expect_continue: bool,
     /// 전체에서 이미 핸들러에 반환한 누적 바이트 수.
     consumed: usize,
     /// 광고/실제 body 크기 cap. `bytes(None)` 호출 시 `bytes(max_body_size)` 와 동일.
@@ -634,14 +1167,24 @@ impl Body {
     pub(crate) fn new_streaming(
         leftover: Vec<u8>,
         stream: OwnedReadHalf,
-        content_length: Option<usize>,
+        framing: Framing,
         ip: Option<SocketAddr>,
         max_body_size: Option<usize>,
+        max_trailer_size: usize,
     ) -> Self {
         Self {
+            expect_continue: framing.needs_continue(leftover.len()),
             leftover,
             stream: Some(stream),
-            content_length,
+            content_length: framing.content_length,
+            chunked: framing
+                .chunked
+                .then(|| ChunkedDecoder::new(max_body_size, max_trailer_size)),
+            trailers: None,
+            #[cfg(feature = "decompression")]
+            decoder: framing
+                .content_coding
+                .map(|coding| Box::new(decompress::ContentDecoder::new(coding, max_body_size))),
             consumed: 0,
             max_body_size,
             ip,
@@ -656,6 +1199,11 @@ impl Body {
             leftover: bytes,
             stream: None,
             content_length: Some(len),
+            chunked: None,
+            trailers: None,
+            #[cfg(feature = "decompression")]
+            decoder: None,
+            expect_continue: false,
             consumed: 0,
             max_body_size: None,
             ip,
@@ -672,11 +1220,37 @@ impl Body {
         self.max_body_size
     }
 
-    /// Content-Length 헤더 값 (있을 때).
+    /// Content-Length 헤더 값 (있을 때). chunked 요청이나 읽으면서 해제하는 압축 body 면 `None`.
     pub fn content_length(&self) -> Option<usize> {
+        #[cfg(feature = "decompression")]
+        if self.decoder.is_some() {
+            return None;
+        }
         self.content_length
     }
 
+    /// `Transfer-Encoding: chunked` 로 들어와 읽으면서 디코드하는 body 인지.
+    /// buffered / arena 경로는 파싱 시 이미 디코드하므로 `false`.
+    pub fn is_chunked(&self) -> bool {
+        self.chunked.is_some()
+    }
+
+    /// 클라이언트가 `Expect: 100-continue` 로 기다리고 있고 아직 `100 Continue` 를 보내지 않았는지.
+    /// `true` 인 동안 body 를 읽지 않고 4xx 등으로 응답하면 업로드를 받지 않고 거절할 수 있다.
+    pub fn expects_continue(&self) -> bool {
+        self.expect_continue
+    }
+
+    /// chunked body 의 trailer 필드 (`Digest` 등). streaming body 는 종료 chunk 까지 읽은 뒤에만,
+    /// buffered body (`parse_request` 등) 는 파싱 직후부터 `Some`. chunked 가 아니면 `None`.
+    /// trailer 섹션 크기는 `Options::max_trailer_size` 로 제한된다.
+    pub fn trailers(&self) -> Option<&http::HeaderMap> {
+        match &self.chunked {
+            Some(decoder) => decoder.is_done().then(|| decoder.trailers()),
+            None => self.trailers.as_deref(),
+        }
+    }
+
     /// **동기** body 바이트 접근. body가 streaming 모드인 경우, 이미 leftover에
     /// 들어와 있는 바이트만 반환 (실제 전체 body는 `bytes(cap).await?` 호출 필요).
     /// `from_bytes`로 만든 (테스트/internal) Body에서는 전체 바이트가 leftover에 있음.
@@ -693,8 +1267,23 @@ impl Body {
         self.ip
     }
 
-    /// 다음 청크. EOF (Content-Length 도달 or 연결 종료) 시 `None`.
+    /// 다음 청크. EOF (Content-Length 도달, 종료 chunk, 연결 종료) 시 `None`.
+    /// chunked 요청은 디코드된 데이터만 반환하고, `max_body_size` 를 디코드된 크기로 강제한다.
+    /// `Options::decompress_request_body` 가 켜져 있으면 `Content-Encoding` 도 풀어서 돌려준다.
     pub async fn read_chunk(&mut self) -> Result<Option<Bytes>, SendableError> {
+        #[cfg(feature = "decompression")]
+        if self.decoder.is_some() {
+            return self.read_decompressed_chunk().await;
+        }
+        self.read_raw_chunk().await
+    }
+
+    /// `Content-Encoding` 을 풀기 전의 다음 청크 (chunked 는 디코드됨).
+    async fn read_raw_chunk(&mut self) -> Result<Option<Bytes>, SendableError> {
+        if self.chunked.is_some() {
+            return self.read_decoded_chunk().await;
+        }
+
         // 1) leftover에 있는 바이트 먼저 소비.
         if !self.leftover.is_empty() {
             let chunk = std::mem::take(&mut self.leftover);
@@ -709,7 +1298,8 @@ impl Body {
             }
         }
 
-        // 3) socket에서 더 읽기.
+        // 3) socket에서 더 읽기. 클라이언트가 100-continue 를 기다리면 먼저 알린다.
+        self.send_continue().await?;
         let stream = match self.stream.as_mut() {
             Some(s) => s,
             None => return Ok(None),
@@ -737,19 +1327,87 @@ impl Body {
         Ok(Some(Bytes::from(buf)))
     }
 
+    /// 대기 중인 `Expect: 100-continue` 클라이언트에게 `100 Continue` 를 한 번 보낸다.
+    async fn send_continue(&mut self) -> Result<(), SendableError> {
+        if std::mem::take(&mut self.expect_continue) {
+            if let Some(stream) = self.stream.as_ref() {
+                stream.write_interim(CONTINUE_RESPONSE).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// chunked body 의 다음 디코드 조각. 원본을 64KB 씩 읽어 디코더에 넣고, chunk 헤더만
+    /// 들어온 경우처럼 디코드 결과가 비면 더 읽는다.
+    async fn read_decoded_chunk(&mut self) -> Result<Option<Bytes>, SendableError> {
+        use tokio::io::AsyncReadExt;
+        // expect_continue 는 leftover 가 비어 있을 때만 켜지므로 곧 socket 을 읽는다
+        self.send_continue().await?;
+        let Some(decoder) = self.chunked.as_mut() else {
+            return Ok(None);
+        };
+        loop {
+            if decoder.is_done() {
+                return Ok(None);
+            }
+            if self.leftover.is_empty() {
+                let stream = self.stream.as_mut().ok_or(ParseError::BodyIncomplete)?;
+                let mut buf = vec![0u8; 64 * 1024];
+                let n = stream.read(&mut buf).await?;
+                if n == 0 {
+                    self.stream = None;
+                    return Err(ParseError::BodyIncomplete.into());
+                }
+                buf.truncate(n);
+                self.leftover = buf;
+            }
+            let mut out = Vec::new();
+            let used = decoder.decode(&self.leftover, &mut out)?;
+            self.leftover.drain(..used);
+            if !out.is_empty() {
+                self.consumed += out.len();
+                return Ok(Some(Bytes::from(out)));
+            }
+        }
+    }
+
+    /// 압축 body 의 다음 해제 조각. 원본 조각이 헤더 / 체크섬뿐이라 해제 결과가 비면 더 읽는다.
+    #[cfg(feature = "decompression")]
+    async fn read_decompressed_chunk(&mut self) -> Result<Option<Bytes>, SendableError> {
+        loop {
+            let raw = self.read_raw_chunk().await?;
+            let Some(decoder) = self.decoder.as_mut() else {
+                return Ok(None);
+            };
+            let mut out = Vec::new();
+            match raw {
+                Some(raw) => decoder.decode(&raw, &mut out)?,
+                None => {
+                    decoder.finish()?;
+                    return Ok(None);
+                }
+            }
+            if !out.is_empty() {
+                return Ok(Some(Bytes::from(out)));
+            }
+        }
+    }
+
     /// 전체 body를 메모리에 버퍼링해 반환. `max` 초과 시 즉시 에러.
     /// `None`이면 무제한 (위험 — 신뢰된 환경에서만).
     pub async fn bytes(&mut self, max: Option<usize>) -> Result<Vec<u8>, SendableError> {
         // Content-Length 광고만으로 cap 초과 즉시 거부 (alloc 회피).
-        if let (Some(cl), Some(cap)) = (self.content_length, max) {
+        if let (Some(cl), Some(cap)) = (self.content_length(), max) {
             if cl > cap {
-                return Err(
-                    format!("body too large: content-length={} exceeds cap={}", cl, cap).into(),
-                );
+                return Err(ParseError::ContentLengthTooLarge {
+                    content_length: cl,
+                    limit: cap,
+                }
+                .into());
             }
         }
 
-        let mut buf = match self.content_length {
+        let mut buf = match self.content_length() {
             // CL 있고 cap 안이면 정확히 예약 (한 번 alloc).
             Some(cl) if max.map_or(true, |m| cl <= m) => Vec::with_capacity(cl),
             _ => Vec::new(),
@@ -757,11 +1415,7 @@ impl Body {
         while let Some(chunk) = self.read_chunk().await? {
             if let Some(cap) = max {
                 if buf.len() + chunk.len() > cap {
-                    return Err(format!(
-                        "body too large: exceeded cap={} during streaming read",
-                        cap
-                    )
-                    .into());
+                    return Err(ParseError::BodyTooLarge { limit: cap }.into());
                 }
             }
             buf.extend_from_slice(&chunk);
@@ -794,6 +1448,30 @@ impl Body {
     pub fn into_multipart(self, boundary: String) -> multer::Multipart<'static> {
         multer::Multipart::new(self.into_stream(), boundary)
     }
+
+    /// body를 끝까지 소비했는지 (Content-Length 도달 또는 stream 없음).
+    /// keep-alive 재사용 판정에 사용 — 덜 읽은 body가 다음 요청으로 오인되면 안 됨.
+    /// stream이 없으면 (buffered 모드) socket에 남은 body가 없으므로 항상 완료.
+    pub(crate) fn is_complete(&self) -> bool {
+        if let Some(decoder) = &self.chunked {
+            return self.stream.is_none() || decoder.is_done();
+        }
+        match (self.content_length, &self.stream) {
+            (_, None) => true,
+            (Some(cl), Some(_)) => self.leftover.is_empty() && self.consumed >= cl,
+            (None, Some(_)) => false,
+        }
+    }
+
+    /// body 뒤에 딸려 읽힌 바이트 (chunked 종료 이후의 다음 요청 앞부분). `is_complete` 일 때만 의미가 있다.
+    pub(crate) fn take_surplus(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.leftover)
+    }
+
+    /// streaming 모드에서 보유 중인 read half를 꺼낸다 (keep-alive 재조립용).
+    pub(crate) fn take_read_half(&mut self) -> Option<OwnedReadHalf> {
+        self.stream.take()
+    }
 }
 
 #[cfg(feature = "arena")]
@@ -896,10 +1574,74 @@ unsafe impl Sync for ArenaBody {}
 /// body 스트리밍이 끝나는 것이 일반적인 흐름.
 pub struct Writer {
     pub stream: OwnedWriteHalf,
-    pub body: String,
-    pub bytes: Vec<u8>,
-    pub use_file: bool,
+    /// 보낼 body. `responser` 가 variant 별로 (텍스트 / 바이트 / 파일 / 스트림) 보낸다.
+    pub body: ResponseBody,
     pub options: Arc<Options>,
+    /// buffered 파싱 경로에서 body를 다 읽은 뒤 남는 read half.
+    /// keep-alive 드라이버(`Connection`)가 응답 후 소켓을 재조립할 때 사용.
+    pub(crate) read_half: Option<OwnedReadHalf>,
+    /// shutdown 추적 토큰. shutdown 중이면 `responser` 가 `Connection: close` 를 붙인다.
+    pub(crate) guard: Option<ConnectionGuard>,
+    /// 요청의 `Accept-Encoding`. `Options::compression_option` 이 켜져 있으면 `responser` 가 협상에 쓴다.
+    #[cfg(feature = "compression")]
+    pub(crate) accept_encoding: Option<Box<http::HeaderValue>>,
+    /// `GET` 요청의 `Range` / `If-Range`. 파일 응답이면 `responser` 가 206 / 416 을 결정한다.
+    #[cfg(feature = "response_file")]
+    pub(crate) range: Option<Box<range::RangeRequest>>,
+    /// 요청의 조건 헤더 (`If-None-Match` 등). 파일 응답이면 `responser` 가 304 / 412 를 결정한다.
+    #[cfg(feature = "response_file")]
+    pub(crate) preconditions: Option<Box<conditional::Preconditions>>,
+    /// `body_writer` 로 직접 보내는 응답의 진행 상태.
+    pub(crate) sink_state: SinkState,
+}
+
+impl Writer {
+    pub fn new(stream: OwnedWriteHalf, options: Arc<Options>) -> Self {
+        Self {
+            stream,
+            body: ResponseBody::Empty,
+            options,
+            read_half: None,
+            guard: None,
+            #[cfg(feature = "compression")]
+            accept_encoding: None,
+            #[cfg(feature = "response_file")]
+            range: None,
+            #[cfg(feature = "response_file")]
+            preconditions: None,
+            sink_state: SinkState::Idle,
+        }
+    }
+
+    /// `response_file` 로 설정한 파일.
+    #[cfg(feature = "response_file")]
+    pub fn file(&self) -> Option<&FileBody> {
+        match &self.body {
+            ResponseBody::File(file) => Some(file),
+            _ => None,
+        }
+    }
+
+    /// 응답 압축 협상용으로 요청의 `Accept-Encoding` 을 기억해 둔다.
+    #[cfg(feature = "compression")]
+    pub(crate) fn with_accept_encoding(mut self, headers: &http::HeaderMap) -> Self {
+        self.accept_encoding = headers
+            .get(http::header::ACCEPT_ENCODING)
+            .map(|v| Box::new(v.clone()));
+        self
+    }
+
+    /// 파일 응답의 Range / 조건부 요청 처리용으로 요청 헤더를 기억해 둔다.
+    #[cfg(feature = "response_file")]
+    pub(crate) fn with_file_request(
+        mut self,
+        method: &http::Method,
+        headers: &http::HeaderMap,
+    ) -> Self {
+        self.range = range::RangeRequest::from_request(method, headers);
+        self.preconditions = conditional::Preconditions::from_request(method, headers);
+        self
+    }
 }
 
 fn is_connection_error(e: &io::Error) -> bool {
@@ -911,14 +1653,40 @@ fn is_connection_error(e: &io::Error) -> bool {
     )
 }
 
+/// `ArenaWriter` 가 보낼 body. 텍스트 / 바이트는 `_bump` 에 두고 포인터만 든다.
+#[cfg(feature = "arena")]
+pub(crate) enum ArenaResponse {
+    Empty,
+    Data {
+        ptr: *const u8,
+        len: usize,
+    },
+    #[cfg(feature = "response_file")]
+    File(FileBody),
+    Stream(BodyStream),
+}
+
 #[cfg(feature = "arena")]
 pub struct ArenaWriter {
     pub stream: OwnedWriteHalf,
     _bump: Option<Box<Bump>>,
-    response_data_ptr: *const u8,
-    response_data_len: usize,
-    pub use_file: bool,
+    pub(crate) body: ArenaResponse,
     pub options: Arc<Options>,
+    /// arena 경로는 body를 미리 다 읽으므로 read half를 여기 보관 (`Writer::read_half` 와 동일 용도).
+    pub(crate) read_half: Option<OwnedReadHalf>,
+    /// `Writer::guard` 와 동일 용도.
+    pub(crate) guard: Option<ConnectionGuard>,
+    /// `Writer::accept_encoding` 과 동일 용도.
+    #[cfg(feature = "compression")]
+    pub(crate) accept_encoding: Option<Box<http::HeaderValue>>,
+    /// `Writer::range` 와 동일 용도.
+    #[cfg(feature = "response_file")]
+    pub(crate) range: Option<Box<range::RangeRequest>>,
+    /// `Writer::preconditions` 와 동일 용도.
+    #[cfg(feature = "response_file")]
+    pub(crate) preconditions: Option<Box<conditional::Preconditions>>,
+    /// `Writer::sink_state` 와 동일 용도.
+    pub(crate) sink_state: SinkState,
 }
 
 #[cfg(feature = "arena")]
@@ -927,22 +1695,48 @@ impl ArenaWriter {
         Self {
             stream,
             _bump: None,
-            response_data_ptr: std::ptr::null(),
-            response_data_len: 0,
-            use_file: false,
+            body: ArenaResponse::Empty,
             options,
+            read_half: None,
+            guard: None,
+            #[cfg(feature = "compression")]
+            accept_encoding: None,
+            #[cfg(feature = "response_file")]
+            range: None,
+            #[cfg(feature = "response_file")]
+            preconditions: None,
+            sink_state: SinkState::Idle,
         }
     }
 
+    /// 응답 압축 협상용으로 요청의 `Accept-Encoding` 을 기억해 둔다.
+    #[cfg(feature = "compression")]
+    pub(crate) fn with_accept_encoding(mut self, headers: &http::HeaderMap) -> Self {
+        self.accept_encoding = headers
+            .get(http::header::ACCEPT_ENCODING)
+            .map(|v| Box::new(v.clone()));
+        self
+    }
+
+    /// 파일 응답의 Range / 조건부 요청 처리용으로 요청 헤더를 기억해 둔다.
+    #[cfg(feature = "response_file")]
+    pub(crate) fn with_file_request(
+        mut self,
+        method: &http::Method,
+        headers: &http::HeaderMap,
+    ) -> Self {
+        self.range = range::RangeRequest::from_request(method, headers);
+        self.preconditions = conditional::Preconditions::from_request(method, headers);
+        self
+    }
+
     pub async fn write_arena_bytes(&mut self) -> Result<(), SendableError> {
-        if !self.response_data_ptr.is_null() && self.response_data_len > 0 {
+        if let ArenaResponse::Data { ptr, len } = self.body {
             use crate::helpers::traits::http_response::SendBytes;
-            let data_ptr = self.response_data_ptr;
-            let data_len = self.response_data_len;
 
-            // SAFETY: response_data_ptr/len 은 set_arena_response 에서 _bump 내부 할당으로
+            // SAFETY: ptr/len 은 set_arena_response 에서 _bump 내부 할당으로
             // 채워지며 _bump가 살아있는 동안 유효. &mut self 호출이라 동시 변경 불가.
-            let data = unsafe { std::slice::from_raw_parts(data_ptr, data_len) };
+            let data = unsafe { std::slice::from_raw_parts(ptr, len) };
 
             self.stream.send_bytes(data).await?;
         }
@@ -950,13 +1744,20 @@ impl ArenaWriter {
     }
 
     pub fn set_arena_response(&mut self, data: &str) -> Result<bool, SendableError> {
+        self.set_arena_bytes(data.as_bytes());
+        Ok(true)
+    }
+
+    /// 압축된 응답 body 처럼 UTF-8 이 아닌 데이터를 arena 에 둔다.
+    pub fn set_arena_bytes(&mut self, data: &[u8]) {
         let bump = Box::new(Bump::new());
-        let allocated_data = bump.alloc_str(data);
+        let allocated_data = bump.alloc_slice_copy(data);
 
-        self.response_data_ptr = allocated_data.as_ptr();
-        self.response_data_len = allocated_data.len();
+        self.body = ArenaResponse::Data {
+            ptr: allocated_data.as_ptr(),
+            len: allocated_data.len(),
+        };
         self._bump = Some(bump);
-        Ok(true)
     }
 
     pub fn set_arena_json<T>(&mut self, data: &T) -> Result<bool, SendableError>
@@ -967,11 +1768,28 @@ impl ArenaWriter {
         self.set_arena_response(&json_string)
     }
 
+    /// 스트리밍 body 를 설정한다. `responser_arena` 가 chunked 로 (`Content-Length` 헤더가 있으면 그대로) 보낸다.
+    pub fn set_arena_stream(&mut self, stream: BodyStream) {
+        self.body = ArenaResponse::Stream(stream);
+        self._bump = None;
+    }
+
+    /// arena 에 둔 응답 데이터. 파일 / 스트림 응답이거나 아직 설정 전이면 빈 슬라이스.
     pub fn get_response_data(&self) -> &[u8] {
-        if self.response_data_ptr.is_null() || self.response_data_len == 0 {
-            &[]
-        } else {
-            unsafe { std::slice::from_raw_parts(self.response_data_ptr, self.response_data_len) }
+        match self.body {
+            ArenaResponse::Data { ptr, len } if len > 0 => unsafe {
+                std::slice::from_raw_parts(ptr, len)
+            },
+            _ => &[],
+        }
+    }
+
+    /// `response_file` 로 설정한 파일.
+    #[cfg(feature = "response_file")]
+    pub fn file(&self) -> Option<&FileBody> {
+        match &self.body {
+            ArenaResponse::File(file) => Some(file),
+            _ => None,
         }
     }
 
@@ -994,32 +1812,10 @@ impl ArenaWriter {
         let file_path = safe_path_join(root_path, decoded_path.as_ref())
             .ok_or("invalid path: traversal segments are not allowed")?;
 
-        let bump = Box::new(Bump::new());
-
-        // non-UTF-8 경로(Windows 등)에서 panic 회피 — lossy 변환 후 처리
-        let path_str_cow = file_path.to_string_lossy();
-
-        if let Ok(metadata) = std::fs::metadata(&file_path) {
-            let file_size = metadata.len() as usize;
-            if file_size <= self.options.zero_copy_threshold {
-                let path_with_marker = format!("__ZERO_COPY_FILE__:{}", path_str_cow);
-                let allocated_path = bump.alloc_str(&path_with_marker);
-
-                self.response_data_ptr = allocated_path.as_ptr();
-                self.response_data_len = allocated_path.len();
-                self._bump = Some(bump);
-                self.use_file = true;
-                return Ok(());
-            }
-        }
-
-        // 기존 방식
-        let allocated_path = bump.alloc_str(&path_str_cow);
-
-        self.response_data_ptr = allocated_path.as_ptr();
-        self.response_data_len = allocated_path.len();
-        self._bump = Some(bump);
-        self.use_file = true;
+        // zero_copy_threshold 이하는 제로카피 캐시, 그보다 크면 스트리밍
+        let file = FileBody::new(file_path, self.options.zero_copy_threshold as u64)?;
+        self.body = ArenaResponse::File(file);
+        self._bump = None;
         Ok(())
     }
 }
@@ -0,0 +1,508 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use http::{Request, Response};
+
+use crate::serve::{error_status, ServeResponse};
+use crate::{dev_print, Body, Handler, SendableError, Writer};
+#[cfg(feature = "arena")]
+use crate::{ArenaBody, ArenaWriter};
+
+/// `Middleware::before` 의 결과.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// 다음 미들웨어 (마지막이면 핸들러) 로 진행.
+    Continue,
+    /// 핸들러와 뒤쪽 미들웨어를 건너뛰고 지금까지 채운 `response` 로 응답 (인증 실패, 캐시 hit 등).
+    Respond,
+}
+
+/// 핸들러 앞뒤에 끼우는 공통 처리 (로깅, 인증, 헤더 등). `Chain` 에 등록해 사용한다.
+///
+/// `before` 는 등록 순서대로, `after` 는 역순으로 호출된다. `before` 가 `Flow::Respond` 로
+/// 응답을 끝낸 경우에도 그 미들웨어까지의 `after` 는 호출된다. `Err` 는 핸들러 에러와 같이
+/// `StatusError` 의 상태 코드 (그 외 500) 로 응답을 바꾼다.
+///
+/// arena 모드는 `Middleware<ArenaBody, ArenaWriter>` 를 구현한다.
+///
+/// ```rust,no_run
+/// # use atomic_http::*;
+/// # use atomic_http::external::async_trait::async_trait;
+/// # use http::{header::HeaderValue, Request, Response, StatusCode};
+/// struct RequireToken;
+///
+/// #[async_trait]
+/// impl Middleware for RequireToken {
+///     async fn before(
+///         &self,
+///         request: &mut Request<Body>,
+///         response: &mut Response<Writer>,
+///     ) -> Result<Flow, SendableError> {
+///         if request.headers().contains_key("authorization") {
+///             return Ok(Flow::Continue);
+///         }
+///         *response.status_mut() = StatusCode::UNAUTHORIZED;
+///         response.body_mut().body = r#"{"error":"unauthorized"}"#.into();
+///         Ok(Flow::Respond)
+///     }
+///
+///     async fn after(&self, response: &mut Response<Writer>) -> Result<(), SendableError> {
+///         response
+///             .headers_mut()
+///             .insert("x-served-by", HeaderValue::from_static("atomic_http"));
+///         Ok(())
+///     }
+/// }
+///
+/// # struct App;
+/// # #[async_trait]
+/// # impl Handler for App {
+/// #     async fn handle(&self, _: Request<Body>, _: &mut Response<Writer>) -> Result<(), SendableError> { Ok(()) }
+/// # }
+/// # async fn run() -> Result<(), SendableError> {
+/// let server = Server::new("127.0.0.1:8080").await?;
+/// server.serve(Chain::new(App).with_buffered(RequireToken)).await;
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait]
+pub trait Middleware<B = Body, W = Writer>: Send + Sync + 'static
+where
+    B: Send + 'static,
+    W: Send + 'static,
+{
+    async fn before(
+        &self,
+        _request: &mut Request<B>,
+        _response: &mut Response<W>,
+    ) -> Result<Flow, SendableError> {
+        Ok(Flow::Continue)
+    }
+
+    async fn after(&self, _response: &mut Response<W>) -> Result<(), SendableError> {
+        Ok(())
+    }
+}
+
+/// 미들웨어 목록 + 핸들러. 자신도 `Handler` 이므로 `Server::serve*` 에 그대로 넘기거나,
+/// 직접 만든 accept 루프에서 `chain.handle(request, &mut response)` 로 호출한다.
+/// `H` 에는 일반 핸들러 외에 `Router<H>` (`router` feature) 도 쓸 수 있다.
+///
+/// buffered (`Body`/`Writer`) 와 arena (`ArenaBody`/`ArenaWriter`) 경로의 미들웨어 목록을 따로
+/// 가지므로 `serve_auto` 처럼 두 경로를 모두 쓰는 경우 `with` 로 양쪽에 등록한다.
+pub struct Chain<H> {
+    handler: H,
+    buffered: Vec<Arc<dyn Middleware>>,
+    #[cfg(feature = "arena")]
+    arena: Vec<Arc<dyn Middleware<ArenaBody, ArenaWriter>>>,
+}
+
+impl<H> Chain<H> {
+    pub fn new(handler: H) -> Self {
+        Self {
+            handler,
+            buffered: Vec::new(),
+            #[cfg(feature = "arena")]
+            arena: Vec::new(),
+        }
+    }
+
+    /// buffered / arena 양쪽 경로에 미들웨어 추가.
+    #[cfg(feature = "arena")]
+    pub fn with<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware + Middleware<ArenaBody, ArenaWriter>,
+    {
+        let middleware = Arc::new(middleware);
+        self.buffered.push(middleware.clone());
+        self.arena.push(middleware);
+        self
+    }
+
+    /// buffered / arena 양쪽 경로에 미들웨어 추가 (arena feature 비활성화 시 buffered 만).
+    #[cfg(not(feature = "arena"))]
+    pub fn with<M: Middleware>(mut self, middleware: M) -> Self {
+        self.buffered.push(Arc::new(middleware));
+        self
+    }
+
+    /// buffered (`Body`/`Writer`) 경로에만 미들웨어 추가.
+    pub fn with_buffered<M: Middleware>(mut self, middleware: M) -> Self {
+        self.buffered.push(Arc::new(middleware));
+        self
+    }
+
+    /// arena (`ArenaBody`/`ArenaWriter`) 경로에만 미들웨어 추가.
+    #[cfg(feature = "arena")]
+    pub fn with_arena<M: Middleware<ArenaBody, ArenaWriter>>(mut self, middleware: M) -> Self {
+        self.arena.push(Arc::new(middleware));
+        self
+    }
+
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+}
+
+#[async_trait]
+impl<H: Handler> Handler for Chain<H> {
+    async fn handle(
+        &self,
+        request: Request<Body>,
+        response: &mut Response<Writer>,
+    ) -> Result<(), SendableError> {
+        run_chain(&self.buffered, &self.handler, request, response).await;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "arena")]
+#[async_trait]
+impl<H: Handler<ArenaBody, ArenaWriter>> Handler<ArenaBody, ArenaWriter> for Chain<H> {
+    async fn handle(
+        &self,
+        request: Request<ArenaBody>,
+        response: &mut Response<ArenaWriter>,
+    ) -> Result<(), SendableError> {
+        run_chain(&self.arena, &self.handler, request, response).await;
+        Ok(())
+    }
+}
+
+/// before (순서대로) → 핸들러 → after (역순). 에러는 그 자리에서 에러 응답으로 바꿔
+/// 이후 after 훅 (헤더 추가, 로깅 등) 이 최종 응답을 보게 한다.
+async fn run_chain<B, W, H>(
+    layers: &[Arc<dyn Middleware<B, W>>],
+    handler: &H,
+    mut request: Request<B>,
+    response: &mut Response<W>,
+) where
+    B: Send + 'static,
+    W: Send + 'static,
+    H: Handler<B, W>,
+    Response<W>: ServeResponse,
+{
+    let mut entered = 0;
+    let mut reached_handler = true;
+    for layer in layers {
+        entered += 1;
+        match layer.before(&mut request, response).await {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::Respond) => {
+                reached_handler = false;
+                break;
+            }
+            Err(e) => {
+                set_error(response, &e);
+                reached_handler = false;
+                // 실패한 미들웨어는 진입하지 않은 것으로 본다
+                entered -= 1;
+                break;
+            }
+        }
+    }
+
+    if reached_handler {
+        if let Err(e) = handler.handle(request, response).await {
+            set_error(response, &e);
+        }
+    }
+
+    for layer in layers[..entered].iter().rev() {
+        if let Err(e) = layer.after(response).await {
+            set_error(response, &e);
+        }
+    }
+}
+
+fn set_error<W>(response: &mut Response<W>, e: &SendableError)
+where
+    Response<W>: ServeResponse,
+{
+    dev_print!("middleware chain error: {}", e);
+    let (status, message) = error_status(e);
+    response.set_error(status, &message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Server, StatusError};
+    use http::header::HeaderValue;
+    use http::StatusCode;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    /// before/after 호출 순서를 기록.
+    struct Trace {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Middleware for Trace {
+        async fn before(
+            &self,
+            _request: &mut Request<Body>,
+            _response: &mut Response<Writer>,
+        ) -> Result<Flow, SendableError> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("before {}", self.name));
+            Ok(Flow::Continue)
+        }
+
+        async fn after(&self, response: &mut Response<Writer>) -> Result<(), SendableError> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("after {}", self.name));
+            response
+                .headers_mut()
+                .append("x-trace", HeaderValue::from_static(self.name));
+            Ok(())
+        }
+    }
+
+    struct Auth;
+
+    #[async_trait]
+    impl Middleware for Auth {
+        async fn before(
+            &self,
+            request: &mut Request<Body>,
+            response: &mut Response<Writer>,
+        ) -> Result<Flow, SendableError> {
+            match request.headers().get("authorization") {
+                Some(v) if v == "secret" => {
+                    request.extensions_mut().insert("alice");
+                    Ok(Flow::Continue)
+                }
+                Some(_) => Err(StatusError::new(StatusCode::FORBIDDEN, "forbidden").into()),
+                None => {
+                    *response.status_mut() = StatusCode::UNAUTHORIZED;
+                    Ok(Flow::Respond)
+                }
+            }
+        }
+    }
+
+    struct Whoami {
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Handler for Whoami {
+        async fn handle(
+            &self,
+            request: Request<Body>,
+            response: &mut Response<Writer>,
+        ) -> Result<(), SendableError> {
+            self.log.lock().unwrap().push("handler".into());
+            let user = request.extensions().get::<&str>().copied().unwrap_or("?");
+            response.body_mut().body = user.to_string().into();
+            *response.status_mut() = StatusCode::OK;
+            Ok(())
+        }
+    }
+
+    async fn send(addr: std::net::SocketAddr, raw: &str) -> String {
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client.write_all(raw.as_bytes()).await.unwrap();
+        let mut buf = Vec::new();
+        client.read_to_end(&mut buf).await.unwrap();
+        String::from_utf8_lossy(&buf).to_lowercase()
+    }
+
+    #[tokio::test]
+    async fn chain_runs_hooks_in_order_and_short_circuits() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let chain = Chain::new(Whoami { log: log.clone() })
+            .with_buffered(Trace {
+                name: "outer",
+                log: log.clone(),
+            })
+            .with_buffered(Auth)
+            .with_buffered(Trace {
+                name: "inner",
+                log: log.clone(),
+            });
+
+        let server = Server::new("127.0.0.1:0").await.unwrap();
+        let addr = server.listener().local_addr().unwrap();
+        let shutdown = server.shutdown_handle();
+        let serving = tokio::spawn(server.serve(chain));
+
+        let ok = send(
+            addr,
+            "GET / HTTP/1.1\r\nHost: x\r\nAuthorization: secret\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(ok.starts_with("http/1.1 200"), "{}", ok);
+        assert!(ok.ends_with("alice"), "{}", ok);
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "before outer",
+                "before inner",
+                "handler",
+                "after inner",
+                "after outer"
+            ]
+        );
+        log.lock().unwrap().clear();
+
+        // 헤더 없음 → Auth 가 401 로 끝냄. 핸들러/inner 는 건너뛰고 outer.after 는 실행
+        let unauthorized = send(
+            addr,
+            "GET / HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(unauthorized.starts_with("http/1.1 401"), "{}", unauthorized);
+        assert!(unauthorized.contains("x-trace: outer"));
+        assert!(!unauthorized.contains("x-trace: inner"));
+        assert_eq!(*log.lock().unwrap(), ["before outer", "after outer"]);
+
+        // 잘못된 토큰 → StatusError 가 응답 상태로
+        let forbidden = send(
+            addr,
+            "GET / HTTP/1.1\r\nHost: x\r\nAuthorization: nope\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(forbidden.starts_with("http/1.1 403"), "{}", forbidden);
+        assert!(forbidden.contains(r#"{"error":"forbidden"}"#));
+        assert!(forbidden.contains("x-trace: outer"));
+
+        shutdown.trigger();
+        tokio::time::timeout(Duration::from_secs(3), serving)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[cfg(feature = "arena")]
+    #[tokio::test]
+    async fn chain_applies_shared_middleware_to_arena_path() {
+        struct ServerHeader;
+
+        #[async_trait]
+        impl Middleware for ServerHeader {}
+
+        #[async_trait]
+        impl Middleware<ArenaBody, ArenaWriter> for ServerHeader {
+            async fn after(
+                &self,
+                response: &mut Response<ArenaWriter>,
+            ) -> Result<(), SendableError> {
+                response
+                    .headers_mut()
+                    .insert("server", HeaderValue::from_static("atomic"));
+                Ok(())
+            }
+        }
+
+        struct Fails;
+
+        #[async_trait]
+        impl Handler<ArenaBody, ArenaWriter> for Fails {
+            async fn handle(
+                &self,
+                _request: Request<ArenaBody>,
+                _response: &mut Response<ArenaWriter>,
+            ) -> Result<(), SendableError> {
+                Err("internal detail".into())
+            }
+        }
+
+        let server = Server::new("127.0.0.1:0").await.unwrap();
+        let addr = server.listener().local_addr().unwrap();
+        let shutdown = server.shutdown_handle();
+        let serving = tokio::spawn(server.serve_arena(Chain::new(Fails).with(ServerHeader)));
+
+        // 핸들러 에러로 바뀐 500 응답에도 after 훅이 적용된다
+        let reply = send(
+            addr,
+            "GET / HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(reply.starts_with("http/1.1 500"), "{}", reply);
+        assert!(reply.contains("server: atomic"), "{}", reply);
+        assert!(!reply.contains("internal detail"));
+
+        shutdown.trigger();
+        tokio::time::timeout(Duration::from_secs(3), serving)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[cfg(feature = "router")]
+    #[tokio::test]
+    async fn chain_wraps_router_with_params_and_404() {
+        use crate::router::{RouteParams, Router};
+
+        struct User;
+
+        #[async_trait]
+        impl Handler for User {
+            async fn handle(
+                &self,
+                request: Request<Body>,
+                response: &mut Response<Writer>,
+            ) -> Result<(), SendableError> {
+                let params = request.extensions().get::<RouteParams>().unwrap();
+                response.body_mut().body = format!("user {}", params.get("id").unwrap()).into();
+                *response.status_mut() = StatusCode::OK;
+                Ok(())
+            }
+        }
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let router = Router::<Box<dyn Handler>>::new()
+            .get("/users/{id}", Box::new(User))
+            .post("/login", Box::new(Whoami { log: log.clone() }));
+        let chain = Chain::new(router).with_buffered(Trace {
+            name: "router",
+            log: log.clone(),
+        });
+
+        let server = Server::new("127.0.0.1:0").await.unwrap();
+        let addr = server.listener().local_addr().unwrap();
+        let shutdown = server.shutdown_handle();
+        let serving = tokio::spawn(server.serve(chain));
+
+        let user = send(
+            addr,
+            "GET /users/42 HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(user.starts_with("http/1.1 200"), "{}", user);
+        assert!(user.ends_with("user 42"));
+        assert!(user.contains("x-trace: router"));
+
+        let missing = send(
+            addr,
+            "GET /nope HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(missing.starts_with("http/1.1 404"), "{}", missing);
+        assert!(missing.contains("x-trace: router"));
+
+        let wrong_method = send(
+            addr,
+            "GET /login HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(wrong_method.starts_with("http/1.1 405"), "{}", wrong_method);
+        assert!(wrong_method.contains("allow: post\r\n"), "{}", wrong_method);
+
+        shutdown.trigger();
+        tokio::time::timeout(Duration::from_secs(3), serving)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
@@ -0,0 +1,418 @@
+use std::fmt;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use tokio::io::AsyncReadExt;
+
+use crate::socket::{Peer, Stream};
+use crate::{Options, SendableError};
+
+/// v2 binary header 시그니처 (12 bytes).
+const V2_SIGNATURE: &[u8; 12] = b"\r\n\r\n\0\r\nQUIT\n";
+const V1_PREFIX: &[u8] = b"PROXY ";
+/// v1 한 줄 최대 길이 (CRLF 포함, 스펙 상한).
+const V1_MAX_LEN: usize = 107;
+const V2_HEADER_LEN: usize = 16;
+const READ_CHUNK: usize = 512;
+
+/// HAProxy PROXY protocol (v1 text / v2 binary) 처리 방식. `Options::proxy_protocol`.
+///
+/// L4 load balancer (HAProxy, AWS NLB 등) 뒤에서는 TCP peer 가 LB 주소이므로, 연결 첫머리의
+/// PROXY preamble 에서 원래 클라이언트 주소를 꺼내 `Accept::peer` / `Body::ip` 대신 쓴다.
+/// preamble 은 `Options::proxy_protocol_trusted` 대역에서 온 연결에서만 믿는다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProxyProtocol {
+    /// preamble 을 찾지 않는다 (기존 동작).
+    #[default]
+    Off,
+    /// 신뢰 대역에서 온 연결에 preamble 이 있으면 사용하고, 없으면 그대로 HTTP 로 처리.
+    Optional,
+    /// 신뢰 대역에서 온, preamble 이 있는 연결만 받는다. 나머지는 요청을 읽기 전에 닫는다.
+    Required,
+}
+
+impl FromStr for ProxyProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" | "false" => Ok(Self::Off),
+            "optional" => Ok(Self::Optional),
+            "required" | "true" => Ok(Self::Required),
+            other => Err(format!("unknown proxy protocol mode: {}", other)),
+        }
+    }
+}
+
+/// IP 주소 대역 (`10.0.0.0/8`, `2001:db8::/32`). prefix 없이 주소만 쓰면 단일 호스트.
+/// IPv4-mapped IPv6 주소 (`::ffff:10.0.0.1`) 는 IPv4 로 보고 비교한다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, String> {
+        let addr = addr.to_canonical();
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix_len > max {
+            return Err(format!(
+                "prefix length {} is too long for {}",
+                prefix_len, addr
+            ));
+        }
+        Ok(Self { addr, prefix_len })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, len)) => (
+                addr,
+                Some(
+                    len.parse::<u8>()
+                        .map_err(|_| format!("invalid prefix length in {}", s))?,
+                ),
+            ),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("invalid ip address in {}", s))?;
+        let addr = addr.to_canonical();
+        let prefix_len = prefix_len.unwrap_or(if addr.is_ipv4() { 32 } else { 128 });
+        Self::new(addr, prefix_len)
+    }
+}
+
+impl fmt::Display for IpCidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+/// 연결 첫머리의 PROXY preamble 을 소비하고 `peer` 를 원래 클라이언트 주소로 바꾼다.
+/// preamble 뒤에 딸려 읽힌 바이트 (HTTP 요청 앞부분) 를 돌려주므로 헤더 reader 의 prefix 로 넘긴다.
+///
+/// `Off` 이거나 신뢰하지 않는 peer 의 `Optional` 연결이면 아무것도 읽지 않는다.
+/// UDS 로 들어온 연결은 로컬 proxy 로 보고 신뢰한다.
+pub(crate) async fn read_preamble(
+    stream: &mut Stream,
+    options: &Options,
+    peer: &mut Peer,
+) -> Result<Vec<u8>, SendableError> {
+    if options.proxy_protocol == ProxyProtocol::Off {
+        return Ok(Vec::new());
+    }
+    let trusted = match peer.addr() {
+        Some(addr) => options
+            .proxy_protocol_trusted
+            .iter()
+            .any(|cidr| cidr.contains(addr.ip())),
+        None => true,
+    };
+    if !trusted {
+        if options.proxy_protocol == ProxyProtocol::Required {
+            return Err(format!(
+                "PROXY protocol required but {} is not a trusted proxy",
+                peer
+            )
+            .into());
+        }
+        return Ok(Vec::new());
+    }
+
+    let read_timeout = Duration::from_millis(options.read_timeout_milliseconds);
+    let deadline = Duration::from_millis(options.header_read_deadline_ms.unwrap_or_else(|| {
+        options.read_timeout_milliseconds * (options.read_max_retry as u64 + 1)
+    }));
+    let started = Instant::now();
+    let mut buffer = Vec::with_capacity(READ_CHUNK);
+
+    loop {
+        match parse(&buffer)? {
+            Preamble::Incomplete => {}
+            Preamble::Absent => {
+                if options.proxy_protocol == ProxyProtocol::Required {
+                    return Err(format!("PROXY protocol header missing from {}", peer).into());
+                }
+                return Ok(buffer);
+            }
+            Preamble::Parsed { len, source } => {
+                if let Some(source) = source {
+                    crate::dev_print!("PROXY protocol: {} -> {}", peer, source);
+                    *peer = Peer::Tcp(source);
+                }
+                return Ok(buffer.split_off(len));
+            }
+        }
+
+        if started.elapsed() > deadline {
+            return Err("PROXY protocol header read deadline exceeded".into());
+        }
+        let current_len = buffer.len();
+        buffer.resize(current_len + READ_CHUNK, 0);
+        match tokio::time::timeout(read_timeout, stream.read(&mut buffer[current_len..])).await {
+            Ok(Ok(0)) => return Err("connection closed before PROXY protocol header".into()),
+            Ok(Ok(n)) => buffer.truncate(current_len + n),
+            Ok(Err(e)) if e.kind() == io::ErrorKind::WouldBlock => buffer.truncate(current_len),
+            Ok(Err(e)) => return Err(e.into()),
+            Err(_) => buffer.truncate(current_len),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Preamble {
+    /// 판정하기에 바이트가 모자람.
+    Incomplete,
+    /// PROXY preamble 이 아님 (바로 HTTP 요청).
+    Absent,
+    /// `len` bytes 짜리 preamble. `source` 가 `None` 이면 LOCAL / UNKNOWN (peer 유지).
+    Parsed {
+        len: usize,
+        source: Option<SocketAddr>,
+    },
+}
+
+fn parse(buffer: &[u8]) -> Result<Preamble, SendableError> {
+    let is_prefix_of = |signature: &[u8]| {
+        let n = buffer.len().min(signature.len());
+        buffer[..n] == signature[..n]
+    };
+    if is_prefix_of(V2_SIGNATURE) {
+        if buffer.len() < V2_HEADER_LEN {
+            return Ok(Preamble::Incomplete);
+        }
+        return parse_v2(buffer);
+    }
+    if is_prefix_of(V1_PREFIX) {
+        if buffer.len() < V1_PREFIX.len() {
+            return Ok(Preamble::Incomplete);
+        }
+        return parse_v1(buffer);
+    }
+    Ok(Preamble::Absent)
+}
+
+/// `PROXY TCP4 <src> <dst> <sport> <dport>\r\n` / `PROXY UNKNOWN ...\r\n`
+fn parse_v1(buffer: &[u8]) -> Result<Preamble, SendableError> {
+    let window = &buffer[..buffer.len().min(V1_MAX_LEN)];
+    let end = match window.windows(2).position(|w| w == b"\r\n") {
+        Some(pos) => pos,
+        None if buffer.len() >= V1_MAX_LEN => {
+            return Err("PROXY v1 header exceeds 107 bytes".into())
+        }
+        None => return Ok(Preamble::Incomplete),
+    };
+    let line = std::str::from_utf8(&buffer[..end]).map_err(|_| "PROXY v1 header is not ASCII")?;
+    let mut parts = line.split(' ').skip(1);
+    let invalid = || -> SendableError { format!("invalid PROXY v1 header: {}", line).into() };
+
+    let source = match parts.next() {
+        Some("UNKNOWN") => None,
+        Some(family @ ("TCP4" | "TCP6")) => {
+            let src: IpAddr = parts
+                .next()
+                .ok_or_else(invalid)?
+                .parse()
+                .map_err(|_| invalid())?;
+            let _dst: IpAddr = parts
+                .next()
+                .ok_or_else(invalid)?
+                .parse()
+                .map_err(|_| invalid())?;
+            let sport: u16 = parts
+                .next()
+                .ok_or_else(invalid)?
+                .parse()
+                .map_err(|_| invalid())?;
+            let _dport: u16 = parts
+                .next()
+                .ok_or_else(invalid)?
+                .parse()
+                .map_err(|_| invalid())?;
+            if (family == "TCP4") != src.is_ipv4() || parts.next().is_some() {
+                return Err(invalid());
+            }
+            Some(SocketAddr::new(src, sport))
+        }
+        _ => return Err(invalid()),
+    };
+    Ok(Preamble::Parsed {
+        len: end + 2,
+        source,
+    })
+}
+
+/// 12 bytes 시그니처 + ver/cmd + family + 길이 (u16 BE) + 주소 블록 (+ TLV, 무시).
+fn parse_v2(buffer: &[u8]) -> Result<Preamble, SendableError> {
+    let version_command = buffer[12];
+    if version_command >> 4 != 2 {
+        return Err(format!(
+            "unsupported PROXY protocol version {}",
+            version_command >> 4
+        )
+        .into());
+    }
+    let family = buffer[13];
+    let addr_len = u16::from_be_bytes([buffer[14], buffer[15]]) as usize;
+    let len = V2_HEADER_LEN + addr_len;
+    if buffer.len() < len {
+        return Ok(Preamble::Incomplete);
+    }
+    let addrs = &buffer[V2_HEADER_LEN..len];
+
+    let source = match version_command & 0x0F {
+        // LOCAL — LB 자체의 health check 등. peer 유지
+        0x0 => None,
+        0x1 => match family >> 4 {
+            0x1 if addrs.len() >= 12 => {
+                let ip = Ipv4Addr::new(addrs[0], addrs[1], addrs[2], addrs[3]);
+                let port = u16::from_be_bytes([addrs[8], addrs[9]]);
+                Some(SocketAddr::new(ip.into(), port))
+            }
+            0x2 if addrs.len() >= 36 => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(&addrs[..16]);
+                let port = u16::from_be_bytes([addrs[32], addrs[33]]);
+                Some(SocketAddr::new(Ipv6Addr::from(octets).into(), port))
+            }
+            // AF_UNSPEC / AF_UNIX — 쓸 수 있는 IP 주소 없음
+            0x0 | 0x3 => None,
+            _ => return Err("truncated PROXY v2 address block".into()),
+        },
+        command => return Err(format!("unsupported PROXY v2 command {}", command).into()),
+    };
+    Ok(Preamble::Parsed { len, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v2(command: u8, family: u8, addrs: &[u8]) -> Vec<u8> {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.push(0x20 | command);
+        header.push(family);
+        header.extend_from_slice(&(addrs.len() as u16).to_be_bytes());
+        header.extend_from_slice(addrs);
+        header
+    }
+
+    #[test]
+    fn parses_v1_and_keeps_following_bytes() {
+        let raw = b"PROXY TCP4 203.0.113.7 10.0.0.1 51234 80\r\nGET / HTTP/1.1\r\n";
+        assert_eq!(
+            parse(raw).unwrap(),
+            Preamble::Parsed {
+                len: 42,
+                source: Some("203.0.113.7:51234".parse().unwrap()),
+            }
+        );
+        assert_eq!(&raw[42..45], b"GET");
+
+        let raw = b"PROXY TCP6 2001:db8::1 2001:db8::2 443 8443\r\n";
+        let Preamble::Parsed { source, .. } = parse(raw).unwrap() else {
+            panic!("expected v1 header");
+        };
+        assert_eq!(source, Some("[2001:db8::1]:443".parse().unwrap()));
+
+        assert!(matches!(
+            parse(b"PROXY UNKNOWN\r\n").unwrap(),
+            Preamble::Parsed {
+                len: 15,
+                source: None
+            }
+        ));
+        assert_eq!(parse(b"PROXY TCP4 1.2.3.4").unwrap(), Preamble::Incomplete);
+        assert_eq!(parse(b"PRO").unwrap(), Preamble::Incomplete);
+        assert!(parse(b"PROXY TCP4 1.2.3.4 5.6.7.8 99999 80\r\n").is_err());
+        assert!(parse(b"PROXY TCP4 ::1 ::1 1 2\r\n").is_err());
+    }
+
+    #[test]
+    fn parses_v2_inet_inet6_and_local() {
+        let mut addrs = vec![198, 51, 100, 9, 10, 0, 0, 1];
+        addrs.extend_from_slice(&40000u16.to_be_bytes());
+        addrs.extend_from_slice(&443u16.to_be_bytes());
+        addrs.extend_from_slice(&[0x04, 0x00, 0x01, 0xAA]); // TLV 는 무시
+        let mut raw = v2(0x1, 0x11, &addrs);
+        let header_len = raw.len();
+        raw.extend_from_slice(b"GET /");
+        assert_eq!(
+            parse(&raw).unwrap(),
+            Preamble::Parsed {
+                len: header_len,
+                source: Some("198.51.100.9:40000".parse().unwrap()),
+            }
+        );
+        assert_eq!(parse(&raw[..header_len - 1]).unwrap(), Preamble::Incomplete);
+
+        let src: Ipv6Addr = "2001:db8::7".parse().unwrap();
+        let mut addrs = src.octets().to_vec();
+        addrs.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        addrs.extend_from_slice(&1234u16.to_be_bytes());
+        addrs.extend_from_slice(&80u16.to_be_bytes());
+        let Preamble::Parsed { source, .. } = parse(&v2(0x1, 0x21, &addrs)).unwrap() else {
+            panic!("expected v2 header");
+        };
+        assert_eq!(source, Some(SocketAddr::new(src.into(), 1234)));
+
+        assert!(matches!(
+            parse(&v2(0x0, 0x00, &[])).unwrap(),
+            Preamble::Parsed {
+                len: 16,
+                source: None
+            }
+        ));
+        assert!(parse(&v2(0x1, 0x11, &[1, 2, 3])).is_err());
+    }
+
+    #[test]
+    fn plain_http_is_not_a_preamble() {
+        assert_eq!(parse(b"GET / HTTP/1.1\r\n").unwrap(), Preamble::Absent);
+        assert_eq!(parse(b"\r\n\r\nX").unwrap(), Preamble::Absent);
+    }
+
+    #[test]
+    fn cidr_matching() {
+        let net: IpCidr = "10.0.0.0/8".parse().unwrap();
+        assert!(net.contains("10.20.30.40".parse().unwrap()));
+        assert!(net.contains("::ffff:10.1.1.1".parse().unwrap()));
+        assert!(!net.contains("11.0.0.1".parse().unwrap()));
+
+        let host: IpCidr = "192.168.1.5".parse().unwrap();
+        assert_eq!(host.to_string(), "192.168.1.5/32");
+        assert!(!host.contains("192.168.1.6".parse().unwrap()));
+
+        let any: IpCidr = "::/0".parse().unwrap();
+        assert!(any.contains("2001:db8::1".parse().unwrap()));
+        assert!("10.0.0.0/33".parse::<IpCidr>().is_err());
+    }
+}
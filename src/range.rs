@@ -0,0 +1,397 @@
+//! 파일 응답의 `Range` / `If-Range` 처리 (`response_file` feature). 요청 헤더는 파싱 시점에
+//! `Writer` / `ArenaWriter` 에 옮겨 두고, `responser` 가 파일 길이를 안 뒤 평가한다.
+
+use std::fmt::Write as _;
+use std::hash::{BuildHasher, Hasher};
+use std::time::SystemTime;
+
+use http::header::{HeaderMap, HeaderValue, IF_RANGE, RANGE};
+use http::Method;
+
+use crate::conditional::if_range_matches;
+use crate::helpers::traits::zero_copy::FileValidators;
+
+/// 이보다 많은 range 를 요청하면 무시하고 전체를 보낸다 — 작은 조각 수천 개로 응답을 부풀리는 요청 방어.
+const MAX_RANGES: usize = 32;
+
+/// 요청의 `Range` 와 `If-Range`. `GET` 요청에만 만든다.
+#[derive(Debug, Clone)]
+pub(crate) struct RangeRequest {
+    range: HeaderValue,
+    if_range: Option<HeaderValue>,
+}
+
+/// `[start, end)` 바이트 구간.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    pub(crate) fn len(&self) -> u64 {
+        self.end - self.start
+    }
+
+    /// `Content-Range` 값 (`bytes 0-99/1000`).
+    pub(crate) fn content_range(&self, complete: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end - 1, complete)
+    }
+}
+
+/// 평가 결과. 전체 응답 (`200`) 이면 `RangeRequest::evaluate` 가 `None` 을 돌려준다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Partial {
+    /// `206`, 단일 구간.
+    Single(ByteRange),
+    /// `206`, `multipart/byteranges`.
+    Multiple(Vec<ByteRange>),
+    /// `416` — 만족하는 구간이 없음.
+    Unsatisfiable,
+}
+
+impl RangeRequest {
+    pub(crate) fn from_request(method: &Method, headers: &HeaderMap) -> Option<Box<Self>> {
+        if method != Method::GET {
+            return None;
+        }
+        Some(Box::new(Self {
+            range: headers.get(RANGE)?.clone(),
+            if_range: headers.get(IF_RANGE).cloned(),
+        }))
+    }
+
+    /// 길이 `len` 인 파일에 적용한다. `If-Range` 가 맞지 않거나 `Range` 가 문법에 어긋나면 `None`
+    /// (헤더를 무시하고 전체 응답). validator 를 모르면 `If-Range` 는 맞지 않은 것으로 본다.
+    pub(crate) fn evaluate(
+        &self,
+        len: u64,
+        validators: Option<&FileValidators>,
+    ) -> Option<Partial> {
+        if let Some(if_range) = &self.if_range {
+            if !validators.is_some_and(|v| if_range_matches(if_range, v)) {
+                return None;
+            }
+        }
+        parse_ranges(self.range.to_str().ok()?, len)
+    }
+}
+
+/// `bytes=0-99, 200-, -50` 를 해석한다. 단위가 `bytes` 가 아니거나 문법 오류, 구간이 너무 많거나
+/// 겹쳐서 파일보다 커지면 `None`.
+fn parse_ranges(value: &str, len: u64) -> Option<Partial> {
+    let (unit, specs) = value.split_once('=')?;
+    if !unit.trim().eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+
+    let mut ranges = Vec::new();
+    let mut requested = 0;
+    for spec in specs.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        requested += 1;
+        if requested > MAX_RANGES {
+            return None;
+        }
+        let (first, last) = spec.split_once('-')?;
+        let range = if first.is_empty() {
+            let suffix = parse_digits(last)?;
+            (suffix > 0 && len > 0).then(|| ByteRange {
+                start: len - suffix.min(len),
+                end: len,
+            })
+        } else {
+            let start = parse_digits(first)?;
+            let end = if last.is_empty() {
+                len
+            } else {
+                let last = parse_digits(last)?;
+                if last < start {
+                    return None;
+                }
+                last.saturating_add(1).min(len)
+            };
+            (start < len).then_some(ByteRange { start, end })
+        };
+        ranges.extend(range);
+    }
+    if requested == 0 {
+        return None;
+    }
+
+    match ranges.len() {
+        0 => Some(Partial::Unsatisfiable),
+        1 => Some(Partial::Single(ranges[0])),
+        _ if ranges.iter().map(ByteRange::len).sum::<u64>() > len => None,
+        _ => Some(Partial::Multiple(ranges)),
+    }
+}
+
+fn parse_digits(value: &str) -> Option<u64> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// `multipart/byteranges` body 의 틀. 각 조각 앞 헤더와 닫는 boundary 만 들고, 데이터는 파일에서 바로 잘라 보낸다.
+pub(crate) struct Multipart {
+    pub content_type: String,
+    pub parts: Vec<(String, ByteRange)>,
+    pub closing: String,
+}
+
+impl Multipart {
+    pub(crate) fn new(ranges: &[ByteRange], content_type: &str, complete: u64) -> Self {
+        let boundary = boundary();
+        let parts = ranges
+            .iter()
+            .enumerate()
+            .map(|(i, range)| {
+                let mut head = String::new();
+                if i > 0 {
+                    head.push_str("\r\n");
+                }
+                let _ = write!(
+                    head,
+                    "--{}\r\nContent-Type: {}\r\nContent-Range: {}\r\n\r\n",
+                    boundary,
+                    content_type,
+                    range.content_range(complete)
+                );
+                (head, *range)
+            })
+            .collect();
+        Self {
+            content_type: format!("multipart/byteranges; boundary={}", boundary),
+            parts,
+            closing: format!("\r\n--{}--\r\n", boundary),
+        }
+    }
+}
+
+/// 응답마다 다른 boundary. `RandomState` 의 시드를 난수원으로 쓴다.
+fn boundary() -> String {
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u64(
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64),
+    );
+    format!("atomic_http_{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn request(range: &str, if_range: Option<&str>) -> RangeRequest {
+        let mut headers = HeaderMap::new();
+        headers.insert(RANGE, range.parse().unwrap());
+        if let Some(if_range) = if_range {
+            headers.insert(IF_RANGE, if_range.parse().unwrap());
+        }
+        *RangeRequest::from_request(&Method::GET, &headers).unwrap()
+    }
+
+    fn range(start: u64, end: u64) -> ByteRange {
+        ByteRange { start, end }
+    }
+
+    #[test]
+    fn parses_single_suffix_and_open_ranges() {
+        let eval = |value: &str| request(value, None).evaluate(1000, None);
+        assert_eq!(eval("bytes=0-99"), Some(Partial::Single(range(0, 100))));
+        assert_eq!(eval("bytes=900-"), Some(Partial::Single(range(900, 1000))));
+        assert_eq!(eval("bytes=-100"), Some(Partial::Single(range(900, 1000))));
+        assert_eq!(eval("bytes=-5000"), Some(Partial::Single(range(0, 1000))));
+        assert_eq!(
+            eval("Bytes=990-2000"),
+            Some(Partial::Single(range(990, 1000)))
+        );
+        assert_eq!(
+            eval("bytes=0-0, 5000-, -10"),
+            Some(Partial::Multiple(vec![range(0, 1), range(990, 1000)]))
+        );
+    }
+
+    #[test]
+    fn invalid_or_abusive_ranges_are_ignored_and_unsatisfiable_give_416() {
+        let eval = |value: &str| request(value, None).evaluate(1000, None);
+        assert_eq!(eval("items=0-5"), None);
+        assert_eq!(eval("bytes=5-1"), None);
+        assert_eq!(eval("bytes=a-b"), None);
+        assert_eq!(eval("bytes=+1-2"), None);
+        assert_eq!(eval("bytes="), None);
+        assert_eq!(eval("bytes=0-999,0-999"), None);
+        let many = format!("bytes={}", vec!["0-0"; MAX_RANGES + 1].join(","));
+        assert_eq!(eval(&many), None);
+
+        assert_eq!(eval("bytes=1000-"), Some(Partial::Unsatisfiable));
+        assert_eq!(eval("bytes=-0"), Some(Partial::Unsatisfiable));
+        assert_eq!(
+            request("bytes=0-", None).evaluate(0, None),
+            Some(Partial::Unsatisfiable)
+        );
+    }
+
+    #[test]
+    fn if_range_gates_the_range() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let validators = FileValidators::new(100, modified);
+        let older = httpdate::fmt_http_date(modified - Duration::from_secs(1));
+
+        let partial = Some(Partial::Single(range(0, 10)));
+        assert_eq!(
+            request("bytes=0-9", Some(&validators.etag)).evaluate(100, Some(&validators)),
+            partial
+        );
+        assert_eq!(
+            request("bytes=0-9", Some(&older)).evaluate(100, Some(&validators)),
+            None
+        );
+        assert_eq!(
+            request("bytes=0-9", Some("\"etag\"")).evaluate(100, Some(&validators)),
+            None
+        );
+        assert_eq!(
+            request("bytes=0-9", Some(&validators.etag)).evaluate(100, None),
+            None
+        );
+    }
+
+    #[test]
+    fn only_get_requests_carry_ranges() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RANGE, "bytes=0-1".parse().unwrap());
+        assert!(RangeRequest::from_request(&Method::HEAD, &headers).is_none());
+        assert!(RangeRequest::from_request(&Method::POST, &headers).is_none());
+        assert!(RangeRequest::from_request(&Method::GET, &HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn multipart_parts_frame_each_range() {
+        let ranges = [range(0, 10), range(50, 60)];
+        let multipart = Multipart::new(&ranges, "text/plain", 100);
+        let boundary = multipart
+            .content_type
+            .strip_prefix("multipart/byteranges; boundary=")
+            .unwrap();
+        assert_eq!(
+            multipart.parts[0].0,
+            format!(
+                "--{}\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-9/100\r\n\r\n",
+                boundary
+            )
+        );
+        assert!(multipart.parts[1]
+            .0
+            .starts_with(&format!("\r\n--{}\r\n", boundary)));
+        assert_eq!(multipart.parts[1].1, range(50, 60));
+        assert_eq!(multipart.closing, format!("\r\n--{}--\r\n", boundary));
+        assert_ne!(
+            Multipart::new(&ranges, "text/plain", 100).content_type,
+            multipart.content_type
+        );
+    }
+
+    #[cfg(feature = "arena")]
+    #[tokio::test]
+    async fn file_responses_honour_range_requests() {
+        use crate::test_support::{file_connection, read_raw, TempDir};
+        use crate::{ResponseUtil, ResponseUtilArena};
+        use tokio::io::AsyncWriteExt;
+
+        let dir = TempDir::new("ranges");
+        let content: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        dir.write("video.bin", &content);
+        let modified = std::fs::metadata(dir.path().join("video.bin"))
+            .unwrap()
+            .modified()
+            .unwrap();
+
+        let (mut client, mut conn) = file_connection(&dir, |_| {}).await;
+
+        let handle = tokio::spawn(async move {
+            for _ in 0..4 {
+                let (request, mut response) = conn.next_request().await.unwrap().unwrap();
+                response.body_mut().response_file("video.bin").unwrap();
+                response.responser().await.unwrap();
+                assert!(conn.recycle(request, response).await.unwrap());
+            }
+            let (_request, mut response) = conn.next_request_arena().await.unwrap().unwrap();
+            response.body_mut().response_file("video.bin").unwrap();
+            response.responser_arena().await.unwrap();
+        });
+
+        let get = |extra: &str| format!("GET /video.bin HTTP/1.1\r\nHost: x\r\n{}\r\n", extra);
+
+        client
+            .write_all(get("Range: bytes=10-19\r\n").as_bytes())
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 206"), "got: {}", head);
+        assert!(head.contains("content-range: bytes 10-19/1000"));
+        assert!(head.contains("accept-ranges: bytes"));
+        assert_eq!(body, &content[10..20]);
+
+        client
+            .write_all(get("Range: bytes=0-1, -2\r\n").as_bytes())
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 206"), "got: {}", head);
+        let boundary = head
+            .lines()
+            .find_map(|l| l.strip_prefix("content-type: multipart/byteranges; boundary="))
+            .unwrap()
+            .to_string();
+        let mut expected = format!(
+            "--{b}\r\nContent-Type: application/octet-stream\r\nContent-Range: bytes 0-1/1000\r\n\r\n",
+            b = boundary
+        )
+        .into_bytes();
+        expected.extend_from_slice(&content[0..2]);
+        expected.extend_from_slice(
+            format!(
+                "\r\n--{b}\r\nContent-Type: application/octet-stream\r\nContent-Range: bytes 998-999/1000\r\n\r\n",
+                b = boundary
+            )
+            .as_bytes(),
+        );
+        expected.extend_from_slice(&content[998..]);
+        expected.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+        assert_eq!(body, expected);
+
+        client
+            .write_all(get("Range: bytes=5000-\r\n").as_bytes())
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 416"), "got: {}", head);
+        assert!(head.contains("content-range: bytes */1000"));
+        assert!(body.is_empty());
+
+        // If-Range 가 파일과 맞지 않으면 전체를 보낸다
+        let stale = httpdate::fmt_http_date(modified - std::time::Duration::from_secs(60));
+        client
+            .write_all(get(&format!("Range: bytes=0-9\r\nIf-Range: {}\r\n", stale)).as_bytes())
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 200"), "got: {}", head);
+        assert_eq!(body, content);
+
+        let fresh = httpdate::fmt_http_date(modified);
+        client
+            .write_all(get(&format!("Range: bytes=-3\r\nIf-Range: {}\r\n", fresh)).as_bytes())
+            .await
+            .unwrap();
+        let (head, body) = read_raw(&mut client).await;
+        assert!(head.starts_with("http/1.1 206"), "got: {}", head);
+        assert_eq!(body, &content[997..]);
+
+        handle.await.unwrap();
+    }
+}
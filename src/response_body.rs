@@ -0,0 +1,659 @@
+//! 응답 body 종류. `Writer::body` 가 무엇을 보낼지 (텍스트 / 바이트 / 파일 / 스트림) 타입으로
+//! 구분하고, `responser` 는 variant 별로 보낸다. 헤더를 먼저 보내고 body 를 직접 써 나가려면
+//! `body_writer` 가 돌려주는 `BodyWriter` 를 쓴다.
+
+use std::fmt;
+use std::io;
+#[cfg(feature = "response_file")]
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+#[cfg(feature = "response_file")]
+use std::time::SystemTime;
+
+use bytes::{Bytes, BytesMut};
+use futures_util::{Stream, StreamExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+
+use crate::socket::OwnedWriteHalf;
+
+/// `BodyStream::from_reader` 가 한 번에 읽는 크기.
+const READ_BUF_SIZE: usize = 64 * 1024;
+
+/// `Writer` 가 보낼 응답 body.
+///
+/// 텍스트 / 바이트는 `From` 으로 바로 넣을 수 있다 (`response.body_mut().body = "ok".into()`).
+/// 파일은 `Writer::response_file` 로 설정한다.
+#[derive(Debug, Default)]
+pub enum ResponseBody {
+    /// body 없음 (`content-length: 0`).
+    #[default]
+    Empty,
+    Text(String),
+    Bytes(Vec<u8>),
+    /// 파일. 조건부 요청 / Range / 압축 / 제로카피 전송을 거친다.
+    #[cfg(feature = "response_file")]
+    File(FileBody),
+    /// 스트리밍 소스. `Content-Length` 헤더가 없으면 `Transfer-Encoding: chunked` 로 보낸다.
+    Stream(BodyStream),
+}
+
+impl ResponseBody {
+    /// 스트림이면 `None`.
+    pub fn len(&self) -> Option<u64> {
+        match self {
+            ResponseBody::Empty => Some(0),
+            ResponseBody::Text(text) => Some(text.len() as u64),
+            ResponseBody::Bytes(bytes) => Some(bytes.len() as u64),
+            #[cfg(feature = "response_file")]
+            ResponseBody::File(file) => Some(file.len()),
+            ResponseBody::Stream(_) => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// 메모리에 있는 body (`Empty` / `Text` / `Bytes`) 의 바이트.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            ResponseBody::Empty => Some(&[]),
+            ResponseBody::Text(text) => Some(text.as_bytes()),
+            ResponseBody::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for ResponseBody {
+    fn from(text: String) -> Self {
+        ResponseBody::Text(text)
+    }
+}
+
+impl From<&str> for ResponseBody {
+    fn from(text: &str) -> Self {
+        ResponseBody::Text(text.to_string())
+    }
+}
+
+impl From<Vec<u8>> for ResponseBody {
+    fn from(bytes: Vec<u8>) -> Self {
+        ResponseBody::Bytes(bytes)
+    }
+}
+
+impl From<&[u8]> for ResponseBody {
+    fn from(bytes: &[u8]) -> Self {
+        ResponseBody::Bytes(bytes.to_vec())
+    }
+}
+
+impl From<Bytes> for ResponseBody {
+    fn from(bytes: Bytes) -> Self {
+        ResponseBody::Bytes(bytes.into())
+    }
+}
+
+#[cfg(feature = "response_file")]
+impl From<FileBody> for ResponseBody {
+    fn from(file: FileBody) -> Self {
+        ResponseBody::File(file)
+    }
+}
+
+impl From<BodyStream> for ResponseBody {
+    fn from(stream: BodyStream) -> Self {
+        ResponseBody::Stream(stream)
+    }
+}
+
+/// 보낼 파일과 `response_file` 시점의 메타데이터.
+#[cfg(feature = "response_file")]
+#[derive(Debug, Clone)]
+pub struct FileBody {
+    path: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+    zero_copy: bool,
+}
+
+#[cfg(feature = "response_file")]
+impl FileBody {
+    /// 메타데이터를 읽는다. `zero_copy_threshold` 이하면 제로카피 캐시 (메모리 / mmap) 로,
+    /// 그보다 크면 스트리밍으로 보낸다.
+    pub(crate) fn new(path: PathBuf, zero_copy_threshold: u64) -> io::Result<Self> {
+        let metadata = std::fs::metadata(&path)?;
+        Ok(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+            zero_copy: metadata.len() <= zero_copy_threshold,
+            path,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    /// 제로카피 캐시로 보내는지 (`false` 면 128KB 버퍼 / `sendfile` 스트리밍).
+    pub fn is_zero_copy(&self) -> bool {
+        self.zero_copy
+    }
+
+    /// 확장자로 추측한 `Content-Type`.
+    pub fn content_type(&self) -> String {
+        crate::helpers::traits::http_response::get_content_type(&self.path.to_string_lossy())
+    }
+}
+
+/// 스트리밍 응답 body. 각 항목이 그대로 chunk 하나가 된다.
+pub struct BodyStream {
+    inner: Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send>>,
+}
+
+impl BodyStream {
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = io::Result<Bytes>> + Send + 'static,
+    {
+        Self {
+            inner: Box::pin(stream),
+        }
+    }
+
+    /// 실패하지 않는 스트림 (`Stream<Item = Bytes>`).
+    pub fn from_chunks<S>(stream: S) -> Self
+    where
+        S: Stream<Item = Bytes> + Send + 'static,
+    {
+        Self::new(stream.map(Ok))
+    }
+
+    /// `AsyncRead` 를 EOF 까지 읽어 보낸다. 한 번 읽은 만큼 (최대 64KB) 이 chunk 하나가 된다.
+    pub fn from_reader<R>(reader: R) -> Self
+    where
+        R: AsyncRead + Send + 'static,
+    {
+        Self::new(futures_util::stream::try_unfold(
+            Box::pin(reader),
+            |mut reader| async move {
+                let mut buf = BytesMut::with_capacity(READ_BUF_SIZE);
+                let len = reader.read_buf(&mut buf).await?;
+                Ok((len > 0).then(|| (buf.freeze(), reader)))
+            },
+        ))
+    }
+
+    pub(crate) async fn next(&mut self) -> Option<io::Result<Bytes>> {
+        self.inner.next().await
+    }
+}
+
+impl fmt::Debug for BodyStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodyStream").finish_non_exhaustive()
+    }
+}
+
+// SAFETY: `inner` 는 `&mut self` 로만 접근하므로 `&BodyStream` 을 여러 스레드가 공유해도
+// 스트림에 닿을 수 없다. `Writer` 가 `Sync` 를 유지하도록 (`&Writer` 를 await 너머로 들고 있는
+// 핸들러 future 가 `Send` 이도록) 스트림 자체에는 `Sync` 를 요구하지 않는다.
+unsafe impl Sync for BodyStream {}
+
+/// 스트리밍 body 의 경계를 클라이언트에 알리는 방법.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BodyFraming {
+    /// `Transfer-Encoding: chunked`.
+    Chunked,
+    /// 핸들러가 지정한 `Content-Length`. 정확히 이만큼 써야 한다.
+    Length(u64),
+    /// chunked 를 모르는 HTTP/1.0 — 연결을 닫아 끝을 알린다 (`Connection: close`).
+    Close,
+}
+
+/// `body_writer` 로 직접 보내는 응답의 진행 상태. `Idle` 이 아니면 `responser` 는 아무것도 보내지
+/// 않고, `Open` 이면 응답이 잘렸으므로 keep-alive 드라이버가 연결을 재사용하지 않는다.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum SinkState {
+    #[default]
+    Idle,
+    Open,
+    Finished,
+}
+
+/// 헤더를 보낸 뒤 body 를 써 나가는 `AsyncWrite`. `body_writer` / `body_writer_arena` 가 만든다.
+///
+/// chunked 면 `write` 한 번이 chunk 하나가 된다. 다 쓰면 `finish` (또는 `shutdown`) 로 끝을
+/// 알려야 하고, 소켓은 닫지 않는다. 끝내지 않고 drop 하면 응답이 잘린 것이므로 연결을 재사용하지 않는다.
+pub struct BodyWriter<'a> {
+    stream: &'a mut OwnedWriteHalf,
+    /// writer 의 `sink_state`. `finish` 가 성공하면 `Finished`.
+    state: &'a mut SinkState,
+    framing: BodyFraming,
+    /// 소켓에 아직 다 쓰지 못한 chunk frame 과 그중 보낸 바이트 수.
+    frame: Vec<u8>,
+    sent: usize,
+    /// `BodyFraming::Length` 에서 지금까지 쓴 body 바이트.
+    written: u64,
+    finished: bool,
+}
+
+impl<'a> BodyWriter<'a> {
+    pub(crate) fn new(
+        stream: &'a mut OwnedWriteHalf,
+        state: &'a mut SinkState,
+        framing: BodyFraming,
+    ) -> Self {
+        *state = SinkState::Open;
+        Self {
+            stream,
+            state,
+            framing,
+            frame: Vec::new(),
+            sent: 0,
+            written: 0,
+            finished: false,
+        }
+    }
+
+    /// 마지막 chunk 를 보내고 flush 한다. `Content-Length` 보다 덜 썼으면 에러.
+    pub async fn finish(mut self) -> io::Result<()> {
+        futures_util::future::poll_fn(|cx| self.poll_finish(cx)).await
+    }
+
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.sent < self.frame.len() {
+            let len = ready!(Pin::new(&mut *self.stream).poll_write(cx, &self.frame[self.sent..]))?;
+            if len == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.sent += len;
+        }
+        self.frame.clear();
+        self.sent = 0;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_finish(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if !self.finished {
+            match self.framing {
+                BodyFraming::Chunked => self.frame.extend_from_slice(b"0\r\n\r\n"),
+                BodyFraming::Length(len) if self.written < len => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "body is shorter than Content-Length",
+                    )))
+                }
+                _ => {}
+            }
+            self.finished = true;
+        }
+        ready!(self.poll_drain(cx))?;
+        ready!(Pin::new(&mut *self.stream).poll_flush(cx))?;
+        *self.state = SinkState::Finished;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for BodyWriter<'_> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.finished {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "body is already finished",
+            )));
+        }
+        ready!(this.poll_drain(cx))?;
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        match this.framing {
+            BodyFraming::Chunked => {
+                // frame 을 통째로 받아 두고 바로 보내 본다. 다 못 보낸 나머지는 다음 write / flush 가 보낸다
+                this.frame
+                    .extend_from_slice(format!("{:x}\r\n", buf.len()).as_bytes());
+                this.frame.extend_from_slice(buf);
+                this.frame.extend_from_slice(b"\r\n");
+                if let Poll::Ready(Err(e)) = this.poll_drain(cx) {
+                    return Poll::Ready(Err(e));
+                }
+                Poll::Ready(Ok(buf.len()))
+            }
+            BodyFraming::Length(len) => {
+                let remaining = len - this.written;
+                if remaining == 0 {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "body is longer than Content-Length",
+                    )));
+                }
+                let buf = &buf[..buf.len().min(remaining.try_into().unwrap_or(usize::MAX))];
+                let len = ready!(Pin::new(&mut *this.stream).poll_write(cx, buf))?;
+                this.written += len as u64;
+                Poll::Ready(Ok(len))
+            }
+            BodyFraming::Close => Pin::new(&mut *this.stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut *this.stream).poll_flush(cx)
+    }
+
+    /// `finish` 와 같다. 소켓은 닫지 않는다.
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_finish(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lengths_follow_the_variant() {
+        assert_eq!(ResponseBody::default().len(), Some(0));
+        assert!(ResponseBody::default().is_empty());
+        assert_eq!(ResponseBody::from("héllo").len(), Some(6));
+        assert_eq!(
+            ResponseBody::from(vec![1, 2, 3]).as_bytes(),
+            Some(&[1, 2, 3][..])
+        );
+        let stream = BodyStream::new(futures_util::stream::empty());
+        let body = ResponseBody::from(stream);
+        assert_eq!(body.len(), None);
+        assert!(!body.is_empty());
+        assert!(body.as_bytes().is_none());
+    }
+
+    #[tokio::test]
+    async fn readers_and_chunk_streams_become_body_streams() {
+        let data: Vec<u8> = (0..150 * 1024).map(|i| (i % 251) as u8).collect();
+        let mut stream = BodyStream::from_reader(io::Cursor::new(data.clone()));
+        let mut received = Vec::new();
+        let mut chunks = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.unwrap();
+            assert!(chunk.len() <= READ_BUF_SIZE);
+            received.extend_from_slice(&chunk);
+            chunks += 1;
+        }
+        assert_eq!(chunks, 3);
+        assert!(received == data);
+
+        let mut stream = BodyStream::from_chunks(futures_util::stream::iter([Bytes::from("a")]));
+        assert_eq!(stream.next().await.unwrap().unwrap(), "a");
+        assert!(stream.next().await.is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn body_writer_enforces_content_length() {
+        use crate::socket::Stream;
+        use tokio::io::AsyncWriteExt;
+
+        let (a, _b) = tokio::net::UnixStream::pair().unwrap();
+        let (_, mut write) = Stream::Unix(a).into_split();
+        let mut state = SinkState::Idle;
+        let mut writer = BodyWriter::new(&mut write, &mut state, BodyFraming::Length(5));
+        writer.write_all(b"abc").await.unwrap();
+        let err = writer.finish().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(state, SinkState::Open);
+
+        let mut writer = BodyWriter::new(&mut write, &mut state, BodyFraming::Length(2));
+        let err = writer.write_all(b"abc").await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        writer.finish().await.unwrap();
+        assert_eq!(state, SinkState::Finished);
+    }
+
+    #[cfg(feature = "response_file")]
+    #[test]
+    fn file_body_records_metadata() {
+        let path = std::env::temp_dir().join(format!("atomic_body_{}.json", std::process::id()));
+        std::fs::write(&path, b"{}").unwrap();
+        let file = FileBody::new(path.clone(), 1).unwrap();
+        assert_eq!(file.len(), 2);
+        assert!(!file.is_zero_copy());
+        assert!(file.modified().is_some());
+        assert_eq!(file.content_type(), "application/json");
+        assert!(FileBody::new(path.clone(), 2).unwrap().is_zero_copy());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            FileBody::new(path, 2).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn response_bodies_are_sent_by_variant() {
+        use crate::connection::Connection;
+        use crate::test_support::{read_response, socket_pair, test_options};
+        use crate::ResponseUtil;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let mut conn = Connection::new(server, test_options(), peer);
+
+        let handle = tokio::spawn(async move {
+            let stream = || {
+                let chunks = ["ab", "", "cde"].map(|s| Ok::<_, std::io::Error>(Bytes::from(s)));
+                BodyStream::new(futures_util::stream::iter(chunks))
+            };
+            for i in 0..4 {
+                let Some((request, mut response)) = conn.next_request().await.unwrap() else {
+                    break;
+                };
+                response.body_mut().body = match i {
+                    // 예전 파일 표식처럼 보이는 텍스트도 그대로 텍스트
+                    0 => "__ZERO_COPY_FILE__:/etc/hostname".into(),
+                    1 => ResponseBody::Bytes(vec![0, 1, 2, 3]),
+                    _ => stream().into(),
+                };
+                response.responser().await.unwrap();
+                if !conn.recycle(request, response).await.unwrap() {
+                    break;
+                }
+            }
+        });
+
+        client
+            .write_all(b"GET /0 HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        let text = read_response(&mut client).await;
+        assert!(text.contains("content-length: 32\r\n"), "got: {}", text);
+        assert!(text.ends_with("\r\n\r\n__ZERO_COPY_FILE__:/etc/hostname"));
+
+        client
+            .write_all(b"GET /1 HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        let bytes = read_response(&mut client).await;
+        assert!(bytes.contains("content-length: 4\r\n"), "got: {}", bytes);
+
+        client
+            .write_all(b"GET /2 HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = Vec::new();
+        while !buf.ends_with(b"0\r\n\r\n") {
+            let mut chunk = [0u8; 1024];
+            let n = client.read(&mut chunk).await.unwrap();
+            assert!(n > 0, "connection closed early");
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        let chunked = String::from_utf8(buf).unwrap();
+        assert!(
+            chunked.contains("transfer-encoding: chunked\r\n"),
+            "got: {}",
+            chunked
+        );
+        assert!(!chunked.contains("content-length"));
+        assert!(chunked.ends_with("\r\n\r\n2\r\nab\r\n3\r\ncde\r\n0\r\n\r\n"));
+
+        // HTTP/1.0 은 chunked 를 모르므로 그대로 보내고 연결을 닫아 끝을 알린다
+        client
+            .write_all(b"GET /3 HTTP/1.0\r\nConnection: keep-alive\r\n\r\n")
+            .await
+            .unwrap();
+        let mut raw = Vec::new();
+        client.read_to_end(&mut raw).await.unwrap();
+        let raw = String::from_utf8(raw).unwrap();
+        assert!(raw.contains("connection: close\r\n"), "got: {}", raw);
+        assert!(!raw.contains("content-length") && !raw.contains("transfer-encoding"));
+        assert!(raw.ends_with("\r\n\r\nabcde"));
+
+        handle.await.unwrap();
+    }
+
+    #[cfg(feature = "arena")]
+    #[tokio::test]
+    async fn body_writer_streams_after_the_headers() {
+        use crate::connection::Connection;
+        use crate::test_support::{read_response, socket_pair, test_options};
+        use crate::{ResponseUtil, ResponseUtilArena};
+        use http::header::CONTENT_LENGTH;
+        use http::{HeaderValue, StatusCode};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        let (mut client, server) = socket_pair().await;
+        let peer = client.local_addr().unwrap();
+        let mut conn = Connection::new(server, test_options(), peer);
+
+        let handle = tokio::spawn(async move {
+            // chunked: write 한 번이 chunk 하나, 이후 responser 는 아무것도 보내지 않는다
+            let (request, mut response) = conn.next_request().await.unwrap().unwrap();
+            *response.status_mut() = StatusCode::OK;
+            let mut writer = response.body_writer().await.unwrap();
+            writer.write_all(b"hello ").await.unwrap();
+            writer.write_all(b"world").await.unwrap();
+            writer.finish().await.unwrap();
+            response.responser().await.unwrap();
+            assert!(response.body_writer().await.is_err());
+            assert!(conn.recycle(request, response).await.unwrap());
+
+            // Content-Length 를 정하면 그대로 보내고, 넘치게 쓰면 에러
+            let (request, mut response) = conn.next_request().await.unwrap().unwrap();
+            *response.status_mut() = StatusCode::OK;
+            response
+                .headers_mut()
+                .insert(CONTENT_LENGTH, HeaderValue::from_static("4"));
+            let mut writer = response.body_writer().await.unwrap();
+            assert!(writer.write_all(b"abcdef").await.is_err());
+            writer.finish().await.unwrap();
+            assert!(conn.recycle(request, response).await.unwrap());
+
+            // arena 는 AsyncRead 를 스트림으로 붙인다
+            let (_request, mut response) = conn.next_request_arena().await.unwrap().unwrap();
+            *response.status_mut() = StatusCode::OK;
+            let reader: &'static [u8] = b"from a reader";
+            response
+                .body_mut()
+                .set_arena_stream(BodyStream::from_reader(reader));
+            response.responser_arena().await.unwrap();
+            assert!(conn.recycle_arena(response).await.unwrap());
+
+            // 끝내지 않은 body 는 잘린 응답이므로 연결을 닫는다
+            let (request, mut response) = conn.next_request().await.unwrap().unwrap();
+            *response.status_mut() = StatusCode::OK;
+            let mut writer = response.body_writer().await.unwrap();
+            writer.write_all(b"partial").await.unwrap();
+            drop(writer);
+            assert!(!conn.recycle(request, response).await.unwrap());
+        });
+
+        async fn read_until(client: &mut TcpStream, end: &[u8]) -> String {
+            let mut buf = Vec::new();
+            while !buf.ends_with(end) {
+                let mut chunk = [0u8; 1024];
+                let n = client.read(&mut chunk).await.unwrap();
+                assert!(n > 0, "connection closed early");
+                buf.extend_from_slice(&chunk[..n]);
+            }
+            String::from_utf8(buf).unwrap()
+        }
+
+        let request = b"GET / HTTP/1.1\r\nHost: x\r\n\r\n";
+        client.write_all(request).await.unwrap();
+        let chunked = read_until(&mut client, b"0\r\n\r\n").await;
+        assert!(
+            chunked.starts_with("HTTP/1.1 200 OK\r\n"),
+            "got: {}",
+            chunked
+        );
+        assert!(chunked.contains("transfer-encoding: chunked\r\n"));
+        assert!(chunked.ends_with("\r\n\r\n6\r\nhello \r\n5\r\nworld\r\n0\r\n\r\n"));
+
+        client.write_all(request).await.unwrap();
+        let sized = read_response(&mut client).await;
+        assert!(sized.contains("content-length: 4\r\n"), "got: {}", sized);
+        assert!(sized.ends_with("\r\n\r\nabcd"));
+
+        client.write_all(request).await.unwrap();
+        let arena = read_until(&mut client, b"0\r\n\r\n").await;
+        assert!(
+            arena.ends_with("\r\n\r\nd\r\nfrom a reader\r\n0\r\n\r\n"),
+            "got: {}",
+            arena
+        );
+
+        client.write_all(request).await.unwrap();
+        let mut rest = Vec::new();
+        client.read_to_end(&mut rest).await.unwrap();
+        assert!(rest.ends_with(b"7\r\npartial\r\n"));
+
+        handle.await.unwrap();
+    }
+
+    #[cfg(all(feature = "response_file", feature = "arena"))]
+    #[tokio::test]
+    async fn streamed_file_read_errors_fail_the_response() {
+        use crate::test_support::{file_connection, TempDir};
+        use crate::ResponseUtilArena;
+        use tokio::io::AsyncWriteExt;
+
+        let dir = TempDir::new("read_error");
+        // 디렉터리는 열리지만 read 가 EISDIR 로 실패한다
+        std::fs::create_dir_all(dir.path().join("dir")).unwrap();
+
+        let (mut client, mut conn) =
+            file_connection(&dir, |options| options.zero_copy_threshold = 0).await;
+
+        let handle = tokio::spawn(async move {
+            let (_request, mut response) = conn.next_request_arena().await.unwrap().unwrap();
+            response.body_mut().response_file("dir").unwrap();
+            response.responser_arena().await
+        });
+
+        client
+            .write_all(b"GET /dir HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        assert!(handle.await.unwrap().is_err());
+    }
+}
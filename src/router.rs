@@ -291,6 +291,105 @@ impl<V> Default for Router<V> {
     }
 }
 
+/// Owned path parameters of the matched route, inserted into the request
+/// extensions when a [`Router`] is used as a [`Handler`](crate::Handler).
+///
+/// ```text
+/// route: "/users/{id}"
+/// request.extensions().get::<RouteParams>()?.get("id") => Some("42")
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteParams(Vec<(String, String)>);
+
+impl RouteParams {
+    /// Get a parameter value by name.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Iterate over all `(key, value)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns `true` if there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of parameters.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<V> Router<V> {
+    /// Methods other than `method` that `path` is routed for, in registration order.
+    fn other_methods(&self, method: &Method, path: &str) -> Vec<&Method> {
+        self.trees
+            .iter()
+            .filter(|(m, tree)| m != method && tree.at(path).is_ok())
+            .map(|(m, _)| m)
+            .collect()
+    }
+}
+
+/// A router whose values are handlers dispatches requests itself, so it can
+/// be passed to `Server::serve*` or wrapped in a middleware [`Chain`](crate::Chain).
+///
+/// Unmatched paths answer `404`, paths routed only for other methods `405`
+/// with an `Allow` header listing those methods.
+/// Use `Router<Box<dyn Handler>>` (or `Arc<dyn Handler>`) to mix handler types.
+#[async_trait::async_trait]
+impl<B, W, H> crate::Handler<B, W> for Router<H>
+where
+    B: Send + 'static,
+    W: Send + 'static,
+    H: crate::Handler<B, W>,
+{
+    async fn handle(
+        &self,
+        mut request: http::Request<B>,
+        response: &mut http::Response<W>,
+    ) -> Result<(), crate::SendableError> {
+        // params borrow the path; copy it so the request can move into the handler
+        let path = request.uri().path().to_string();
+        let Some(matched) = self.find(request.method(), &path) else {
+            let allowed = self.other_methods(request.method(), &path);
+            if allowed.is_empty() {
+                return Err(
+                    crate::StatusError::new(http::StatusCode::NOT_FOUND, "not found").into(),
+                );
+            }
+            let allow = allowed
+                .iter()
+                .map(|m| m.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            response
+                .headers_mut()
+                .insert(http::header::ALLOW, http::HeaderValue::from_str(&allow)?);
+            return Err(crate::StatusError::new(
+                http::StatusCode::METHOD_NOT_ALLOWED,
+                "method not allowed",
+            )
+            .into());
+        };
+        if !matched.params.is_empty() {
+            let params = matched
+                .params
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            request.extensions_mut().insert(RouteParams(params));
+        }
+        matched.value.handle(request, response).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -338,6 +437,24 @@ mod tests {
         assert!(router.find(&Method::POST, "/users").is_none());
     }
 
+    #[test]
+    fn other_methods_for_allow_header() {
+        let router = Router::new()
+            .get("/users", Route::GetUser)
+            .post("/users", Route::CreateUser)
+            .get("/", Route::Home);
+
+        assert_eq!(
+            router.other_methods(&Method::DELETE, "/users"),
+            [&Method::GET, &Method::POST]
+        );
+        assert_eq!(
+            router.other_methods(&Method::GET, "/users"),
+            [&Method::POST]
+        );
+        assert!(router.other_methods(&Method::GET, "/missing").is_empty());
+    }
+
     #[test]
     fn not_found() {
         let router = Router::new().get("/", Route::Home);
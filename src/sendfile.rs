@@ -0,0 +1,177 @@
+//! Linux `sendfile(2)` 파일 전송 (`sendfile` feature). 큰 정적 파일을 user-space 버퍼를 거치지 않고
+//! 커널이 파일에서 소켓으로 바로 보낸다. 평문 TCP 연결에서만 쓰고, 나머지는 호출자가 기존 경로로 보낸다.
+
+use std::io;
+use std::os::fd::AsRawFd;
+
+use tokio::io::Interest;
+use tokio::net::TcpStream;
+
+use crate::socket::OwnedWriteHalf;
+
+/// `sendfile` 한 번에 보낼 수 있는 최대 바이트 (Linux 제한).
+const MAX_CHUNK: u64 = 0x7fff_f000;
+
+#[cfg(test)]
+thread_local! {
+    /// 이 스레드에서 `sendfile` 로 끝까지 보낸 횟수. 테스트가 커널 경로를 탔는지 확인하는 데 쓴다
+    /// (`#[tokio::test]` 런타임은 한 스레드라 다른 테스트와 섞이지 않는다).
+    pub(crate) static SENT: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// `file` 의 `[offset, offset + len)` 을 `stream` 으로 보낸다. TCP 연결이 아니거나, 파일 시스템이
+/// `sendfile` 을 지원하지 않아 한 바이트도 보내지 못했으면 `Ok(false)` — 호출자가 기존 경로로 보낸다.
+pub(crate) async fn send_file<F: AsRawFd>(
+    stream: &OwnedWriteHalf,
+    file: &F,
+    offset: u64,
+    len: u64,
+) -> io::Result<bool> {
+    let OwnedWriteHalf::Tcp(half) = stream else {
+        return Ok(false);
+    };
+    let socket: &TcpStream = half.as_ref();
+    let mut offset = offset as libc::off_t;
+    let mut remaining = len;
+
+    while remaining > 0 {
+        socket.writable().await?;
+        let result = socket.try_io(Interest::WRITABLE, || {
+            let count = remaining.min(MAX_CHUNK) as usize;
+            // SAFETY: 두 fd 는 호출 동안 살아 있고, `offset` 은 커널이 갱신하는 지역 변수.
+            let sent =
+                unsafe { libc::sendfile(socket.as_raw_fd(), file.as_raw_fd(), &mut offset, count) };
+            if sent < 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(sent as u64)
+            }
+        });
+        match result {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "file shrank while sending",
+                ))
+            }
+            Ok(sent) => remaining -= sent,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+            Err(e)
+                if remaining == len
+                    && matches!(
+                        e.raw_os_error(),
+                        Some(libc::EINVAL | libc::ENOSYS | libc::EOPNOTSUPP)
+                    ) =>
+            {
+                return Ok(false)
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    #[cfg(test)]
+    SENT.with(|sent| sent.set(sent.get() + 1));
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::socket::Stream;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    async fn tcp_pair() -> (OwnedWriteHalf, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+        let (_, write) = Stream::from(server).into_split();
+        (write, client)
+    }
+
+    #[tokio::test]
+    async fn sends_the_requested_slice_of_the_file() {
+        let data: Vec<u8> = (0..3 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        let path = std::env::temp_dir().join(format!("atomic_sendfile_{}", std::process::id()));
+        std::fs::write(&path, &data).unwrap();
+        let file = std::fs::File::open(&path).unwrap();
+
+        let (write, mut client) = tcp_pair().await;
+        let reader = tokio::spawn(async move {
+            let mut received = Vec::new();
+            client.read_to_end(&mut received).await.unwrap();
+            received
+        });
+        assert!(send_file(&write, &file, 1000, data.len() as u64 - 2000)
+            .await
+            .unwrap());
+        drop(write);
+
+        let received = reader.await.unwrap();
+        assert_eq!(received.len(), data.len() - 2000);
+        assert!(received == data[1000..data.len() - 1000]);
+
+        // 파일보다 길게 요청하면 잘린 것으로 본다
+        let (write, _client) = tcp_pair().await;
+        let err = send_file(&write, &file, data.len() as u64 - 10, 11)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn non_tcp_streams_fall_back() {
+        let (a, _b) = tokio::net::UnixStream::pair().unwrap();
+        let (_, write) = Stream::Unix(a).into_split();
+        let file = std::fs::File::open("/proc/self/exe").unwrap();
+        assert!(!send_file(&write, &file, 0, 16).await.unwrap());
+    }
+
+    #[cfg(feature = "arena")]
+    #[tokio::test]
+    async fn large_files_are_sent_with_sendfile() {
+        use crate::test_support::{file_connection, read_raw, TempDir};
+        use crate::{ResponseUtil, ResponseUtilArena};
+        use tokio::io::AsyncWriteExt;
+
+        let dir = TempDir::new("sendfile");
+        // 메모리 캐시 한도 (1MB) 를 넘겨 mmap 경로를 타게 한다
+        let content: Vec<u8> = (0..1536 * 1024u32).map(|i| (i % 251) as u8).collect();
+        dir.write("large.bin", &content);
+
+        let (mut client, mut conn) = file_connection(&dir, |options| {
+            // Writer 는 mmap 경로, arena 는 이 크기를 넘으면 스트리밍 경로로 보낸다
+            options.zero_copy_threshold = 1024;
+            options.sendfile = true;
+        })
+        .await;
+
+        let handle = tokio::spawn(async move {
+            let (request, mut response) = conn.next_request().await.unwrap().unwrap();
+            response.body_mut().response_file("large.bin").unwrap();
+            response.responser().await.unwrap();
+            assert!(conn.recycle(request, response).await.unwrap());
+
+            let (_request, mut response) = conn.next_request_arena().await.unwrap().unwrap();
+            response.body_mut().response_file("large.bin").unwrap();
+            response.responser_arena().await.unwrap();
+        });
+
+        for _ in 0..2 {
+            client
+                .write_all(b"GET /large.bin HTTP/1.1\r\nHost: x\r\n\r\n")
+                .await
+                .unwrap();
+            let (head, body) = read_raw(&mut client).await;
+            assert!(head.starts_with("http/1.1 200"), "got: {}", head);
+            assert_eq!(body.len(), content.len());
+            assert!(body == content);
+        }
+
+        handle.await.unwrap();
+        // 두 응답 모두 버퍼 복사가 아니라 sendfile 로 나갔다
+        assert_eq!(SENT.with(|sent| sent.get()), 2);
+    }
+}
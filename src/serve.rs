@@ -0,0 +1,743 @@
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use http::header::{HeaderValue, CONTENT_TYPE};
+use http::{Request, Response, StatusCode};
+
+use crate::{
+    dev_print, Accept, Body, FramingError, ListenerId, ParseError, ResponseUtil, SendableError,
+    Server, Writer,
+};
+#[cfg(feature = "arena")]
+use crate::{ArenaBody, ArenaWriter, ResponseUtilArena};
+use crate::{AutoParseResult, Connection};
+
+/// `Server::serve*` 가 요청마다 호출하는 핸들러.
+///
+/// `response` 는 소켓 write half 를 가진 채 (기본 400, `application/json`) 전달되며 핸들러는
+/// 상태/헤더/body 만 채운다. 전송 (`responser`) 과 keep-alive 재사용은 `serve` 가 맡는다.
+/// `Err` 를 반환하면 `StatusError` 의 상태 코드로, 그 외 에러는 500 으로 응답한다.
+///
+/// arena 모드는 `Handler<ArenaBody, ArenaWriter>` 를 구현한다.
+///
+/// ```rust,no_run
+/// # use atomic_http::*;
+/// # use atomic_http::external::async_trait::async_trait;
+/// # use http::{Request, Response, StatusCode};
+/// struct Hello;
+///
+/// #[async_trait]
+/// impl Handler for Hello {
+///     async fn handle(
+///         &self,
+///         request: Request<Body>,
+///         response: &mut Response<Writer>,
+///     ) -> Result<(), SendableError> {
+///         if request.uri().path() != "/" {
+///             return Err(StatusError::new(StatusCode::NOT_FOUND, "not found").into());
+///         }
+///         response.body_mut().body = r#"{"hello":"world"}"#.into();
+///         *response.status_mut() = StatusCode::OK;
+///         Ok(())
+///     }
+/// }
+///
+/// # async fn run() -> Result<(), SendableError> {
+/// let server = Server::new("127.0.0.1:8080").await?;
+/// server.serve(Hello).await;
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait]
+pub trait Handler<B = Body, W = Writer>: Send + Sync + 'static {
+    async fn handle(
+        &self,
+        request: Request<B>,
+        response: &mut Response<W>,
+    ) -> Result<(), SendableError>;
+}
+
+#[async_trait]
+impl<B, W, H> Handler<B, W> for Box<H>
+where
+    B: Send + 'static,
+    W: Send + 'static,
+    H: Handler<B, W> + ?Sized,
+{
+    async fn handle(
+        &self,
+        request: Request<B>,
+        response: &mut Response<W>,
+    ) -> Result<(), SendableError> {
+        (**self).handle(request, response).await
+    }
+}
+
+#[async_trait]
+impl<B, W, H> Handler<B, W> for Arc<H>
+where
+    B: Send + 'static,
+    W: Send + 'static,
+    H: Handler<B, W> + ?Sized,
+{
+    async fn handle(
+        &self,
+        request: Request<B>,
+        response: &mut Response<W>,
+    ) -> Result<(), SendableError> {
+        (**self).handle(request, response).await
+    }
+}
+
+/// WebSocket 업그레이드가 끝난 연결을 넘겨받는 핸들러 (`Server::serve_websocket`).
+///
+/// `request` extensions 에 `ConnectionGuard` 가 들어있으므로, 긴 루프는
+/// `guard.triggered()` 를 함께 기다려 graceful shutdown 때 스스로 닫는다.
+#[cfg(feature = "websocket")]
+#[async_trait]
+pub trait WebSocketHandler: Send + Sync + 'static {
+    async fn on_upgrade(
+        &self,
+        stream: tokio_tungstenite::WebSocketStream<crate::Stream>,
+        request: Request<()>,
+        peer: crate::Peer,
+    ) -> Result<(), SendableError>;
+}
+
+/// 핸들러가 응답 상태 코드를 지정해 실패를 알리는 에러. `message` 는 `{"error": message}` 로 응답된다.
+#[derive(Debug)]
+pub struct StatusError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl StatusError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// 핸들러 에러 → (상태 코드, 응답 메시지). 내부 에러 내용은 클라이언트에 노출하지 않는다.
+/// `FramingError` / `ParseError` 는 클라이언트 요청 문제이므로 해당 상태 코드 + 사유.
+pub(crate) fn error_status(e: &SendableError) -> (StatusCode, String) {
+    if let Some(framing) = e.downcast_ref::<FramingError>() {
+        return (StatusCode::BAD_REQUEST, framing.to_string());
+    }
+    if let Some((status, parse)) = e
+        .downcast_ref::<ParseError>()
+        .and_then(|parse| Some((parse.status()?, parse)))
+    {
+        return (status, parse.to_string());
+    }
+    match e.downcast_ref::<StatusError>() {
+        Some(se) => (se.status, se.message.clone()),
+        None => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::INTERNAL_SERVER_ERROR
+                .canonical_reason()
+                .unwrap_or_default()
+                .to_string(),
+        ),
+    }
+}
+
+/// writer 종류 (`Writer` / `ArenaWriter`) 에 상관없이 에러 응답 + 전송.
+#[async_trait]
+pub(crate) trait ServeResponse: Send {
+    fn set_error(&mut self, status: StatusCode, message: &str);
+    async fn send(&mut self) -> Result<(), SendableError>;
+}
+
+#[async_trait]
+impl ServeResponse for Response<Writer> {
+    fn set_error(&mut self, status: StatusCode, message: &str) {
+        *self.status_mut() = status;
+        self.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        self.body_mut().body = serde_json::json!({ "error": message }).to_string().into();
+    }
+
+    async fn send(&mut self) -> Result<(), SendableError> {
+        self.responser().await
+    }
+}
+
+#[cfg(feature = "arena")]
+#[async_trait]
+impl ServeResponse for Response<ArenaWriter> {
+    fn set_error(&mut self, status: StatusCode, message: &str) {
+        *self.status_mut() = status;
+        self.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        let _ = self
+            .body_mut()
+            .set_arena_json(&serde_json::json!({ "error": message }));
+    }
+
+    async fn send(&mut self) -> Result<(), SendableError> {
+        self.responser_arena().await
+    }
+}
+
+/// 핸들러 호출 → 에러 매핑 → 전송. 전송에 실패하면 `None` (연결 종료).
+/// 요청 extensions 에 연결이 들어온 `ListenerId` 를 넣어 핸들러가 listener 별로 분기할 수 있게 한다.
+async fn respond<B, W, H>(
+    handler: &H,
+    listener: ListenerId,
+    mut request: Request<B>,
+    mut response: Response<W>,
+) -> Option<Response<W>>
+where
+    H: Handler<B, W> + ?Sized,
+    B: Send + 'static,
+    W: Send + 'static,
+    Response<W>: ServeResponse,
+{
+    request.extensions_mut().insert(listener);
+    if let Err(e) = handler.handle(request, &mut response).await {
+        dev_print!("handler error: {}", e);
+        let (status, message) = error_status(&e);
+        response.set_error(status, &message);
+    }
+    match response.send().await {
+        Ok(()) => Some(response),
+        Err(e) => {
+            dev_print!("failed to send response: {}", e);
+            None
+        }
+    }
+}
+
+impl Server {
+    /// accept → spawn → buffered 파싱 → `handler` → `responser` 를 keep-alive 연결 단위로 반복.
+    /// `shutdown_handle().trigger()` 로 멈추며, 연결이 정리될 때까지
+    /// (`Options::shutdown_deadline_ms`) 기다린 뒤 남은 연결 수를 반환한다.
+    pub async fn serve<H>(self, handler: H) -> usize
+    where
+        H: Handler,
+    {
+        let handler = Arc::new(handler);
+        self.run(move |accept| serve_buffered(accept, Arc::clone(&handler)))
+            .await
+    }
+
+    /// `serve` 의 arena (zero-copy 파싱) 버전.
+    #[cfg(feature = "arena")]
+    pub async fn serve_arena<H>(self, handler: H) -> usize
+    where
+        H: Handler<ArenaBody, ArenaWriter>,
+    {
+        let handler = Arc::new(handler);
+        self.run(move |accept| serve_arena(accept, Arc::clone(&handler)))
+            .await
+    }
+
+    /// `serve` 의 auto 버전 — Content-Length ≤ `DEFAULT_AUTO_ARENA_CAP` 이면 arena 핸들러,
+    /// 초과 시 streaming body 로 `Handler<Body, Writer>` 호출.
+    /// streaming body 요청은 핸들러가 body 를 소비하므로 응답 후 연결을 닫는다.
+    #[cfg(feature = "arena")]
+    pub async fn serve_auto<H>(self, handler: H) -> usize
+    where
+        H: Handler + Handler<ArenaBody, ArenaWriter>,
+    {
+        let handler = Arc::new(handler);
+        self.run(move |accept| serve_auto(accept, Arc::clone(&handler)))
+            .await
+    }
+
+    /// `serve` 의 auto 버전 (arena feature 비활성화 시 항상 streaming body).
+    #[cfg(not(feature = "arena"))]
+    pub async fn serve_auto<H>(self, handler: H) -> usize
+    where
+        H: Handler,
+    {
+        let handler = Arc::new(handler);
+        self.run(move |accept| serve_auto(accept, Arc::clone(&handler)))
+            .await
+    }
+
+    /// WebSocket 업그레이드 요청은 `ws_handler` 로, 나머지 HTTP 는 `serve_auto` 와 같은 규칙으로
+    /// `handler` 에 넘긴다 (`Accept::stream_parse_auto`). HTTP 응답 후 연결은 닫힌다.
+    #[cfg(all(feature = "websocket", feature = "arena"))]
+    pub async fn serve_websocket<H, S>(self, handler: H, ws_handler: S) -> usize
+    where
+        H: Handler + Handler<ArenaBody, ArenaWriter>,
+        S: WebSocketHandler,
+    {
+        let handler = Arc::new(handler);
+        let ws_handler = Arc::new(ws_handler);
+        self.run(move |accept| {
+            serve_websocket(accept, Arc::clone(&handler), Arc::clone(&ws_handler))
+        })
+        .await
+    }
+
+    /// `serve_websocket` (arena feature 비활성화 시 HTTP 는 항상 streaming body).
+    #[cfg(all(feature = "websocket", not(feature = "arena")))]
+    pub async fn serve_websocket<H, S>(self, handler: H, ws_handler: S) -> usize
+    where
+        H: Handler,
+        S: WebSocketHandler,
+    {
+        let handler = Arc::new(handler);
+        let ws_handler = Arc::new(ws_handler);
+        self.run(move |accept| {
+            serve_websocket(accept, Arc::clone(&handler), Arc::clone(&ws_handler))
+        })
+        .await
+    }
+
+    /// 공통 accept 루프. shutdown 이 트리거되면 빠져나와 drain 까지 수행.
+    async fn run<F, Fut>(mut self, spawn: F) -> usize
+    where
+        F: Fn(Accept) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        while !self.is_shutting_down() {
+            match self.accept().await {
+                Ok(accept) => {
+                    tokio::spawn(spawn(accept));
+                }
+                Err(e) => dev_print!("accept error: {}", e),
+            }
+        }
+        let deadline = Duration::from_millis(self.options.shutdown_deadline_ms);
+        self.shutdown(deadline).await
+    }
+}
+
+async fn serve_buffered<H: Handler>(accept: Accept, handler: Arc<H>) {
+    let mut conn = accept.into_connection();
+    loop {
+        let (request, response) = match conn.next_request().await {
+            Ok(Some(pair)) => pair,
+            Ok(None) => break,
+            Err(e) => {
+                dev_print!("failed to parse request from {}: {}", conn.peer(), e);
+                break;
+            }
+        };
+        let response = match respond(&*handler, conn.listener(), request, response).await {
+            Some(r) => r,
+            None => break,
+        };
+        if !conn.recycle_response(response).await.unwrap_or(false) {
+            break;
+        }
+    }
+}
+
+#[cfg(feature = "arena")]
+async fn serve_arena<H: Handler<ArenaBody, ArenaWriter>>(accept: Accept, handler: Arc<H>) {
+    let mut conn = accept.into_connection();
+    loop {
+        let (request, response) = match conn.next_request_arena().await {
+            Ok(Some(pair)) => pair,
+            Ok(None) => break,
+            Err(e) => {
+                dev_print!("failed to parse request from {}: {}", conn.peer(), e);
+                break;
+            }
+        };
+        let response = match respond(&*handler, conn.listener(), request, response).await {
+            Some(r) => r,
+            None => break,
+        };
+        if !conn.recycle_arena(response).await.unwrap_or(false) {
+            break;
+        }
+    }
+}
+
+#[cfg(feature = "arena")]
+async fn serve_auto<H>(accept: Accept, handler: Arc<H>)
+where
+    H: Handler + Handler<ArenaBody, ArenaWriter>,
+{
+    let mut conn = accept.into_connection();
+    loop {
+        let result = match conn.next_request_auto().await {
+            Ok(Some(result)) => result,
+            Ok(None) => break,
+            Err(e) => {
+                dev_print!("failed to parse request from {}: {}", conn.peer(), e);
+                break;
+            }
+        };
+        if !handle_auto(&mut conn, &*handler, result).await {
+            break;
+        }
+    }
+}
+
+#[cfg(not(feature = "arena"))]
+async fn serve_auto<H: Handler>(accept: Accept, handler: Arc<H>) {
+    let mut conn = accept.into_connection();
+    loop {
+        let result = match conn.next_request_auto().await {
+            Ok(Some(result)) => result,
+            Ok(None) => break,
+            Err(e) => {
+                dev_print!("failed to parse request from {}: {}", conn.peer(), e);
+                break;
+            }
+        };
+        if !handle_auto(&mut conn, &*handler, result).await {
+            break;
+        }
+    }
+}
+
+/// auto 파싱 결과 하나를 처리하고 연결을 계속 쓸 수 있으면 `true`.
+#[cfg(feature = "arena")]
+async fn handle_auto<H>(conn: &mut Connection, handler: &H, result: AutoParseResult) -> bool
+where
+    H: Handler + Handler<ArenaBody, ArenaWriter>,
+{
+    match result {
+        AutoParseResult::Arena { request, response } => {
+            match respond::<ArenaBody, ArenaWriter, H>(handler, conn.listener(), request, response)
+                .await
+            {
+                Some(response) => conn.recycle_arena(response).await.unwrap_or(false),
+                None => false,
+            }
+        }
+        AutoParseResult::Streaming { request, response } => {
+            match respond::<Body, Writer, H>(handler, conn.listener(), request, response).await {
+                Some(response) => conn.recycle_response(response).await.unwrap_or(false),
+                None => false,
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "arena"))]
+async fn handle_auto<H: Handler>(
+    conn: &mut Connection,
+    handler: &H,
+    result: AutoParseResult,
+) -> bool {
+    let AutoParseResult::Streaming { request, response } = result;
+    match respond(handler, conn.listener(), request, response).await {
+        Some(response) => conn.recycle_response(response).await.unwrap_or(false),
+        None => false,
+    }
+}
+
+#[cfg(all(feature = "websocket", feature = "arena"))]
+async fn serve_websocket<H, S>(accept: Accept, handler: Arc<H>, ws_handler: Arc<S>)
+where
+    H: Handler + Handler<ArenaBody, ArenaWriter>,
+    S: WebSocketHandler,
+{
+    use crate::StreamResultAuto;
+    let peer = accept.peer;
+    let listener = accept.listener;
+    match accept.stream_parse_auto().await {
+        Ok(StreamResultAuto::WebSocket(stream, mut request, peer)) => {
+            request.extensions_mut().insert(listener);
+            if let Err(e) = ws_handler.on_upgrade(stream, request, peer).await {
+                dev_print!("websocket handler error from {}: {}", peer, e);
+            }
+        }
+        Ok(StreamResultAuto::HttpArena(request, response)) => {
+            respond::<ArenaBody, ArenaWriter, H>(&*handler, listener, request, response).await;
+        }
+        Ok(StreamResultAuto::HttpStreaming(request, response)) => {
+            respond::<Body, Writer, H>(&*handler, listener, request, response).await;
+        }
+        Err(e) => dev_print!("failed to parse request from {}: {}", peer, e),
+    }
+}
+
+#[cfg(all(feature = "websocket", not(feature = "arena")))]
+async fn serve_websocket<H, S>(accept: Accept, handler: Arc<H>, ws_handler: Arc<S>)
+where
+    H: Handler,
+    S: WebSocketHandler,
+{
+    use crate::StreamResultAuto;
+    let peer = accept.peer;
+    let listener = accept.listener;
+    match accept.stream_parse_auto().await {
+        Ok(StreamResultAuto::WebSocket(stream, mut request, peer)) => {
+            request.extensions_mut().insert(listener);
+            if let Err(e) = ws_handler.on_upgrade(stream, request, peer).await {
+                dev_print!("websocket handler error from {}: {}", peer, e);
+            }
+        }
+        Ok(StreamResultAuto::HttpStreaming(request, response)) => {
+            respond(&*handler, listener, request, response).await;
+        }
+        Err(e) => dev_print!("failed to parse request from {}: {}", peer, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    struct Echo;
+
+    #[async_trait]
+    impl Handler for Echo {
+        async fn handle(
+            &self,
+            request: Request<Body>,
+            response: &mut Response<Writer>,
+        ) -> Result<(), SendableError> {
+            match request.uri().path() {
+                "/fail" => Err("boom".into()),
+                "/teapot" => {
+                    Err(StatusError::new(StatusCode::IM_A_TEAPOT, "short and stout").into())
+                }
+                "/capped" => {
+                    request.into_body().bytes(Some(4)).await?;
+                    Ok(())
+                }
+                path => {
+                    response.body_mut().body = path.to_string().into();
+                    *response.status_mut() = StatusCode::OK;
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn serve_runs_handler_and_maps_errors_to_status() {
+        let server = Server::new("127.0.0.1:0").await.unwrap();
+        let addr = server.listener().local_addr().unwrap();
+        let shutdown = server.shutdown_handle();
+        let serving = tokio::spawn(server.serve(Echo));
+
+        // 한 연결에서 네 요청 (keep-alive) — 마지막에 close
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(
+                b"GET /hello HTTP/1.1\r\nHost: x\r\n\r\n\
+                  GET /fail HTTP/1.1\r\nHost: x\r\n\r\n\
+                  POST /capped HTTP/1.1\r\nHost: x\r\nContent-Length: 8\r\n\r\n12345678\
+                  GET /teapot HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut all = Vec::new();
+        client.read_to_end(&mut all).await.unwrap();
+        let all = String::from_utf8_lossy(&all);
+
+        let ok = all.find("HTTP/1.1 200 OK").expect(&all);
+        let internal = all.find("HTTP/1.1 500").expect(&all);
+        let too_large = all.find("HTTP/1.1 413").expect(&all);
+        let teapot = all.find("HTTP/1.1 418").expect(&all);
+        assert!(ok < internal && internal < too_large && too_large < teapot);
+        assert!(all.contains("/hello"));
+        assert!(!all.contains("boom"), "internal error must not leak");
+        assert!(all.contains(r#"{"error":"short and stout"}"#));
+
+        shutdown.trigger();
+        let remaining = tokio::time::timeout(Duration::from_secs(3), serving)
+            .await
+            .expect("serve should return after shutdown")
+            .unwrap();
+        assert_eq!(remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn serve_rejects_smuggling_attempt_with_400_and_closes() {
+        let server = Server::new("127.0.0.1:0").await.unwrap();
+        let addr = server.listener().local_addr().unwrap();
+        let shutdown = server.shutdown_handle();
+        let serving = tokio::spawn(server.serve(Echo));
+
+        // 두 번째 요청이 CL + TE — 그 뒤에 숨긴 요청은 처리되면 안 된다
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(
+                b"GET /hello HTTP/1.1\r\nHost: x\r\n\r\n\
+                  POST /smuggle HTTP/1.1\r\nHost: x\r\nContent-Length: 4\r\nTransfer-Encoding: chunked\r\n\r\n\
+                  0\r\n\r\nGET /hidden HTTP/1.1\r\nHost: x\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut all = Vec::new();
+        client.read_to_end(&mut all).await.unwrap();
+        let all = String::from_utf8_lossy(&all);
+
+        let ok = all.find("HTTP/1.1 200 OK").expect(&all);
+        let bad = all.find("HTTP/1.1 400 Bad Request").expect(&all);
+        assert!(ok < bad);
+        assert!(all.contains("connection: close"), "{}", all);
+        assert!(!all.contains("/hidden"), "{}", all);
+        assert_eq!(all.matches("HTTP/1.1").count(), 2, "{}", all);
+
+        shutdown.trigger();
+        tokio::time::timeout(Duration::from_secs(3), serving)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn serve_tags_requests_with_listener_and_applies_its_options() {
+        struct WhichListener;
+
+        #[async_trait]
+        impl Handler for WhichListener {
+            async fn handle(
+                &self,
+                request: Request<Body>,
+                response: &mut Response<Writer>,
+            ) -> Result<(), SendableError> {
+                let id = request.extensions().get::<ListenerId>().copied().unwrap();
+                response.body_mut().body = id.index().to_string().into();
+                *response.status_mut() = StatusCode::OK;
+                Ok(())
+            }
+        }
+
+        async fn send(addr: std::net::SocketAddr, raw: &[u8]) -> String {
+            let mut client = TcpStream::connect(addr).await.unwrap();
+            client.write_all(raw).await.unwrap();
+            let mut all = Vec::new();
+            let _ = client.read_to_end(&mut all).await;
+            String::from_utf8_lossy(&all).to_string()
+        }
+
+        let mut server = Server::new("127.0.0.1:0").await.unwrap();
+        let mut admin_options = crate::Options::new();
+        admin_options.max_body_size = Some(4);
+        let admin = server
+            .add_listener_with_options("127.0.0.1:0", admin_options)
+            .await
+            .unwrap();
+        assert_eq!(admin.index(), 1);
+        let public_addr = server.listener().local_addr().unwrap();
+        let (_, admin_listener) = server.listeners().nth(1).unwrap();
+        let admin_addr = admin_listener.local_addr().unwrap();
+        let shutdown = server.shutdown_handle();
+        let serving = tokio::spawn(server.serve(WhichListener));
+
+        let get = b"GET / HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n";
+        let post = b"POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 10\r\nConnection: close\r\n\r\n0123456789";
+
+        let public = send(public_addr, get).await;
+        assert!(public.starts_with("HTTP/1.1 200") && public.ends_with("\r\n\r\n0"));
+        let admin = send(admin_addr, get).await;
+        assert!(admin.starts_with("HTTP/1.1 200") && admin.ends_with("\r\n\r\n1"));
+
+        // max_body_size 는 admin listener 에만 적용
+        let public = send(public_addr, post).await;
+        assert!(public.starts_with("HTTP/1.1 200"), "got: {}", public);
+        let admin = send(admin_addr, post).await;
+        assert!(!admin.starts_with("HTTP/1.1 200"), "got: {}", admin);
+
+        shutdown.trigger();
+        serving.await.unwrap();
+    }
+
+    #[cfg(all(
+        unix,
+        not(any(target_os = "solaris", target_os = "illumos", target_os = "cygwin"))
+    ))]
+    #[tokio::test]
+    async fn reuseport_acceptors_share_port_and_shutdown() {
+        let servers = Server::bind_reuseport("127.0.0.1:0", 3, crate::Options::new())
+            .await
+            .unwrap();
+        assert_eq!(servers.len(), 3);
+        let addr = servers[0].listener().local_addr().unwrap();
+        assert!(servers
+            .iter()
+            .all(|s| s.listener().local_addr().unwrap() == addr));
+        let shutdown = servers[0].shutdown_handle();
+        let handler = Arc::new(Echo);
+        let serving: Vec<_> = servers
+            .into_iter()
+            .map(|server| tokio::spawn(server.serve(Arc::clone(&handler))))
+            .collect();
+
+        for i in 0..12 {
+            let mut client = TcpStream::connect(addr).await.unwrap();
+            let raw = format!(
+                "GET /{} HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+                i
+            );
+            client.write_all(raw.as_bytes()).await.unwrap();
+            let mut all = Vec::new();
+            client.read_to_end(&mut all).await.unwrap();
+            let all = String::from_utf8_lossy(&all);
+            assert!(all.ends_with(&format!("\r\n\r\n/{}", i)), "got: {}", all);
+        }
+
+        // 공유 shutdown handle 하나로 모든 acceptor 가 멈춘다
+        shutdown.trigger();
+        for task in serving {
+            let remaining = tokio::time::timeout(Duration::from_secs(3), task)
+                .await
+                .expect("every acceptor should stop")
+                .unwrap();
+            assert_eq!(remaining, 0);
+        }
+    }
+
+    #[cfg(feature = "arena")]
+    #[tokio::test]
+    async fn serve_arena_handles_json_body() {
+        struct Sum;
+
+        #[async_trait]
+        impl Handler<ArenaBody, ArenaWriter> for Sum {
+            async fn handle(
+                &self,
+                request: Request<ArenaBody>,
+                response: &mut Response<ArenaWriter>,
+            ) -> Result<(), SendableError> {
+                let numbers: Vec<u64> = request.body().parse_json_zero_copy()?;
+                response
+                    .body_mut()
+                    .set_arena_json(&numbers.iter().sum::<u64>())?;
+                *response.status_mut() = StatusCode::OK;
+                Ok(())
+            }
+        }
+
+        let server = Server::new("127.0.0.1:0").await.unwrap();
+        let addr = server.listener().local_addr().unwrap();
+        let shutdown = server.shutdown_handle();
+        let serving = tokio::spawn(server.serve_arena(Sum));
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(
+                b"POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 7\r\nConnection: close\r\n\r\n[1,2,3]",
+            )
+            .await
+            .unwrap();
+        let mut all = Vec::new();
+        client.read_to_end(&mut all).await.unwrap();
+        let all = String::from_utf8_lossy(&all);
+        assert!(all.starts_with("HTTP/1.1 200"), "got: {}", all);
+        assert!(all.ends_with("\r\n\r\n6"), "got: {}", all);
+
+        shutdown.trigger();
+        serving.await.unwrap();
+    }
+}
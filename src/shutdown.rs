@@ -0,0 +1,249 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{watch, Notify};
+
+use crate::connection_limit::ConnectionPermit;
+
+/// graceful shutdown 트리거 + 살아있는 연결 수 추적. `Server` 가 하나 보유하고 `Clone` 으로 공유.
+///
+/// ```rust,no_run
+/// # use atomic_http::*;
+/// # async fn run(stop: tokio::sync::oneshot::Receiver<()>) -> Result<(), SendableError> {
+/// let mut server = Server::new("127.0.0.1:8080").await?;
+/// let handle = server.shutdown_handle();
+/// // 시그널, 관리 API 등 아무 future 나 가능
+/// handle.trigger_on(async {
+///     let _ = stop.await;
+/// });
+/// while !server.is_shutting_down() {
+///     let Ok(accept) = server.accept().await else { continue };
+///     tokio::spawn(async move {
+///         let mut conn = accept.into_connection();
+///         // ... next_request / recycle
+///     });
+/// }
+/// let remaining = server.shutdown(std::time::Duration::from_secs(30)).await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ShutdownHandle {
+    inner: Arc<ShutdownInner>,
+}
+
+struct ShutdownInner {
+    triggered: watch::Sender<bool>,
+    live: AtomicUsize,
+    /// live가 0이 될 때 깨움.
+    drained: Notify,
+}
+
+impl Default for ShutdownHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShutdownHandle {
+    pub fn new() -> Self {
+        let (triggered, _) = watch::channel(false);
+        Self {
+            inner: Arc::new(ShutdownInner {
+                triggered,
+                live: AtomicUsize::new(0),
+                drained: Notify::new(),
+            }),
+        }
+    }
+
+    /// shutdown 시작. 여러 번 호출해도 안전.
+    pub fn trigger(&self) {
+        self.inner.triggered.send_replace(true);
+    }
+
+    /// `signal` 이 끝나면 shutdown 을 시작하는 task 를 띄운다 (ctrl_c, channel 수신 등).
+    pub fn trigger_on<F>(&self, signal: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let handle = self.clone();
+        tokio::spawn(async move {
+            signal.await;
+            handle.trigger();
+        });
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        *self.inner.triggered.borrow()
+    }
+
+    /// shutdown 이 시작될 때까지 대기. 이미 시작됐으면 즉시 반환.
+    /// WebSocket 루프나 긴 핸들러에서 `tokio::select!` 로 함께 기다리는 용도.
+    pub async fn triggered(&self) {
+        let mut rx = self.inner.triggered.subscribe();
+        // sender는 self가 보유하므로 Err(닫힘)는 발생하지 않음
+        let _ = rx.wait_for(|v| *v).await;
+    }
+
+    /// 현재 살아있는 (`ConnectionGuard` 를 보유한) 연결 수.
+    pub fn live_connections(&self) -> usize {
+        self.inner.live.load(Ordering::Acquire)
+    }
+
+    /// 살아있는 연결이 모두 끝나거나 `deadline` 이 지날 때까지 대기.
+    /// 반환값은 deadline 시점에 아직 남아있는 연결 수 (0이면 모두 정리됨).
+    pub async fn drain(&self, deadline: Duration) -> usize {
+        let wait = async {
+            loop {
+                let notified = self.inner.drained.notified();
+                if self.live_connections() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        let _ = tokio::time::timeout(deadline, wait).await;
+        self.live_connections()
+    }
+
+    /// 연결 하나를 추적 시작. 반환된 guard (와 그 clone) 가 모두 drop 되면 연결 종료로 본다.
+    pub fn track(&self) -> ConnectionGuard {
+        self.track_with(None)
+    }
+
+    /// `track` + 동시 연결 한도 슬롯. 슬롯은 guard 와 함께 반환된다.
+    pub(crate) fn track_with(&self, permit: Option<ConnectionPermit>) -> ConnectionGuard {
+        self.inner.live.fetch_add(1, Ordering::AcqRel);
+        ConnectionGuard {
+            tracked: Arc::new(Tracked {
+                handle: self.clone(),
+                _permit: permit,
+            }),
+        }
+    }
+}
+
+/// `Server::accept` 가 연결마다 발급하는 추적 토큰. `Writer` / `ArenaWriter` / `Connection` 으로
+/// 옮겨 다니며, WebSocket 업그레이드 시에는 `Request<()>` 의 extensions 에 들어간다.
+/// 동시 연결 한도 (`Options::max_connections*`) 슬롯도 이 guard 가 drop 될 때 반환된다.
+#[derive(Clone)]
+pub struct ConnectionGuard {
+    tracked: Arc<Tracked>,
+}
+
+struct Tracked {
+    handle: ShutdownHandle,
+    _permit: Option<ConnectionPermit>,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        let inner = &self.handle.inner;
+        if inner.live.fetch_sub(1, Ordering::AcqRel) == 1 {
+            inner.drained.notify_waiters();
+        }
+    }
+}
+
+impl ConnectionGuard {
+    pub fn is_shutting_down(&self) -> bool {
+        self.tracked.handle.is_shutting_down()
+    }
+
+    /// `ShutdownHandle::triggered` 와 동일.
+    pub async fn triggered(&self) {
+        self.tracked.handle.triggered().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn drain_waits_for_guards() {
+        let handle = ShutdownHandle::new();
+        let guard = handle.track();
+        let clone = guard.clone();
+        assert_eq!(handle.live_connections(), 1);
+
+        drop(guard);
+        // clone이 남아있으므로 아직 live
+        assert_eq!(handle.drain(Duration::from_millis(20)).await, 1);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(clone);
+        });
+        assert_eq!(handle.drain(Duration::from_secs(2)).await, 0);
+    }
+
+    #[tokio::test]
+    async fn server_shutdown_closes_idle_keep_alive_and_drains() {
+        use crate::{ResponseUtil, Server};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut server = Server::new("127.0.0.1:0").await.unwrap();
+        let addr = server.listener().local_addr().unwrap();
+        let handle = server.shutdown_handle();
+
+        let acceptor = tokio::spawn(async move {
+            while !server.is_shutting_down() {
+                let Ok(accept) = server.accept().await else {
+                    continue;
+                };
+                tokio::spawn(async move {
+                    let mut conn = accept.into_connection();
+                    while let Ok(Some((request, mut response))) = conn.next_request().await {
+                        *response.status_mut() = http::StatusCode::OK;
+                        response.responser().await.unwrap();
+                        if !conn.recycle(request, response).await.unwrap() {
+                            break;
+                        }
+                    }
+                });
+            }
+            server.shutdown(Duration::from_secs(2)).await
+        });
+
+        let mut client = tokio::net::TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = [0u8; 512];
+        let n = client.read(&mut buf).await.unwrap();
+        assert!(buf[..n].starts_with(b"HTTP/1.1 200"));
+        assert_eq!(handle.live_connections(), 1);
+
+        // keep-alive idle 상태에서 shutdown → 연결이 닫히고 drain 완료
+        handle.trigger();
+        let remaining = tokio::time::timeout(Duration::from_secs(3), acceptor)
+            .await
+            .expect("shutdown should finish before deadline")
+            .unwrap();
+        assert_eq!(remaining, 0);
+        assert_eq!(client.read(&mut buf).await.unwrap(), 0);
+        assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn trigger_on_future_wakes_waiters() {
+        let handle = ShutdownHandle::new();
+        let guard = handle.track();
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        handle.trigger_on(async move {
+            let _ = rx.await;
+        });
+        assert!(!guard.is_shutting_down());
+
+        tx.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(1), guard.triggered())
+            .await
+            .expect("trigger_on should fire");
+        assert!(handle.is_shutting_down());
+    }
+}
@@ -0,0 +1,716 @@
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{tcp, TcpListener, TcpStream};
+#[cfg(unix)]
+use tokio::net::{unix, UnixListener, UnixStream};
+
+#[cfg(unix)]
+pub use tokio::net::unix::UCred;
+
+/// 요청을 주고받는 소켓. TCP 와 (unix 플랫폼에서) Unix domain socket 을 같은 파싱/응답 경로로 다룬다.
+#[derive(Debug)]
+pub enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    /// TCP 에서만 의미가 있으므로 UDS 는 무시.
+    pub fn set_nodelay(&self, no_delay: bool) -> io::Result<()> {
+        match self {
+            Stream::Tcp(s) => s.set_nodelay(no_delay),
+            #[cfg(unix)]
+            Stream::Unix(_) => Ok(()),
+        }
+    }
+
+    pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
+        match self {
+            Stream::Tcp(s) => {
+                let (r, w) = s.into_split();
+                (OwnedReadHalf::Tcp(r), OwnedWriteHalf::Tcp(w))
+            }
+            #[cfg(unix)]
+            Stream::Unix(s) => {
+                let (r, w) = s.into_split();
+                (OwnedReadHalf::Unix(r), OwnedWriteHalf::Unix(w))
+            }
+        }
+    }
+}
+
+impl From<TcpStream> for Stream {
+    fn from(stream: TcpStream) -> Self {
+        Stream::Tcp(stream)
+    }
+}
+
+#[cfg(unix)]
+impl From<UnixStream> for Stream {
+    fn from(stream: UnixStream) -> Self {
+        Stream::Unix(stream)
+    }
+}
+
+/// `Stream::into_split` 의 read half. `Body` (streaming) 와 `Writer` (keep-alive 재조립용) 가 보유.
+#[derive(Debug)]
+pub enum OwnedReadHalf {
+    Tcp(tcp::OwnedReadHalf),
+    #[cfg(unix)]
+    Unix(unix::OwnedReadHalf),
+}
+
+/// `Stream::into_split` 의 write half. `Writer` / `ArenaWriter` 의 `stream`.
+#[derive(Debug)]
+pub enum OwnedWriteHalf {
+    Tcp(tcp::OwnedWriteHalf),
+    #[cfg(unix)]
+    Unix(unix::OwnedWriteHalf),
+}
+
+impl OwnedReadHalf {
+    /// 같은 소켓에서 나온 두 half 를 다시 합친다.
+    pub fn reunite(self, write: OwnedWriteHalf) -> io::Result<Stream> {
+        let mismatch = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "halves are not from the same socket",
+            )
+        };
+        match (self, write) {
+            (OwnedReadHalf::Tcp(r), OwnedWriteHalf::Tcp(w)) => {
+                r.reunite(w).map(Stream::Tcp).map_err(|_| mismatch())
+            }
+            #[cfg(unix)]
+            (OwnedReadHalf::Unix(r), OwnedWriteHalf::Unix(w)) => {
+                r.reunite(w).map(Stream::Unix).map_err(|_| mismatch())
+            }
+            #[cfg(unix)]
+            _ => Err(mismatch()),
+        }
+    }
+
+    /// write half 없이 같은 소켓에 짧은 interim 응답 (`100 Continue`) 을 쓴다. `Body` 전용 —
+    /// write half 를 가진 `Writer` 가 최종 응답을 쓰기 전에만 호출되므로 순서가 섞이지 않는다.
+    pub(crate) async fn write_interim(&self, mut bytes: &[u8]) -> io::Result<()> {
+        while !bytes.is_empty() {
+            let written = match self {
+                OwnedReadHalf::Tcp(r) => {
+                    let stream: &TcpStream = r.as_ref();
+                    stream.writable().await?;
+                    stream.try_write(bytes)
+                }
+                #[cfg(unix)]
+                OwnedReadHalf::Unix(r) => {
+                    let stream: &UnixStream = r.as_ref();
+                    stream.writable().await?;
+                    stream.try_write(bytes)
+                }
+            };
+            match written {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => bytes = &bytes[n..],
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+macro_rules! delegate_read {
+    ($ty:ident) => {
+        impl AsyncRead for $ty {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut ReadBuf<'_>,
+            ) -> Poll<io::Result<()>> {
+                match self.get_mut() {
+                    $ty::Tcp(s) => Pin::new(s).poll_read(cx, buf),
+                    #[cfg(unix)]
+                    $ty::Unix(s) => Pin::new(s).poll_read(cx, buf),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! delegate_write {
+    ($ty:ident) => {
+        impl AsyncWrite for $ty {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<io::Result<usize>> {
+                match self.get_mut() {
+                    $ty::Tcp(s) => Pin::new(s).poll_write(cx, buf),
+                    #[cfg(unix)]
+                    $ty::Unix(s) => Pin::new(s).poll_write(cx, buf),
+                }
+            }
+
+            fn poll_write_vectored(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                bufs: &[io::IoSlice<'_>],
+            ) -> Poll<io::Result<usize>> {
+                match self.get_mut() {
+                    $ty::Tcp(s) => Pin::new(s).poll_write_vectored(cx, bufs),
+                    #[cfg(unix)]
+                    $ty::Unix(s) => Pin::new(s).poll_write_vectored(cx, bufs),
+                }
+            }
+
+            fn is_write_vectored(&self) -> bool {
+                match self {
+                    $ty::Tcp(s) => s.is_write_vectored(),
+                    #[cfg(unix)]
+                    $ty::Unix(s) => s.is_write_vectored(),
+                }
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                match self.get_mut() {
+                    $ty::Tcp(s) => Pin::new(s).poll_flush(cx),
+                    #[cfg(unix)]
+                    $ty::Unix(s) => Pin::new(s).poll_flush(cx),
+                }
+            }
+
+            fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                match self.get_mut() {
+                    $ty::Tcp(s) => Pin::new(s).poll_shutdown(cx),
+                    #[cfg(unix)]
+                    $ty::Unix(s) => Pin::new(s).poll_shutdown(cx),
+                }
+            }
+        }
+    };
+}
+
+delegate_read!(Stream);
+delegate_write!(Stream);
+delegate_read!(OwnedReadHalf);
+delegate_write!(OwnedWriteHalf);
+
+/// 연결 상대. TCP 는 주소, UDS 는 (얻을 수 있으면) 커널이 보고한 peer credentials.
+///
+/// UDS 연결의 요청은 `Body::ip` 가 `None` 이고, 대신 request extensions 에 `UCred` 가 들어간다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Peer {
+    Tcp(SocketAddr),
+    #[cfg(unix)]
+    Unix(Option<UCred>),
+}
+
+impl Peer {
+    /// TCP 상대 주소. UDS 면 `None`.
+    pub fn addr(&self) -> Option<SocketAddr> {
+        match self {
+            Peer::Tcp(addr) => Some(*addr),
+            #[cfg(unix)]
+            Peer::Unix(_) => None,
+        }
+    }
+
+    /// UDS 상대 프로세스의 uid/gid/pid. TCP 거나 조회에 실패했으면 `None`.
+    #[cfg(unix)]
+    pub fn credentials(&self) -> Option<UCred> {
+        match self {
+            Peer::Unix(cred) => *cred,
+            Peer::Tcp(_) => None,
+        }
+    }
+
+    /// 파싱된 요청에 연결 정보를 붙인다. UDS 면 `UCred`, `Options::trusted_proxies` 가 있으면
+    /// forwarding 헤더로 찾은 `ClientInfo` extension.
+    pub(crate) fn tag<B>(&self, request: &mut http::Request<B>, options: &crate::Options) {
+        #[cfg(unix)]
+        if let Some(cred) = self.credentials() {
+            request.extensions_mut().insert(cred);
+        }
+        if !options.trusted_proxies.is_empty() {
+            let info = crate::forwarded::resolve(
+                request.headers(),
+                self.addr().map(|addr| addr.ip()),
+                &options.trusted_proxies,
+            );
+            request.extensions_mut().insert(info);
+        }
+    }
+}
+
+impl From<SocketAddr> for Peer {
+    fn from(addr: SocketAddr) -> Self {
+        Peer::Tcp(addr)
+    }
+}
+
+impl fmt::Display for Peer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Peer::Tcp(addr) => addr.fmt(f),
+            #[cfg(unix)]
+            Peer::Unix(Some(cred)) => write!(f, "unix(uid={}, pid={:?})", cred.uid(), cred.pid()),
+            #[cfg(unix)]
+            Peer::Unix(None) => f.write_str("unix"),
+        }
+    }
+}
+
+/// `Server` 가 연결을 받는 소켓.
+#[derive(Debug)]
+pub enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixSocketListener),
+}
+
+impl Listener {
+    pub async fn accept(&self) -> io::Result<(Stream, Peer)> {
+        std::future::poll_fn(|cx| self.poll_accept(cx)).await
+    }
+
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<(Stream, Peer)>> {
+        match self {
+            Listener::Tcp(l) => l
+                .poll_accept(cx)
+                .map_ok(|(stream, addr)| (Stream::Tcp(stream), Peer::Tcp(addr))),
+            #[cfg(unix)]
+            Listener::Unix(l) => l.listener.poll_accept(cx).map_ok(|(stream, _)| {
+                let cred = stream.peer_cred().ok();
+                (Stream::Unix(stream), Peer::Unix(cred))
+            }),
+        }
+    }
+
+    /// TCP 면 바인드된 주소. UDS 는 `Unsupported` 에러 (`unix_path` 사용).
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        match self {
+            Listener::Tcp(l) => l.local_addr(),
+            #[cfg(unix)]
+            Listener::Unix(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unix socket listener has no inet address",
+            )),
+        }
+    }
+
+    /// UDS 면 socket 파일 경로.
+    #[cfg(unix)]
+    pub fn unix_path(&self) -> Option<&Path> {
+        match self {
+            Listener::Unix(l) => l.path(),
+            Listener::Tcp(_) => None,
+        }
+    }
+}
+
+impl Listener {
+    /// 미리 열어둔 (권한 분리 bind, 부모 프로세스에서 상속 등) std listener 를 넘겨받는다.
+    /// tokio 런타임 안에서 호출해야 한다.
+    pub fn from_std(listener: std::net::TcpListener) -> io::Result<Listener> {
+        listener.set_nonblocking(true)?;
+        Ok(Listener::Tcp(TcpListener::from_std(listener)?))
+    }
+
+    /// `from_std` 의 UDS 버전. socket 파일은 넘겨준 쪽 소유이므로 drop 시 지우지 않는다.
+    #[cfg(unix)]
+    pub fn from_std_unix(listener: std::os::unix::net::UnixListener) -> io::Result<Listener> {
+        listener.set_nonblocking(true)?;
+        Ok(Listener::Unix(UnixSocketListener::adopt(
+            UnixListener::from_std(listener)?,
+        )))
+    }
+}
+
+/// systemd socket activation (`LISTEN_PID` / `LISTEN_FDS`) 으로 넘겨받은 listener 들.
+/// fd 3 부터 순서대로 TCP / UDS 를 판별해 `Listener` 로 감싼다.
+///
+/// `LISTEN_PID` 가 이 프로세스가 아니거나 변수가 없으면 빈 `Vec`.
+/// 자식 프로세스가 같은 fd 를 다시 가져가지 않도록 환경변수를 지우고 fd 에 `CLOEXEC` 를 건다.
+/// tokio 런타임 안에서, 프로세스당 한 번만 호출해야 한다.
+#[cfg(unix)]
+pub fn listen_fds() -> io::Result<Vec<Listener>> {
+    use std::os::fd::RawFd;
+
+    const SD_LISTEN_FDS_START: RawFd = 3;
+
+    let pid = std::env::var("LISTEN_PID").ok();
+    let fds = std::env::var("LISTEN_FDS").ok();
+    std::env::remove_var("LISTEN_PID");
+    std::env::remove_var("LISTEN_FDS");
+    std::env::remove_var("LISTEN_FDNAMES");
+
+    let (Some(pid), Some(fds)) = (pid, fds) else {
+        return Ok(Vec::new());
+    };
+    if pid.trim().parse::<u32>().ok() != Some(std::process::id()) {
+        return Ok(Vec::new());
+    }
+    let count: RawFd = fds
+        .trim()
+        .parse()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid LISTEN_FDS"))?;
+
+    (SD_LISTEN_FDS_START..SD_LISTEN_FDS_START + count)
+        .map(|fd| listener_from_fd(inherited_fd(fd)?))
+        .collect()
+}
+
+/// systemd 가 넘겨준 fd 를 dup (CLOEXEC) 하고 원본은 닫는다.
+/// 열려 있지 않은 fd 면 (`LISTEN_FDS` 가 틀렸거나 오래된 값) 감싸지 않고 `InvalidInput`.
+#[cfg(unix)]
+fn inherited_fd(fd: std::os::fd::RawFd) -> io::Result<std::os::fd::OwnedFd> {
+    use std::os::fd::{FromRawFd, OwnedFd};
+
+    if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("LISTEN_FDS: fd {} is not open", fd),
+        ));
+    }
+    // 열려 있는 fd 는 socket activation 으로 이 프로세스가 넘겨받은 것
+    let inherited = unsafe { OwnedFd::from_raw_fd(fd) };
+    let fd = inherited.try_clone()?;
+    drop(inherited);
+    Ok(fd)
+}
+
+/// listen 중인 stream socket 인지 확인한 뒤 주소 family 로 TCP / UDS 판별
+/// (TCP 로 `local_addr` 가 실패하면 UDS 로 재시도). UDP socket 이나 이미 연결된 socket
+/// (`Accept=yes`) 은 `InvalidInput`.
+#[cfg(unix)]
+fn listener_from_fd(fd: std::os::fd::OwnedFd) -> io::Result<Listener> {
+    if socket_option(&fd, libc::SO_TYPE)? != libc::SOCK_STREAM
+        || socket_option(&fd, libc::SO_ACCEPTCONN)? == 0
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "inherited fd is not a listening stream socket",
+        ));
+    }
+    let tcp = std::net::TcpListener::from(fd);
+    if tcp.local_addr().is_ok() {
+        return Listener::from_std(tcp);
+    }
+    let unix = std::os::unix::net::UnixListener::from(std::os::fd::OwnedFd::from(tcp));
+    unix.local_addr()?;
+    Listener::from_std_unix(unix)
+}
+
+/// `getsockopt(SOL_SOCKET, option)` 의 int 값.
+#[cfg(unix)]
+fn socket_option(fd: &std::os::fd::OwnedFd, option: libc::c_int) -> io::Result<libc::c_int> {
+    use std::os::fd::AsRawFd;
+
+    let mut value: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    let ret = unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            option,
+            &mut value as *mut libc::c_int as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if ret == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(value)
+}
+
+impl From<TcpListener> for Listener {
+    fn from(listener: TcpListener) -> Self {
+        Listener::Tcp(listener)
+    }
+}
+
+/// `Server` 안에서 listener 를 가리키는 번호. `Server::new` 등으로 바인드한 기본 listener 가
+/// `PRIMARY` 이고, `Server::add_listener*` 로 추가한 순서대로 1, 2, ... 가 붙는다.
+/// `Accept::listener` / `Connection::listener()` 로 연결이 들어온 listener 를 구분하며,
+/// `Server::serve*` 는 요청 extensions 에도 넣어준다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ListenerId(pub(crate) usize);
+
+impl ListenerId {
+    pub const PRIMARY: ListenerId = ListenerId(0);
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for ListenerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "listener#{}", self.0)
+    }
+}
+
+/// 여러 listener 중 먼저 연결이 들어온 곳에서 accept. 매번 `start` 번째부터 돌아가며
+/// poll 해서 바쁜 listener 하나가 나머지를 굶기지 않게 한다. cancel-safe.
+pub(crate) struct AcceptSet<'a> {
+    listeners: &'a [Listener],
+    start: usize,
+}
+
+impl<'a> AcceptSet<'a> {
+    pub(crate) fn new(listeners: &'a [Listener], start: usize) -> Self {
+        Self { listeners, start }
+    }
+
+    pub(crate) async fn accept(&self) -> io::Result<(Stream, Peer, ListenerId)> {
+        std::future::poll_fn(|cx| {
+            let len = self.listeners.len();
+            for i in 0..len {
+                let index = (self.start + i) % len;
+                if let Poll::Ready(accepted) = self.listeners[index].poll_accept(cx) {
+                    return Poll::Ready(
+                        accepted.map(|(stream, peer)| (stream, peer, ListenerId(index))),
+                    );
+                }
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+/// `UnixListener` + socket 파일 경로. 직접 `bind` 한 경우에만 파일을 소유하며 drop 시 지운다
+/// (`Listener::from_std_unix` / socket activation 으로 넘겨받은 socket 파일은 건드리지 않음).
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct UnixSocketListener {
+    listener: UnixListener,
+    path: Option<PathBuf>,
+    remove_on_drop: bool,
+}
+
+#[cfg(unix)]
+impl UnixSocketListener {
+    /// `path` 에 바인드. 이전 프로세스가 남긴 (아무도 listen 하지 않는) socket 파일은 지우고,
+    /// 살아있는 서버가 쓰는 중이거나 socket 이 아닌 파일이면 에러.
+    pub fn bind(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        remove_stale_socket(path)?;
+        let listener = UnixListener::bind(path)?;
+        Ok(Self {
+            listener,
+            path: Some(path.to_path_buf()),
+            remove_on_drop: true,
+        })
+    }
+
+    /// 이미 열린 listener 를 넘겨받는다. socket 파일은 소유하지 않는다.
+    fn adopt(listener: UnixListener) -> Self {
+        let path = listener
+            .local_addr()
+            .ok()
+            .and_then(|addr| addr.as_pathname().map(Path::to_path_buf));
+        Self {
+            listener,
+            path,
+            remove_on_drop: false,
+        }
+    }
+
+    /// socket 파일 경로. 이름 없는 (abstract 등) socket 이면 `None`.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+}
+
+#[cfg(unix)]
+impl Drop for UnixSocketListener {
+    fn drop(&mut self) {
+        if let (true, Some(path)) = (self.remove_on_drop, &self.path) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let meta = match std::fs::symlink_metadata(path) {
+        Ok(m) => m,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    if !meta.file_type().is_socket() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not a socket", path.display()),
+        ));
+    }
+    match std::os::unix::net::UnixStream::connect(path) {
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("{} is in use by another server", path.display()),
+        )),
+        Err(_) => {
+            crate::dev_print!("removing stale unix socket {}", path.display());
+            std::fs::remove_file(path)
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn temp_socket_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("atomic_http_{}_{}.sock", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn bind_replaces_stale_socket_but_not_live_one() {
+        let path = temp_socket_path("stale");
+        let _ = std::fs::remove_file(&path);
+
+        // 아무도 listen 하지 않는 socket 파일 (비정상 종료 흉내)
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let live = UnixSocketListener::bind(&path).unwrap();
+        let err = UnixSocketListener::bind(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+
+        drop(live);
+        assert!(!path.exists(), "socket file removed on drop");
+    }
+
+    #[test]
+    fn bind_refuses_to_remove_regular_file() {
+        let path = temp_socket_path("regular");
+        std::fs::write(&path, b"data").unwrap();
+        let err = remove_stale_socket(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn adopted_listeners_are_detected_by_family_and_keep_socket_file() {
+        use std::os::fd::OwnedFd;
+
+        let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = tcp.local_addr().unwrap();
+        let listener = listener_from_fd(OwnedFd::from(tcp)).unwrap();
+        assert!(matches!(listener, Listener::Tcp(_)));
+        assert_eq!(listener.local_addr().unwrap(), addr);
+
+        let path = temp_socket_path("adopted");
+        let _ = std::fs::remove_file(&path);
+        let unix = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        let listener = listener_from_fd(OwnedFd::from(unix)).unwrap();
+        assert_eq!(listener.unix_path(), Some(path.as_path()));
+
+        let client = UnixStream::connect(&path).await.unwrap();
+        let (_, peer) = listener.accept().await.unwrap();
+        assert_eq!(
+            peer.credentials().map(|c| c.uid()),
+            client.peer_cred().ok().map(|c| c.uid())
+        );
+
+        // 넘겨받은 socket 파일은 소유하지 않음
+        drop(listener);
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn inherited_fds_must_be_listening_stream_sockets() {
+        use std::os::fd::OwnedFd;
+
+        let err = inherited_fd(std::os::fd::RawFd::MAX).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let err = listener_from_fd(OwnedFd::from(udp)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        // Accept=yes 로 넘겨받는 연결된 socket
+        let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let connected = std::net::TcpStream::connect(tcp.local_addr().unwrap()).unwrap();
+        let err = listener_from_fd(OwnedFd::from(connected)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let file = std::fs::File::open("/dev/null").unwrap();
+        assert!(listener_from_fd(OwnedFd::from(file)).is_err());
+    }
+
+    #[test]
+    fn listen_fds_ignores_sockets_meant_for_another_process() {
+        std::env::set_var("LISTEN_PID", (std::process::id() + 1).to_string());
+        std::env::set_var("LISTEN_FDS", "1");
+        assert!(listen_fds().unwrap().is_empty());
+        assert!(
+            std::env::var("LISTEN_FDS").is_err(),
+            "variables are consumed"
+        );
+    }
+
+    #[tokio::test]
+    async fn server_over_unix_socket_exposes_peer_credentials() {
+        use crate::{Body, Handler, SendableError, Server, Writer};
+        use http::{Request, Response, StatusCode};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        struct Whoami;
+
+        #[async_trait::async_trait]
+        impl Handler for Whoami {
+            async fn handle(
+                &self,
+                request: Request<Body>,
+                response: &mut Response<Writer>,
+            ) -> Result<(), SendableError> {
+                let cred = request
+                    .extensions()
+                    .get::<UCred>()
+                    .ok_or("no credentials")?;
+                assert!(request.body().ip().is_none());
+                response.body_mut().body = format!("uid={}", cred.uid()).into();
+                *response.status_mut() = StatusCode::OK;
+                Ok(())
+            }
+        }
+
+        let path = temp_socket_path("serve");
+        let server = Server::bind_unix(&path).await.unwrap();
+        let shutdown = server.shutdown_handle();
+        let serving = tokio::spawn(server.serve(Whoami));
+
+        let mut client = UnixStream::connect(&path).await.unwrap();
+        client
+            .write_all(
+                b"GET / HTTP/1.1\r\nHost: x\r\n\r\n\
+                  GET / HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut reply = Vec::new();
+        client.read_to_end(&mut reply).await.unwrap();
+        let reply = String::from_utf8_lossy(&reply);
+        let uid = format!("uid={}", client.peer_cred().unwrap().uid());
+        assert_eq!(reply.matches("HTTP/1.1 200").count(), 2, "{}", reply);
+        assert_eq!(reply.matches(uid.as_str()).count(), 2, "{}", reply);
+
+        shutdown.trigger();
+        serving.await.unwrap();
+        assert!(!path.exists(), "socket file removed after shutdown");
+    }
+}
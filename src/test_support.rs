@@ -0,0 +1,129 @@
+//! 모듈 테스트가 같이 쓰는 도우미 — 연결된 소켓 쌍, 응답 읽기, 파일 응답용 임시 디렉터리.
+
+use std::sync::Arc;
+
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+
+#[cfg(feature = "response_file")]
+use crate::connection::Connection;
+use crate::Options;
+
+pub(crate) async fn socket_pair() -> (TcpStream, TcpStream) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (client_res, server_res) = tokio::join!(TcpStream::connect(addr), listener.accept());
+    (client_res.unwrap(), server_res.unwrap().0)
+}
+
+/// 응답 하나 (헤더 + content-length 만큼 body) 를 읽어 문자열로 반환.
+pub(crate) async fn read_response(client: &mut TcpStream) -> String {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    loop {
+        let n = client.read(&mut chunk).await.unwrap();
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        let text = String::from_utf8_lossy(&buf).to_string();
+        if let Some(end) = text.find("\r\n\r\n") {
+            let cl = text[..end]
+                .lines()
+                .find_map(|l| {
+                    l.to_ascii_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|v| v.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if buf.len() >= end + 4 + cl {
+                return text;
+            }
+        }
+    }
+    String::from_utf8_lossy(&buf).to_string()
+}
+
+/// 헤더 (소문자) 와 content-length 만큼의 body 를 읽는다.
+#[cfg(any(feature = "compression", feature = "response_file"))]
+pub(crate) async fn read_raw(client: &mut TcpStream) -> (String, Vec<u8>) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 64 * 1024];
+    let mut head_end = None;
+    loop {
+        let n = client.read(&mut chunk).await.unwrap();
+        assert!(n > 0, "connection closed early");
+        buf.extend_from_slice(&chunk[..n]);
+        if head_end.is_none() {
+            head_end = buf.windows(4).position(|w| w == b"\r\n\r\n");
+        }
+        if let Some(end) = head_end {
+            let head = String::from_utf8_lossy(&buf[..end]).to_ascii_lowercase();
+            let cl = head
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length:"))
+                .map(|v| v.trim().parse::<usize>().unwrap())
+                .unwrap_or(0);
+            if buf.len() >= end + 4 + cl {
+                return (head, buf[end + 4..end + 4 + cl].to_vec());
+            }
+        }
+    }
+}
+
+pub(crate) fn test_options() -> Arc<Options> {
+    let mut options = Options::new();
+    options.read_timeout_milliseconds = 500;
+    options.read_max_retry = 2;
+    Arc::new(options)
+}
+
+/// 테스트마다 다른 임시 디렉터리 (프로세스 id + 카운터). drop 하면 지운다.
+#[cfg(feature = "response_file")]
+pub(crate) struct TempDir(std::path::PathBuf);
+
+#[cfg(feature = "response_file")]
+impl TempDir {
+    pub(crate) fn new(name: &str) -> Self {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "atomic_http_test_{}_{}_{}",
+            name,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub(crate) fn path(&self) -> &std::path::Path {
+        &self.0
+    }
+
+    pub(crate) fn write(&self, name: &str, content: impl AsRef<[u8]>) {
+        std::fs::write(self.0.join(name), content).unwrap();
+    }
+}
+
+#[cfg(feature = "response_file")]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.0).ok();
+    }
+}
+
+/// `dir` 을 `root_path` 로 하는 서버 쪽 연결과 그 클라이언트. `configure` 로 옵션을 더 바꾼다.
+#[cfg(feature = "response_file")]
+pub(crate) async fn file_connection(
+    dir: &TempDir,
+    configure: impl FnOnce(&mut Options),
+) -> (TcpStream, Connection) {
+    let (client, server) = socket_pair().await;
+    let peer = client.local_addr().unwrap();
+    let mut options = Options::new();
+    options.read_timeout_milliseconds = 500;
+    options.root_path = dir.path().to_path_buf();
+    configure(&mut options);
+    (client, Connection::new(server, Arc::new(options), peer))
+}
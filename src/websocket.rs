@@ -1,22 +1,21 @@
-use std::net::SocketAddr;
 use std::sync::Arc;
 
 use http::{Request, Response};
 use tokio::io::AsyncWriteExt;
-use tokio::net::TcpStream;
 use tokio_tungstenite::tungstenite::protocol::Role;
 use tokio_tungstenite::WebSocketStream;
 
 use crate::helpers::traits::http_stream::{
-    find_header_end_optimized, get_bytes_from_reader, get_parse_result_from_request, get_request,
-    read_headers_only, HeaderReadResult,
+    fail, get_bytes_from_reader, get_parse_result_from_request, get_request, read_headers_only,
+    FullRequest, HeaderReadResult, HeaderSlots,
 };
-use crate::{Body, Options, SendableError, Writer};
+use crate::socket::{Peer, Stream};
+use crate::{Body, ConnectionGuard, Options, SendableError, Writer};
 
 #[cfg(feature = "arena")]
 use crate::helpers::traits::http_stream::{
     get_bytes_arena_direct, get_parse_result_arena_writer, parse_http_request_arena,
-    read_remaining_body,
+    read_full_request,
 };
 #[cfg(feature = "arena")]
 use crate::{ArenaBody, ArenaWriter};
@@ -24,11 +23,12 @@ use crate::{ArenaBody, ArenaWriter};
 /// Result of a WebSocket upgrade attempt.
 pub enum StreamResult {
     /// Regular HTTP request — parsed into standard Request/Response.
-    Http(Request<Body>, Response<Writer>),
+    /// The response is boxed so both variants are about the same size.
+    Http(Request<Body>, Box<Response<Writer>>),
     /// WebSocket upgrade completed — stream is ready for WebSocket frames.
     /// The `Request<()>` contains the original upgrade request metadata
     /// (URI, method, headers) for routing and authentication.
-    WebSocket(WebSocketStream<TcpStream>, Request<()>, SocketAddr),
+    WebSocket(WebSocketStream<Stream>, Request<()>, Peer),
 }
 
 /// Result of a WebSocket upgrade attempt (arena variant).
@@ -39,7 +39,7 @@ pub enum StreamResultArena {
     /// WebSocket upgrade completed — stream is ready for WebSocket frames.
     /// The `Request<()>` contains the original upgrade request metadata
     /// (URI, method, headers) for routing and authentication.
-    WebSocket(WebSocketStream<TcpStream>, Request<()>, SocketAddr),
+    WebSocket(WebSocketStream<Stream>, Request<()>, Peer),
 }
 
 /// Result of a `stream_parse_auto` — 3-way branch:
@@ -48,7 +48,7 @@ pub enum StreamResultArena {
 /// arena feature 비활성화 시 `HttpArena` variant 는 컴파일 안 됨.
 pub enum StreamResultAuto {
     /// WebSocket upgrade completed.
-    WebSocket(WebSocketStream<TcpStream>, Request<()>, SocketAddr),
+    WebSocket(WebSocketStream<Stream>, Request<()>, Peer),
     /// HTTP, Content-Length ≤ arena_cap — arena zero-copy parsing.
     #[cfg(feature = "arena")]
     HttpArena(Request<ArenaBody>, Response<ArenaWriter>),
@@ -56,13 +56,53 @@ pub enum StreamResultAuto {
     HttpStreaming(Request<Body>, Response<Writer>),
 }
 
+impl StreamResult {
+    /// Hand the connection's shutdown guard to whoever owns the stream next: the `Writer`
+    /// for plain HTTP, the upgrade request's extensions for WebSocket.
+    pub(crate) fn set_guard(&mut self, guard: Option<ConnectionGuard>) {
+        match self {
+            StreamResult::Http(_, response) => response.body_mut().guard = guard,
+            StreamResult::WebSocket(_, request, _) => insert_guard(request, guard),
+        }
+    }
+}
+
+#[cfg(feature = "arena")]
+impl StreamResultArena {
+    pub(crate) fn set_guard(&mut self, guard: Option<ConnectionGuard>) {
+        match self {
+            StreamResultArena::Http(_, response) => response.body_mut().guard = guard,
+            StreamResultArena::WebSocket(_, request, _) => insert_guard(request, guard),
+        }
+    }
+}
+
+impl StreamResultAuto {
+    pub(crate) fn set_guard(&mut self, guard: Option<ConnectionGuard>) {
+        match self {
+            StreamResultAuto::WebSocket(_, request, _) => insert_guard(request, guard),
+            #[cfg(feature = "arena")]
+            StreamResultAuto::HttpArena(_, response) => response.body_mut().guard = guard,
+            StreamResultAuto::HttpStreaming(_, response) => response.body_mut().guard = guard,
+        }
+    }
+}
+
+/// WebSocket handlers keep the upgrade request around for routing; the guard rides along in
+/// its extensions so `Server::shutdown` counts the socket until the request is dropped.
+/// Long-lived loops should `select!` on `ConnectionGuard::triggered()` and close the stream.
+fn insert_guard(request: &mut Request<()>, guard: Option<ConnectionGuard>) {
+    if let Some(guard) = guard {
+        request.extensions_mut().insert(guard);
+    }
+}
+
 /// Parse the HTTP request line and headers from raw bytes via `httparse`.
 /// If this is a WebSocket upgrade request, returns `Some((websocket_key, Request<()>))`.
 /// Otherwise returns `None`.
-fn parse_upgrade_request(header_bytes: &[u8]) -> Option<(String, Request<()>)> {
-    const MAX_HEADERS: usize = 64;
-    let mut headers_buf = [httparse::EMPTY_HEADER; MAX_HEADERS];
-    let mut req = httparse::Request::new(&mut headers_buf);
+fn parse_upgrade_request(header_bytes: &[u8], max_headers: usize) -> Option<(String, Request<()>)> {
+    let mut headers_buf = HeaderSlots::new(max_headers);
+    let mut req = httparse::Request::new(headers_buf.as_mut());
     // Partial이어도 best-effort 진행 (이미 \r\n\r\n까지 잘려 들어옴)
     let _ = req.parse(header_bytes).ok()?;
 
@@ -145,9 +185,9 @@ fn compute_accept_key(client_key: &str) -> String {
 
 /// Send the 101 Switching Protocols response and create a `WebSocketStream`.
 async fn perform_upgrade(
-    mut stream: TcpStream,
+    mut stream: Stream,
     client_key: &str,
-) -> Result<WebSocketStream<TcpStream>, SendableError> {
+) -> Result<WebSocketStream<Stream>, SendableError> {
     let accept_key = compute_accept_key(client_key);
 
     let response = format!(
@@ -170,93 +210,112 @@ async fn perform_upgrade(
 /// along with the original `Request<()>` for routing.
 /// Otherwise, parses the request normally and returns `Http`.
 pub(crate) async fn try_upgrade(
-    stream: TcpStream,
+    stream: Stream,
     options: Arc<Options>,
-    peer: SocketAddr,
+    mut peer: Peer,
 ) -> Result<StreamResult, SendableError> {
-    let (bytes, stream) = get_bytes_from_reader(stream, &options).await?;
-
-    // Find header boundary (\r\n\r\n)
-    let header_end = find_header_end_optimized(&bytes)
-        .map(|p| p + 4)
-        .unwrap_or(bytes.len());
+    let FullRequest {
+        bytes,
+        header_end,
+        trailers,
+        stream,
+        ..
+    } = get_bytes_from_reader(stream, &options, &mut peer).await?;
 
     // Check for WebSocket upgrade
-    if let Some((client_key, request)) = parse_upgrade_request(&bytes[..header_end]) {
+    if let Some((client_key, mut request)) =
+        parse_upgrade_request(&bytes[..header_end], options.max_headers)
+    {
+        peer.tag(&mut request, &options);
         let ws_stream = perform_upgrade(stream, &client_key).await?;
         Ok(StreamResult::WebSocket(ws_stream, request, peer))
     } else {
-        let request = get_request(bytes).await?;
+        let mut request = match get_request(bytes, options.max_headers).await {
+            Ok(request) => request,
+            Err(e) => return Err(fail(stream, &options, e).await),
+        };
+        request.body_mut().trailers = trailers.map(Box::new);
         let (req, res) = get_parse_result_from_request(request, stream, options, peer)?;
-        Ok(StreamResult::Http(req, res))
+        Ok(StreamResult::Http(req, Box::new(res)))
     }
 }
 
 /// `stream_parse_auto` 의 내부 구현 — 헤더만 먼저 읽어 WebSocket 판정 후
 /// HTTP인 경우 Content-Length 보고 arena/streaming 으로 분기한다.
 pub(crate) async fn try_upgrade_auto(
-    stream: TcpStream,
+    stream: Stream,
     options: Arc<Options>,
-    peer: SocketAddr,
+    mut peer: Peer,
     arena_cap: usize,
 ) -> Result<StreamResultAuto, SendableError> {
     // 1) 헤더만 읽기 (WebSocket이든 HTTP든 헤더는 동일하게 필요)
-    let HeaderReadResult {
-        header_bytes,
-        leftover,
-        content_length,
-        stream,
-    } = read_headers_only(stream, &options).await?;
+    let head = read_headers_only(stream, &options, &mut peer).await?;
 
     // 2) WebSocket upgrade 판정 — 헤더의 Upgrade/Connection 헤더만 보고 결정
-    if let Some((client_key, request)) = parse_upgrade_request(&header_bytes) {
+    if let Some((client_key, mut request)) =
+        parse_upgrade_request(&head.header_bytes, options.max_headers)
+    {
         // WebSocket 핸드셰이크. leftover/body 는 무시 (WS 클라이언트는 upgrade 전 body 안 보냄).
-        let ws_stream = perform_upgrade(stream, &client_key).await?;
+        peer.tag(&mut request, &options);
+        let ws_stream = perform_upgrade(head.stream, &client_key).await?;
         return Ok(StreamResultAuto::WebSocket(ws_stream, request, peer));
     }
 
     // 3) HTTP arena 경로 (CL ≤ arena_cap, arena feature 활성)
     #[cfg(feature = "arena")]
-    if let Some(cl) = content_length {
+    if let Some(cl) = head.framing.content_length {
         if cl <= arena_cap {
-            let (full_body, stream2) =
-                read_remaining_body(leftover, stream, content_length, &options).await?;
-            let header_end = header_bytes.len();
-            let mut full = Vec::with_capacity(header_end + full_body.len());
-            full.extend_from_slice(&header_bytes);
-            full.extend_from_slice(&full_body);
-            let arena_body = ArenaBody::new(&full, header_end, header_end);
-            let request = parse_http_request_arena(arena_body)?;
+            let FullRequest {
+                bytes,
+                header_end,
+                stream: stream2,
+                ..
+            } = read_full_request(head, &options).await?;
+            let arena_body = ArenaBody::new(&bytes, header_end, header_end);
+            let request = match parse_http_request_arena(arena_body, options.max_headers) {
+                Ok(request) => request,
+                Err(e) => return Err(fail(stream2, &options, e).await),
+            };
             let (req, res) = get_parse_result_arena_writer(request, stream2, options, peer)?;
             return Ok(StreamResultAuto::HttpArena(req, res));
         }
     }
     let _ = arena_cap; // arena feature 없을 때 unused 방지
+    let HeaderReadResult {
+        header_bytes,
+        leftover,
+        framing,
+        stream,
+    } = head;
 
     // 4) HTTP streaming 경로 (CL > arena_cap or 미상)
-    let request_buffered = get_request(header_bytes).await?;
+    let request_buffered = match get_request(header_bytes, options.max_headers).await {
+        Ok(request) => request,
+        Err(e) => return Err(fail(stream, &options, e).await),
+    };
     let (parts, _empty_body) = request_buffered.into_parts();
     let (read_half, write_half) = stream.into_split();
     let body = Body::new_streaming(
         leftover,
         read_half,
-        content_length,
-        Some(peer),
+        framing,
+        peer.addr(),
         options.max_body_size,
+        options.max_trailer_size,
     );
-    let request = Request::from_parts(parts, body);
+    let mut request = Request::from_parts(parts, body);
+    peer.tag(&mut request, &options);
     let version = request.version();
+    let writer = Writer::new(write_half, options);
+    #[cfg(feature = "compression")]
+    let writer = writer.with_accept_encoding(request.headers());
+    #[cfg(feature = "response_file")]
+    let writer = writer.with_file_request(request.method(), request.headers());
     let response = Response::builder()
         .version(version)
         .header(http::header::CONTENT_TYPE, "application/json")
         .status(400)
-        .body(Writer {
-            stream: write_half,
-            body: String::new(),
-            bytes: vec![],
-            use_file: false,
-            options,
-        })?;
+        .body(writer)?;
     Ok(StreamResultAuto::HttpStreaming(request, response))
 }
 
@@ -266,19 +325,25 @@ pub(crate) async fn try_upgrade_auto(
 /// `ArenaBody` / `ArenaWriter` for zero-copy request parsing.
 #[cfg(feature = "arena")]
 pub(crate) async fn try_upgrade_arena(
-    stream: TcpStream,
+    stream: Stream,
     options: Arc<Options>,
-    peer: SocketAddr,
+    mut peer: Peer,
 ) -> Result<StreamResultArena, SendableError> {
-    let (arena_body, stream) = get_bytes_arena_direct(stream, &options).await?;
+    let (arena_body, stream) = get_bytes_arena_direct(stream, &options, &mut peer).await?;
 
     // Check headers via ArenaBody
-    if let Some((client_key, request)) = parse_upgrade_request(arena_body.get_headers()) {
+    if let Some((client_key, mut request)) =
+        parse_upgrade_request(arena_body.get_headers(), options.max_headers)
+    {
         drop(arena_body);
+        peer.tag(&mut request, &options);
         let ws_stream = perform_upgrade(stream, &client_key).await?;
         Ok(StreamResultArena::WebSocket(ws_stream, request, peer))
     } else {
-        let request = parse_http_request_arena(arena_body)?;
+        let request = match parse_http_request_arena(arena_body, options.max_headers) {
+            Ok(request) => request,
+            Err(e) => return Err(fail(stream, &options, e).await),
+        };
         let (req, res) = get_parse_result_arena_writer(request, stream, options, peer)?;
         Ok(StreamResultArena::Http(req, res))
     }